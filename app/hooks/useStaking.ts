@@ -205,7 +205,7 @@ const useStaking = () => {
             externalMetadata: { attributes },
           } = NFT
 
-          let tripEffectAttributeValue = attributes.find((attribute) => {
+          const tripEffectAttributeValue = attributes.find((attribute) => {
             return attribute.trait_type === "Trip Effect"
           })?.value
 
@@ -228,41 +228,13 @@ const useStaking = () => {
             throw new Error("Can't stake. NFT is NOT Tripped Out.")
           }
 
-          if (isParticle) {
-            // Workaround to give bonus emission to "Space" background attribute
-            const isSpace = attributes.find((attribute) => {
-              return (
-                attribute.trait_type === "Background" &&
-                attribute.value === "Space"
-              )
-            })
-
-            if (isSpace) {
-              tripEffectAttributeValue = "Space"
-            }
-          }
-
           const { ix } = await stakingClient.createStakeInstruction({
             farm,
             mint,
             lock: lock.address,
             owner: publicKey,
-            /**
-             * export type TripEffect =
-              | "None"
-              | "Groovy"
-              | "Geometric"
-              | "Drip"
-              | "Interdimensional"
-              | "2x"
-              | "3x"
-              | "4x"
-              | "Nirvana";
-             */
-            args: {
-              amount: new BN(1),
-              tripEffect: tripEffectAttributeValue || "None",
-            },
+            // Levels are read from the farm's gem level table.
+            args: { amount: new BN(1), merkleProof: null },
           })
 
           return ix
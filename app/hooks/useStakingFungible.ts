@@ -61,7 +61,7 @@ export const useStakingFungible = () => {
       })
 
       const locks = await findFarmLocks(connection, farm)
-      const lock = locks.find((lock) => lock.bonusFactor === 0)
+      const lock = locks.find((lock) => lock.bonusBps === 0)
 
       const stakingClient = StakingProgram(connection)
 
@@ -72,7 +72,7 @@ export const useStakingFungible = () => {
         mint: rewardMint,
         lock: lock.address,
         owner: publicKey,
-        args: { amount: new BN(amount * 1000000000), tripEffect: "None" },
+        args: { amount: new BN(amount * 1000000000), merkleProof: null },
       })
 
       const tx = new Transaction()
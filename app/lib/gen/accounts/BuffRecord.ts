@@ -0,0 +1,135 @@
+import { PublicKey, Connection } from "@solana/web3.js"
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface BuffRecordFields {
+  farmer: PublicKey
+  buffMint: PublicKey
+  receipts: Array<PublicKey>
+  factor: BN
+  buffedAt: BN
+  expiresAt: BN | null
+  consumed: boolean
+}
+
+export interface BuffRecordJSON {
+  farmer: string
+  buffMint: string
+  receipts: Array<string>
+  factor: string
+  buffedAt: string
+  expiresAt: string | null
+  consumed: boolean
+}
+
+export class BuffRecord {
+  readonly farmer: PublicKey
+  readonly buffMint: PublicKey
+  readonly receipts: Array<PublicKey>
+  readonly factor: BN
+  readonly buffedAt: BN
+  readonly expiresAt: BN | null
+  readonly consumed: boolean
+
+  static readonly discriminator = Buffer.from([
+    208, 162, 170, 117, 83, 243, 64, 175,
+  ])
+
+  static readonly layout = borsh.struct([
+    borsh.publicKey("farmer"),
+    borsh.publicKey("buffMint"),
+    borsh.vec(borsh.publicKey(), "receipts"),
+    borsh.u128("factor"),
+    borsh.u64("buffedAt"),
+    borsh.option(borsh.u64(), "expiresAt"),
+    borsh.bool("consumed"),
+  ])
+
+  constructor(fields: BuffRecordFields) {
+    this.farmer = fields.farmer
+    this.buffMint = fields.buffMint
+    this.receipts = fields.receipts
+    this.factor = fields.factor
+    this.buffedAt = fields.buffedAt
+    this.expiresAt = fields.expiresAt
+    this.consumed = fields.consumed
+  }
+
+  static async fetch(
+    c: Connection,
+    address: PublicKey
+  ): Promise<BuffRecord | null> {
+    const info = await c.getAccountInfo(address)
+
+    if (info === null) {
+      return null
+    }
+    if (!info.owner.equals(PROGRAM_ID)) {
+      throw new Error("account doesn't belong to this program")
+    }
+
+    return this.decode(info.data)
+  }
+
+  static async fetchMultiple(
+    c: Connection,
+    addresses: PublicKey[]
+  ): Promise<Array<BuffRecord | null>> {
+    const infos = await c.getMultipleAccountsInfo(addresses)
+
+    return infos.map((info) => {
+      if (info === null) {
+        return null
+      }
+      if (!info.owner.equals(PROGRAM_ID)) {
+        throw new Error("account doesn't belong to this program")
+      }
+
+      return this.decode(info.data)
+    })
+  }
+
+  static decode(data: Buffer): BuffRecord {
+    if (!data.slice(0, 8).equals(BuffRecord.discriminator)) {
+      throw new Error("invalid account discriminator")
+    }
+
+    const dec = BuffRecord.layout.decode(data.slice(8))
+
+    return new BuffRecord({
+      farmer: dec.farmer,
+      buffMint: dec.buffMint,
+      receipts: dec.receipts,
+      factor: dec.factor,
+      buffedAt: dec.buffedAt,
+      expiresAt: dec.expiresAt,
+      consumed: dec.consumed,
+    })
+  }
+
+  toJSON(): BuffRecordJSON {
+    return {
+      farmer: this.farmer.toString(),
+      buffMint: this.buffMint.toString(),
+      receipts: this.receipts.map((item) => item.toString()),
+      factor: this.factor.toString(),
+      buffedAt: this.buffedAt.toString(),
+      expiresAt: (this.expiresAt && this.expiresAt.toString()) || null,
+      consumed: this.consumed,
+    }
+  }
+
+  static fromJSON(obj: BuffRecordJSON): BuffRecord {
+    return new BuffRecord({
+      farmer: new PublicKey(obj.farmer),
+      buffMint: new PublicKey(obj.buffMint),
+      receipts: obj.receipts.map((item) => new PublicKey(item)),
+      factor: new BN(obj.factor),
+      buffedAt: new BN(obj.buffedAt),
+      expiresAt: (obj.expiresAt && new BN(obj.expiresAt)) || null,
+      consumed: obj.consumed,
+    })
+  }
+}
@@ -6,19 +6,49 @@ import { PROGRAM_ID } from "../programId"
 
 export interface FarmFields {
   authority: PublicKey
-  reward: types.RewardFields
+  pendingAuthority: PublicKey | null
+  creator: PublicKey
+  rewards: Array<types.RewardFields>
+  mode: types.EmissionModeKind
+  custody: types.CustodyKind
+  schedule: types.ScheduleFields
+  emissionSeconds: BN
+  emissionUpdatedAt: BN
+  poolCheckpoint: BN
+  paused: boolean
+  config: types.FarmConfigFields
   bump: Array<number>
 }
 
 export interface FarmJSON {
   authority: string
-  reward: types.RewardJSON
+  pendingAuthority: string | null
+  creator: string
+  rewards: Array<types.RewardJSON>
+  mode: types.EmissionModeJSON
+  custody: types.CustodyJSON
+  schedule: types.ScheduleJSON
+  emissionSeconds: string
+  emissionUpdatedAt: string
+  poolCheckpoint: string
+  paused: boolean
+  config: types.FarmConfigJSON
   bump: Array<number>
 }
 
 export class Farm {
   readonly authority: PublicKey
-  readonly reward: types.Reward
+  readonly pendingAuthority: PublicKey | null
+  readonly creator: PublicKey
+  readonly rewards: Array<types.Reward>
+  readonly mode: types.EmissionModeKind
+  readonly custody: types.CustodyKind
+  readonly schedule: types.Schedule
+  readonly emissionSeconds: BN
+  readonly emissionUpdatedAt: BN
+  readonly poolCheckpoint: BN
+  readonly paused: boolean
+  readonly config: types.FarmConfig
   readonly bump: Array<number>
 
   static readonly discriminator = Buffer.from([
@@ -27,13 +57,33 @@ export class Farm {
 
   static readonly layout = borsh.struct([
     borsh.publicKey("authority"),
-    types.Reward.layout("reward"),
+    borsh.option(borsh.publicKey(), "pendingAuthority"),
+    borsh.publicKey("creator"),
+    borsh.vec(types.Reward.layout(), "rewards"),
+    types.EmissionMode.layout("mode"),
+    types.Custody.layout("custody"),
+    types.Schedule.layout("schedule"),
+    borsh.u64("emissionSeconds"),
+    borsh.u64("emissionUpdatedAt"),
+    borsh.u64("poolCheckpoint"),
+    borsh.bool("paused"),
+    types.FarmConfig.layout("config"),
     borsh.array(borsh.u8(), 1, "bump"),
   ])
 
   constructor(fields: FarmFields) {
     this.authority = fields.authority
-    this.reward = new types.Reward({ ...fields.reward })
+    this.pendingAuthority = fields.pendingAuthority
+    this.creator = fields.creator
+    this.rewards = fields.rewards.map((item) => new types.Reward({ ...item }))
+    this.mode = fields.mode
+    this.custody = fields.custody
+    this.schedule = new types.Schedule({ ...fields.schedule })
+    this.emissionSeconds = fields.emissionSeconds
+    this.emissionUpdatedAt = fields.emissionUpdatedAt
+    this.poolCheckpoint = fields.poolCheckpoint
+    this.paused = fields.paused
+    this.config = new types.FarmConfig({ ...fields.config })
     this.bump = fields.bump
   }
 
@@ -77,7 +127,21 @@ export class Farm {
 
     return new Farm({
       authority: dec.authority,
-      reward: types.Reward.fromDecoded(dec.reward),
+      pendingAuthority: dec.pendingAuthority,
+      creator: dec.creator,
+      rewards: dec.rewards.map(
+        (
+          item: any /* eslint-disable-line @typescript-eslint/no-explicit-any */
+        ) => types.Reward.fromDecoded(item)
+      ),
+      mode: types.EmissionMode.fromDecoded(dec.mode),
+      custody: types.Custody.fromDecoded(dec.custody),
+      schedule: types.Schedule.fromDecoded(dec.schedule),
+      emissionSeconds: dec.emissionSeconds,
+      emissionUpdatedAt: dec.emissionUpdatedAt,
+      poolCheckpoint: dec.poolCheckpoint,
+      paused: dec.paused,
+      config: types.FarmConfig.fromDecoded(dec.config),
       bump: dec.bump,
     })
   }
@@ -85,7 +149,19 @@ export class Farm {
   toJSON(): FarmJSON {
     return {
       authority: this.authority.toString(),
-      reward: this.reward.toJSON(),
+      pendingAuthority:
+        (this.pendingAuthority && this.pendingAuthority.toString()) ||
+        null,
+      creator: this.creator.toString(),
+      rewards: this.rewards.map((item) => item.toJSON()),
+      mode: this.mode.toJSON(),
+      custody: this.custody.toJSON(),
+      schedule: this.schedule.toJSON(),
+      emissionSeconds: this.emissionSeconds.toString(),
+      emissionUpdatedAt: this.emissionUpdatedAt.toString(),
+      poolCheckpoint: this.poolCheckpoint.toString(),
+      paused: this.paused,
+      config: this.config.toJSON(),
       bump: this.bump,
     }
   }
@@ -93,7 +169,19 @@ export class Farm {
   static fromJSON(obj: FarmJSON): Farm {
     return new Farm({
       authority: new PublicKey(obj.authority),
-      reward: types.Reward.fromJSON(obj.reward),
+      pendingAuthority:
+        (obj.pendingAuthority && new PublicKey(obj.pendingAuthority)) ||
+        null,
+      creator: new PublicKey(obj.creator),
+      rewards: obj.rewards.map((item) => types.Reward.fromJSON(item)),
+      mode: types.EmissionMode.fromJSON(obj.mode),
+      custody: types.Custody.fromJSON(obj.custody),
+      schedule: types.Schedule.fromJSON(obj.schedule),
+      emissionSeconds: new BN(obj.emissionSeconds),
+      emissionUpdatedAt: new BN(obj.emissionUpdatedAt),
+      poolCheckpoint: new BN(obj.poolCheckpoint),
+      paused: obj.paused,
+      config: types.FarmConfig.fromJSON(obj.config),
       bump: obj.bump,
     })
   }
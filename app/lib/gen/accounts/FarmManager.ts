@@ -7,16 +7,19 @@ import { PROGRAM_ID } from "../programId"
 export interface FarmManagerFields {
   farm: PublicKey
   authority: PublicKey
+  permissions: number
 }
 
 export interface FarmManagerJSON {
   farm: string
   authority: string
+  permissions: number
 }
 
 export class FarmManager {
   readonly farm: PublicKey
   readonly authority: PublicKey
+  readonly permissions: number
 
   static readonly discriminator = Buffer.from([
     140, 111, 131, 135, 218, 198, 198, 200,
@@ -25,11 +28,13 @@ export class FarmManager {
   static readonly layout = borsh.struct([
     borsh.publicKey("farm"),
     borsh.publicKey("authority"),
+    borsh.u8("permissions"),
   ])
 
   constructor(fields: FarmManagerFields) {
     this.farm = fields.farm
     this.authority = fields.authority
+    this.permissions = fields.permissions
   }
 
   static async fetch(
@@ -76,6 +81,7 @@ export class FarmManager {
     return new FarmManager({
       farm: dec.farm,
       authority: dec.authority,
+      permissions: dec.permissions,
     })
   }
 
@@ -83,6 +89,7 @@ export class FarmManager {
     return {
       farm: this.farm.toString(),
       authority: this.authority.toString(),
+      permissions: this.permissions,
     }
   }
 
@@ -90,6 +97,7 @@ export class FarmManager {
     return new FarmManager({
       farm: new PublicKey(obj.farm),
       authority: new PublicKey(obj.authority),
+      permissions: obj.permissions,
     })
   }
 }
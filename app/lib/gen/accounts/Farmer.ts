@@ -7,27 +7,51 @@ import { PROGRAM_ID } from "../programId"
 export interface FarmerFields {
   farm: PublicKey
   owner: PublicKey
-  accruedRewards: number
-  totalRewardRate: number
-  lastUpdate: BN
+  accruedRewards: Array<BN>
+  totalRewardRates: Array<BN>
+  reservedRewards: Array<BN>
+  rewardDebts: Array<BN>
+  owedRewards: Array<BN>
+  accruedPerRate: Array<BN>
+  earlyExitStakes: number
+  earlyExitUntil: BN
+  emissionCheckpoint: BN
+  buffExpiries: Array<types.BuffExpiryFields>
+  whitelists: Array<types.WhitelistStakesFields>
   bump: Array<number>
 }
 
 export interface FarmerJSON {
   farm: string
   owner: string
-  accruedRewards: number
-  totalRewardRate: number
-  lastUpdate: string
+  accruedRewards: Array<string>
+  totalRewardRates: Array<string>
+  reservedRewards: Array<string>
+  rewardDebts: Array<string>
+  owedRewards: Array<string>
+  accruedPerRate: Array<string>
+  earlyExitStakes: number
+  earlyExitUntil: string
+  emissionCheckpoint: string
+  buffExpiries: Array<types.BuffExpiryJSON>
+  whitelists: Array<types.WhitelistStakesJSON>
   bump: Array<number>
 }
 
 export class Farmer {
   readonly farm: PublicKey
   readonly owner: PublicKey
-  readonly accruedRewards: number
-  readonly totalRewardRate: number
-  readonly lastUpdate: BN
+  readonly accruedRewards: Array<BN>
+  readonly totalRewardRates: Array<BN>
+  readonly reservedRewards: Array<BN>
+  readonly rewardDebts: Array<BN>
+  readonly owedRewards: Array<BN>
+  readonly accruedPerRate: Array<BN>
+  readonly earlyExitStakes: number
+  readonly earlyExitUntil: BN
+  readonly emissionCheckpoint: BN
+  readonly buffExpiries: Array<types.BuffExpiry>
+  readonly whitelists: Array<types.WhitelistStakes>
   readonly bump: Array<number>
 
   static readonly discriminator = Buffer.from([
@@ -37,9 +61,17 @@ export class Farmer {
   static readonly layout = borsh.struct([
     borsh.publicKey("farm"),
     borsh.publicKey("owner"),
-    borsh.f64("accruedRewards"),
-    borsh.f64("totalRewardRate"),
-    borsh.u64("lastUpdate"),
+    borsh.array(borsh.u128(), 3, "accruedRewards"),
+    borsh.array(borsh.u128(), 3, "totalRewardRates"),
+    borsh.array(borsh.u128(), 3, "reservedRewards"),
+    borsh.array(borsh.u128(), 3, "rewardDebts"),
+    borsh.array(borsh.u128(), 3, "owedRewards"),
+    borsh.array(borsh.u128(), 3, "accruedPerRate"),
+    borsh.u32("earlyExitStakes"),
+    borsh.u64("earlyExitUntil"),
+    borsh.u64("emissionCheckpoint"),
+    borsh.vec(types.BuffExpiry.layout(), "buffExpiries"),
+    borsh.vec(types.WhitelistStakes.layout(), "whitelists"),
     borsh.array(borsh.u8(), 1, "bump"),
   ])
 
@@ -47,8 +79,20 @@ export class Farmer {
     this.farm = fields.farm
     this.owner = fields.owner
     this.accruedRewards = fields.accruedRewards
-    this.totalRewardRate = fields.totalRewardRate
-    this.lastUpdate = fields.lastUpdate
+    this.totalRewardRates = fields.totalRewardRates
+    this.reservedRewards = fields.reservedRewards
+    this.rewardDebts = fields.rewardDebts
+    this.owedRewards = fields.owedRewards
+    this.accruedPerRate = fields.accruedPerRate
+    this.earlyExitStakes = fields.earlyExitStakes
+    this.earlyExitUntil = fields.earlyExitUntil
+    this.emissionCheckpoint = fields.emissionCheckpoint
+    this.buffExpiries = fields.buffExpiries.map((item) =>
+      new types.BuffExpiry({ ...item })
+    )
+    this.whitelists = fields.whitelists.map((item) =>
+      new types.WhitelistStakes({ ...item })
+    )
     this.bump = fields.bump
   }
 
@@ -97,8 +141,24 @@ export class Farmer {
       farm: dec.farm,
       owner: dec.owner,
       accruedRewards: dec.accruedRewards,
-      totalRewardRate: dec.totalRewardRate,
-      lastUpdate: dec.lastUpdate,
+      totalRewardRates: dec.totalRewardRates,
+      reservedRewards: dec.reservedRewards,
+      rewardDebts: dec.rewardDebts,
+      owedRewards: dec.owedRewards,
+      accruedPerRate: dec.accruedPerRate,
+      earlyExitStakes: dec.earlyExitStakes,
+      earlyExitUntil: dec.earlyExitUntil,
+      emissionCheckpoint: dec.emissionCheckpoint,
+      buffExpiries: dec.buffExpiries.map(
+        (
+          item: any /* eslint-disable-line @typescript-eslint/no-explicit-any */
+        ) => types.BuffExpiry.fromDecoded(item)
+      ),
+      whitelists: dec.whitelists.map(
+        (
+          item: any /* eslint-disable-line @typescript-eslint/no-explicit-any */
+        ) => types.WhitelistStakes.fromDecoded(item)
+      ),
       bump: dec.bump,
     })
   }
@@ -107,9 +167,17 @@ export class Farmer {
     return {
       farm: this.farm.toString(),
       owner: this.owner.toString(),
-      accruedRewards: this.accruedRewards,
-      totalRewardRate: this.totalRewardRate,
-      lastUpdate: this.lastUpdate.toString(),
+      accruedRewards: this.accruedRewards.map((item) => item.toString()),
+      totalRewardRates: this.totalRewardRates.map((item) => item.toString()),
+      reservedRewards: this.reservedRewards.map((item) => item.toString()),
+      rewardDebts: this.rewardDebts.map((item) => item.toString()),
+      owedRewards: this.owedRewards.map((item) => item.toString()),
+      accruedPerRate: this.accruedPerRate.map((item) => item.toString()),
+      earlyExitStakes: this.earlyExitStakes,
+      earlyExitUntil: this.earlyExitUntil.toString(),
+      emissionCheckpoint: this.emissionCheckpoint.toString(),
+      buffExpiries: this.buffExpiries.map((item) => item.toJSON()),
+      whitelists: this.whitelists.map((item) => item.toJSON()),
       bump: this.bump,
     }
   }
@@ -118,9 +186,21 @@ export class Farmer {
     return new Farmer({
       farm: new PublicKey(obj.farm),
       owner: new PublicKey(obj.owner),
-      accruedRewards: obj.accruedRewards,
-      totalRewardRate: obj.totalRewardRate,
-      lastUpdate: new BN(obj.lastUpdate),
+      accruedRewards: obj.accruedRewards.map((item) => new BN(item)),
+      totalRewardRates: obj.totalRewardRates.map((item) => new BN(item)),
+      reservedRewards: obj.reservedRewards.map((item) => new BN(item)),
+      rewardDebts: obj.rewardDebts.map((item) => new BN(item)),
+      owedRewards: obj.owedRewards.map((item) => new BN(item)),
+      accruedPerRate: obj.accruedPerRate.map((item) => new BN(item)),
+      earlyExitStakes: obj.earlyExitStakes,
+      earlyExitUntil: new BN(obj.earlyExitUntil),
+      emissionCheckpoint: new BN(obj.emissionCheckpoint),
+      buffExpiries: obj.buffExpiries.map((item) =>
+        types.BuffExpiry.fromJSON(item)
+      ),
+      whitelists: obj.whitelists.map((item) =>
+        types.WhitelistStakes.fromJSON(item)
+      ),
       bump: obj.bump,
     })
   }
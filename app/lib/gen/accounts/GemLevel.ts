@@ -0,0 +1,103 @@
+import { PublicKey, Connection } from "@solana/web3.js"
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface GemLevelFields {
+  farm: PublicKey
+  mint: PublicKey
+  level: number
+}
+
+export interface GemLevelJSON {
+  farm: string
+  mint: string
+  level: number
+}
+
+export class GemLevel {
+  readonly farm: PublicKey
+  readonly mint: PublicKey
+  readonly level: number
+
+  static readonly discriminator = Buffer.from([
+    197, 167, 165, 187, 97, 129, 136, 133,
+  ])
+
+  static readonly layout = borsh.struct([
+    borsh.publicKey("farm"),
+    borsh.publicKey("mint"),
+    borsh.u8("level"),
+  ])
+
+  constructor(fields: GemLevelFields) {
+    this.farm = fields.farm
+    this.mint = fields.mint
+    this.level = fields.level
+  }
+
+  static async fetch(
+    c: Connection,
+    address: PublicKey
+  ): Promise<GemLevel | null> {
+    const info = await c.getAccountInfo(address)
+
+    if (info === null) {
+      return null
+    }
+    if (!info.owner.equals(PROGRAM_ID)) {
+      throw new Error("account doesn't belong to this program")
+    }
+
+    return this.decode(info.data)
+  }
+
+  static async fetchMultiple(
+    c: Connection,
+    addresses: PublicKey[]
+  ): Promise<Array<GemLevel | null>> {
+    const infos = await c.getMultipleAccountsInfo(addresses)
+
+    return infos.map((info) => {
+      if (info === null) {
+        return null
+      }
+      if (!info.owner.equals(PROGRAM_ID)) {
+        throw new Error("account doesn't belong to this program")
+      }
+
+      return this.decode(info.data)
+    })
+  }
+
+  static decode(data: Buffer): GemLevel {
+    if (!data.slice(0, 8).equals(GemLevel.discriminator)) {
+      throw new Error("invalid account discriminator")
+    }
+
+    const dec = GemLevel.layout.decode(data.slice(8))
+
+    return new GemLevel({
+      farm: dec.farm,
+      mint: dec.mint,
+      level: dec.level,
+    })
+  }
+
+  toJSON(): GemLevelJSON {
+    return {
+      farm: this.farm.toString(),
+      mint: this.mint.toString(),
+      level: this.level,
+    }
+  }
+
+  static fromJSON(obj: GemLevelJSON): GemLevel {
+    return new GemLevel({
+      farm: new PublicKey(obj.farm),
+      mint: new PublicKey(obj.mint),
+      level: obj.level,
+    })
+  }
+}
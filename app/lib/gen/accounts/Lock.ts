@@ -8,21 +8,24 @@ export interface LockFields {
   farm: PublicKey
   duration: BN
   cooldown: BN
-  bonusFactor: number
+  bonusBps: number
+  earlyUnstake: types.EarlyUnstakeKind
 }
 
 export interface LockJSON {
   farm: string
   duration: string
   cooldown: string
-  bonusFactor: number
+  bonusBps: number
+  earlyUnstake: types.EarlyUnstakeJSON
 }
 
 export class Lock {
   readonly farm: PublicKey
   readonly duration: BN
   readonly cooldown: BN
-  readonly bonusFactor: number
+  readonly bonusBps: number
+  readonly earlyUnstake: types.EarlyUnstakeKind
 
   static readonly discriminator = Buffer.from([
     8, 255, 36, 202, 210, 22, 57, 137,
@@ -32,14 +35,16 @@ export class Lock {
     borsh.publicKey("farm"),
     borsh.u64("duration"),
     borsh.u64("cooldown"),
-    borsh.u8("bonusFactor"),
+    borsh.u16("bonusBps"),
+    types.EarlyUnstake.layout("earlyUnstake"),
   ])
 
   constructor(fields: LockFields) {
     this.farm = fields.farm
     this.duration = fields.duration
     this.cooldown = fields.cooldown
-    this.bonusFactor = fields.bonusFactor
+    this.bonusBps = fields.bonusBps
+    this.earlyUnstake = fields.earlyUnstake
   }
 
   static async fetch(c: Connection, address: PublicKey): Promise<Lock | null> {
@@ -84,7 +89,8 @@ export class Lock {
       farm: dec.farm,
       duration: dec.duration,
       cooldown: dec.cooldown,
-      bonusFactor: dec.bonusFactor,
+      bonusBps: dec.bonusBps,
+      earlyUnstake: types.EarlyUnstake.fromDecoded(dec.earlyUnstake),
     })
   }
 
@@ -93,7 +99,8 @@ export class Lock {
       farm: this.farm.toString(),
       duration: this.duration.toString(),
       cooldown: this.cooldown.toString(),
-      bonusFactor: this.bonusFactor,
+      bonusBps: this.bonusBps,
+      earlyUnstake: this.earlyUnstake.toJSON(),
     }
   }
 
@@ -102,7 +109,8 @@ export class Lock {
       farm: new PublicKey(obj.farm),
       duration: new BN(obj.duration),
       cooldown: new BN(obj.cooldown),
-      bonusFactor: obj.bonusFactor,
+      bonusBps: obj.bonusBps,
+      earlyUnstake: types.EarlyUnstake.fromJSON(obj.earlyUnstake),
     })
   }
 }
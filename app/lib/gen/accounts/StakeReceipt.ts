@@ -8,33 +8,63 @@ export interface StakeReceiptFields {
   farmer: PublicKey
   mint: PublicKey
   lock: PublicKey
+  whitelist: PublicKey
   startTs: BN
   endTs: BN | null
+  reservedUntil: BN
+  reserved: Array<BN>
   amount: BN
-  rewardRate: number
-  buff: types.BuffFields | null
+  topUpAmount: BN
+  topUpTs: BN
+  rewardRates: Array<BN>
+  unbuffedRates: Array<BN>
+  earned: Array<BN>
+  earningsCheckpoint: Array<BN>
+  buffs: Array<types.BuffFields>
+  legacyBonus: boolean
+  delisted: boolean
 }
 
 export interface StakeReceiptJSON {
   farmer: string
   mint: string
   lock: string
+  whitelist: string
   startTs: string
   endTs: string | null
+  reservedUntil: string
+  reserved: Array<string>
   amount: string
-  rewardRate: number
-  buff: types.BuffJSON | null
+  topUpAmount: string
+  topUpTs: string
+  rewardRates: Array<string>
+  unbuffedRates: Array<string>
+  earned: Array<string>
+  earningsCheckpoint: Array<string>
+  buffs: Array<types.BuffJSON>
+  legacyBonus: boolean
+  delisted: boolean
 }
 
 export class StakeReceipt {
   readonly farmer: PublicKey
   readonly mint: PublicKey
   readonly lock: PublicKey
+  readonly whitelist: PublicKey
   readonly startTs: BN
   readonly endTs: BN | null
+  readonly reservedUntil: BN
+  readonly reserved: Array<BN>
   readonly amount: BN
-  readonly rewardRate: number
-  readonly buff: types.Buff | null
+  readonly topUpAmount: BN
+  readonly topUpTs: BN
+  readonly rewardRates: Array<BN>
+  readonly unbuffedRates: Array<BN>
+  readonly earned: Array<BN>
+  readonly earningsCheckpoint: Array<BN>
+  readonly buffs: Array<types.Buff>
+  readonly legacyBonus: boolean
+  readonly delisted: boolean
 
   static readonly discriminator = Buffer.from([
     189, 110, 129, 87, 79, 225, 96, 177,
@@ -44,22 +74,42 @@ export class StakeReceipt {
     borsh.publicKey("farmer"),
     borsh.publicKey("mint"),
     borsh.publicKey("lock"),
+    borsh.publicKey("whitelist"),
     borsh.u64("startTs"),
     borsh.option(borsh.u64(), "endTs"),
+    borsh.u64("reservedUntil"),
+    borsh.array(borsh.u128(), 3, "reserved"),
     borsh.u64("amount"),
-    borsh.f64("rewardRate"),
-    borsh.option(types.Buff.layout(), "buff"),
+    borsh.u64("topUpAmount"),
+    borsh.u64("topUpTs"),
+    borsh.array(borsh.u128(), 3, "rewardRates"),
+    borsh.array(borsh.u128(), 3, "unbuffedRates"),
+    borsh.array(borsh.u128(), 3, "earned"),
+    borsh.array(borsh.u128(), 3, "earningsCheckpoint"),
+    borsh.vec(types.Buff.layout(), "buffs"),
+    borsh.bool("legacyBonus"),
+    borsh.bool("delisted"),
   ])
 
   constructor(fields: StakeReceiptFields) {
     this.farmer = fields.farmer
     this.mint = fields.mint
     this.lock = fields.lock
+    this.whitelist = fields.whitelist
     this.startTs = fields.startTs
     this.endTs = fields.endTs
+    this.reservedUntil = fields.reservedUntil
+    this.reserved = fields.reserved
     this.amount = fields.amount
-    this.rewardRate = fields.rewardRate
-    this.buff = (fields.buff && new types.Buff({ ...fields.buff })) || null
+    this.topUpAmount = fields.topUpAmount
+    this.topUpTs = fields.topUpTs
+    this.rewardRates = fields.rewardRates
+    this.unbuffedRates = fields.unbuffedRates
+    this.earned = fields.earned
+    this.earningsCheckpoint = fields.earningsCheckpoint
+    this.buffs = fields.buffs.map((item) => new types.Buff({ ...item }))
+    this.legacyBonus = fields.legacyBonus
+    this.delisted = fields.delisted
   }
 
   static async fetch(
@@ -107,11 +157,25 @@ export class StakeReceipt {
       farmer: dec.farmer,
       mint: dec.mint,
       lock: dec.lock,
+      whitelist: dec.whitelist,
       startTs: dec.startTs,
       endTs: dec.endTs,
+      reservedUntil: dec.reservedUntil,
+      reserved: dec.reserved,
       amount: dec.amount,
-      rewardRate: dec.rewardRate,
-      buff: (dec.buff && types.Buff.fromDecoded(dec.buff)) || null,
+      topUpAmount: dec.topUpAmount,
+      topUpTs: dec.topUpTs,
+      rewardRates: dec.rewardRates,
+      unbuffedRates: dec.unbuffedRates,
+      earned: dec.earned,
+      earningsCheckpoint: dec.earningsCheckpoint,
+      buffs: dec.buffs.map(
+        (
+          item: any /* eslint-disable-line @typescript-eslint/no-explicit-any */
+        ) => types.Buff.fromDecoded(item)
+      ),
+      legacyBonus: dec.legacyBonus,
+      delisted: dec.delisted,
     })
   }
 
@@ -120,11 +184,23 @@ export class StakeReceipt {
       farmer: this.farmer.toString(),
       mint: this.mint.toString(),
       lock: this.lock.toString(),
+      whitelist: this.whitelist.toString(),
       startTs: this.startTs.toString(),
       endTs: (this.endTs && this.endTs.toString()) || null,
+      reservedUntil: this.reservedUntil.toString(),
+      reserved: this.reserved.map((item) => item.toString()),
       amount: this.amount.toString(),
-      rewardRate: this.rewardRate,
-      buff: (this.buff && this.buff.toJSON()) || null,
+      topUpAmount: this.topUpAmount.toString(),
+      topUpTs: this.topUpTs.toString(),
+      rewardRates: this.rewardRates.map((item) => item.toString()),
+      unbuffedRates: this.unbuffedRates.map((item) => item.toString()),
+      earned: this.earned.map((item) => item.toString()),
+      earningsCheckpoint: this.earningsCheckpoint.map((item) =>
+        item.toString()
+      ),
+      buffs: this.buffs.map((item) => item.toJSON()),
+      legacyBonus: this.legacyBonus,
+      delisted: this.delisted,
     }
   }
 
@@ -133,11 +209,21 @@ export class StakeReceipt {
       farmer: new PublicKey(obj.farmer),
       mint: new PublicKey(obj.mint),
       lock: new PublicKey(obj.lock),
+      whitelist: new PublicKey(obj.whitelist),
       startTs: new BN(obj.startTs),
       endTs: (obj.endTs && new BN(obj.endTs)) || null,
+      reservedUntil: new BN(obj.reservedUntil),
+      reserved: obj.reserved.map((item) => new BN(item)),
       amount: new BN(obj.amount),
-      rewardRate: obj.rewardRate,
-      buff: (obj.buff && types.Buff.fromJSON(obj.buff)) || null,
+      topUpAmount: new BN(obj.topUpAmount),
+      topUpTs: new BN(obj.topUpTs),
+      rewardRates: obj.rewardRates.map((item) => new BN(item)),
+      unbuffedRates: obj.unbuffedRates.map((item) => new BN(item)),
+      earned: obj.earned.map((item) => new BN(item)),
+      earningsCheckpoint: obj.earningsCheckpoint.map((item) => new BN(item)),
+      buffs: obj.buffs.map((item) => types.Buff.fromJSON(item)),
+      legacyBonus: obj.legacyBonus,
+      delisted: obj.delisted,
     })
   }
 }
@@ -7,22 +7,31 @@ import { PROGRAM_ID } from "../programId"
 export interface WhitelistProofFields {
   whitelistedAddress: PublicKey
   farm: PublicKey
-  rewardRate: number
+  rewardRates: Array<BN>
   ty: types.WhitelistTypeKind
+  activeStakes: BN
+  delistedAt: BN | null
+  buffConfig: types.BuffConfigFields
 }
 
 export interface WhitelistProofJSON {
   whitelistedAddress: string
   farm: string
-  rewardRate: number
+  rewardRates: Array<string>
   ty: types.WhitelistTypeJSON
+  activeStakes: string
+  delistedAt: string | null
+  buffConfig: types.BuffConfigJSON
 }
 
 export class WhitelistProof {
   readonly whitelistedAddress: PublicKey
   readonly farm: PublicKey
-  readonly rewardRate: number
+  readonly rewardRates: Array<BN>
   readonly ty: types.WhitelistTypeKind
+  readonly activeStakes: BN
+  readonly delistedAt: BN | null
+  readonly buffConfig: types.BuffConfig
 
   static readonly discriminator = Buffer.from([
     194, 230, 60, 10, 60, 98, 236, 39,
@@ -31,15 +40,21 @@ export class WhitelistProof {
   static readonly layout = borsh.struct([
     borsh.publicKey("whitelistedAddress"),
     borsh.publicKey("farm"),
-    borsh.f64("rewardRate"),
+    borsh.array(borsh.u128(), 3, "rewardRates"),
     types.WhitelistType.layout("ty"),
+    borsh.u64("activeStakes"),
+    borsh.option(borsh.u64(), "delistedAt"),
+    types.BuffConfig.layout("buffConfig"),
   ])
 
   constructor(fields: WhitelistProofFields) {
     this.whitelistedAddress = fields.whitelistedAddress
     this.farm = fields.farm
-    this.rewardRate = fields.rewardRate
+    this.rewardRates = fields.rewardRates
     this.ty = fields.ty
+    this.activeStakes = fields.activeStakes
+    this.delistedAt = fields.delistedAt
+    this.buffConfig = new types.BuffConfig({ ...fields.buffConfig })
   }
 
   static async fetch(
@@ -86,8 +101,11 @@ export class WhitelistProof {
     return new WhitelistProof({
       whitelistedAddress: dec.whitelistedAddress,
       farm: dec.farm,
-      rewardRate: dec.rewardRate,
+      rewardRates: dec.rewardRates,
       ty: types.WhitelistType.fromDecoded(dec.ty),
+      activeStakes: dec.activeStakes,
+      delistedAt: dec.delistedAt,
+      buffConfig: types.BuffConfig.fromDecoded(dec.buffConfig),
     })
   }
 
@@ -95,8 +113,11 @@ export class WhitelistProof {
     return {
       whitelistedAddress: this.whitelistedAddress.toString(),
       farm: this.farm.toString(),
-      rewardRate: this.rewardRate,
+      rewardRates: this.rewardRates.map((item) => item.toString()),
       ty: this.ty.toJSON(),
+      activeStakes: this.activeStakes.toString(),
+      delistedAt: (this.delistedAt && this.delistedAt.toString()) || null,
+      buffConfig: this.buffConfig.toJSON(),
     }
   }
 
@@ -104,8 +125,11 @@ export class WhitelistProof {
     return new WhitelistProof({
       whitelistedAddress: new PublicKey(obj.whitelistedAddress),
       farm: new PublicKey(obj.farm),
-      rewardRate: obj.rewardRate,
+      rewardRates: obj.rewardRates.map((item) => new BN(item)),
       ty: types.WhitelistType.fromJSON(obj.ty),
+      activeStakes: new BN(obj.activeStakes),
+      delistedAt: (obj.delistedAt && new BN(obj.delistedAt)) || null,
+      buffConfig: types.BuffConfig.fromJSON(obj.buffConfig),
     })
   }
 }
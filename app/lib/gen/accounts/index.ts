@@ -1,12 +1,16 @@
+export { Farm } from "./Farm"
+export type { FarmFields, FarmJSON } from "./Farm"
 export { FarmManager } from "./FarmManager"
 export type { FarmManagerFields, FarmManagerJSON } from "./FarmManager"
 export { Farmer } from "./Farmer"
 export type { FarmerFields, FarmerJSON } from "./Farmer"
-export { Farm } from "./Farm"
-export type { FarmFields, FarmJSON } from "./Farm"
+export { GemLevel } from "./GemLevel"
+export type { GemLevelFields, GemLevelJSON } from "./GemLevel"
 export { Lock } from "./Lock"
 export type { LockFields, LockJSON } from "./Lock"
 export { StakeReceipt } from "./StakeReceipt"
 export type { StakeReceiptFields, StakeReceiptJSON } from "./StakeReceipt"
+export { BuffRecord } from "./BuffRecord"
+export type { BuffRecordFields, BuffRecordJSON } from "./BuffRecord"
 export { WhitelistProof } from "./WhitelistProof"
 export type { WhitelistProofFields, WhitelistProofJSON } from "./WhitelistProof"
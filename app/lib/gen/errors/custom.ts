@@ -12,6 +12,42 @@ export type CustomError =
   | FactorMustBeGtZero
   | InvalidTripEffect
   | ArithmeticError
+  | InvalidRewardMint
+  | TooManyRewards
+  | RewardAlreadyAdded
+  | InvalidRewardAccounts
+  | InvalidSchedule
+  | InvalidEmissionMode
+  | FarmPaused
+  | NotPendingAuthority
+  | MissingPermission
+  | InvalidPenalty
+  | EarlyUnstakeDisabled
+  | LockMismatch
+  | InvalidUnstakeAmount
+  | InvalidStakeAccounts
+  | InvalidFreezeAccounts
+  | UnsupportedCustody
+  | InvalidPnftAccounts
+  | InvalidMerkleProof
+  | WhitelistDelisted
+  | WhitelistHasStakes
+  | InvalidDelistTime
+  | InvalidBuffConfig
+  | InvalidBuffCount
+  | TooManyExpiringBuffs
+  | UnconsumableBuff
+  | ExpiringBuffNotStackable
+  | RewardsLocked
+  | InvalidGemLevelAccounts
+  | InvalidRewardRates
+  | TooManyWhitelists
+  | InvalidWhitelistAccounts
+  | DelistedStakes
+  | UnverifiedReceiptFarmer
+  | InvalidStakeAmount
+  | UnsupportedPnft
+  | InvalidPermissions
 
 export class CooldownIsNotOver extends Error {
   static readonly code = 6000
@@ -156,6 +192,432 @@ export class ArithmeticError extends Error {
   }
 }
 
+export class InvalidRewardMint extends Error {
+  static readonly code = 6013
+  readonly code = 6013
+  readonly name = "InvalidRewardMint"
+  readonly msg = "This mint is not one of the farm's rewards."
+
+  constructor(readonly logs?: string[]) {
+    super("6013: This mint is not one of the farm's rewards.")
+  }
+}
+
+export class TooManyRewards extends Error {
+  static readonly code = 6014
+  readonly code = 6014
+  readonly name = "TooManyRewards"
+  readonly msg = "The farm already distributes the maximum number of rewards."
+
+  constructor(readonly logs?: string[]) {
+    super("6014: The farm already distributes the maximum number of rewards.")
+  }
+}
+
+export class RewardAlreadyAdded extends Error {
+  static readonly code = 6015
+  readonly code = 6015
+  readonly name = "RewardAlreadyAdded"
+  readonly msg = "This mint is already one of the farm's rewards."
+
+  constructor(readonly logs?: string[]) {
+    super("6015: This mint is already one of the farm's rewards.")
+  }
+}
+
+export class InvalidRewardAccounts extends Error {
+  static readonly code = 6016
+  readonly code = 6016
+  readonly name = "InvalidRewardAccounts"
+  readonly msg =
+    "Reward accounts must be given as (mint, farm vault, farmer vault) groups."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6016: Reward accounts must be given as (mint, farm vault, farmer vault) groups."
+    )
+  }
+}
+
+export class InvalidSchedule extends Error {
+  static readonly code = 6017
+  readonly code = 6017
+  readonly name = "InvalidSchedule"
+  readonly msg = "Invalid schedule. Running campaigns can only be extended."
+
+  constructor(readonly logs?: string[]) {
+    super("6017: Invalid schedule. Running campaigns can only be extended.")
+  }
+}
+
+export class InvalidEmissionMode extends Error {
+  static readonly code = 6018
+  readonly code = 6018
+  readonly name = "InvalidEmissionMode"
+  readonly msg = "This operation is not supported by the farm's emission mode."
+
+  constructor(readonly logs?: string[]) {
+    super("6018: This operation is not supported by the farm's emission mode.")
+  }
+}
+
+export class FarmPaused extends Error {
+  static readonly code = 6019
+  readonly code = 6019
+  readonly name = "FarmPaused"
+  readonly msg = "The farm is paused."
+
+  constructor(readonly logs?: string[]) {
+    super("6019: The farm is paused.")
+  }
+}
+
+export class NotPendingAuthority extends Error {
+  static readonly code = 6020
+  readonly code = 6020
+  readonly name = "NotPendingAuthority"
+  readonly msg = "Only the proposed authority can accept the farm."
+
+  constructor(readonly logs?: string[]) {
+    super("6020: Only the proposed authority can accept the farm.")
+  }
+}
+
+export class MissingPermission extends Error {
+  static readonly code = 6021
+  readonly code = 6021
+  readonly name = "MissingPermission"
+  readonly msg = "This manager doesn't have the permission to do that."
+
+  constructor(readonly logs?: string[]) {
+    super("6021: This manager doesn't have the permission to do that.")
+  }
+}
+
+export class InvalidPenalty extends Error {
+  static readonly code = 6022
+  readonly code = 6022
+  readonly name = "InvalidPenalty"
+  readonly msg = "Penalties can't be over 10000 basis points."
+
+  constructor(readonly logs?: string[]) {
+    super("6022: Penalties can't be over 10000 basis points.")
+  }
+}
+
+export class EarlyUnstakeDisabled extends Error {
+  static readonly code = 6023
+  readonly code = 6023
+  readonly name = "EarlyUnstakeDisabled"
+  readonly msg = "This lock doesn't allow unstaking early."
+
+  constructor(readonly logs?: string[]) {
+    super("6023: This lock doesn't allow unstaking early.")
+  }
+}
+
+export class LockMismatch extends Error {
+  static readonly code = 6024
+  readonly code = 6024
+  readonly name = "LockMismatch"
+  readonly msg = "A running stake can only be topped up with the same lock."
+
+  constructor(readonly logs?: string[]) {
+    super("6024: A running stake can only be topped up with the same lock.")
+  }
+}
+
+export class InvalidUnstakeAmount extends Error {
+  static readonly code = 6025
+  readonly code = 6025
+  readonly name = "InvalidUnstakeAmount"
+  readonly msg = "Can't unstake zero or more than what's staked."
+
+  constructor(readonly logs?: string[]) {
+    super("6025: Can't unstake zero or more than what's staked.")
+  }
+}
+
+export class InvalidStakeAccounts extends Error {
+  static readonly code = 6026
+  readonly code = 6026
+  readonly name = "InvalidStakeAccounts"
+  readonly msg =
+    "Gem accounts must be given as one group per gem, in the documented order."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6026: Gem accounts must be given as one group per gem, in the documented order."
+    )
+  }
+}
+
+export class InvalidFreezeAccounts extends Error {
+  static readonly code = 6027
+  readonly code = 6027
+  readonly name = "InvalidFreezeAccounts"
+  readonly msg =
+    "Frozen gems need their master edition and the Token Metadata program as the last remaining accounts."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6027: Frozen gems need their master edition and the Token Metadata program as the last remaining accounts."
+    )
+  }
+}
+
+export class UnsupportedCustody extends Error {
+  static readonly code = 6028
+  readonly code = 6028
+  readonly name = "UnsupportedCustody"
+  readonly msg = "This operation isn't supported by the farm's custody mode."
+
+  constructor(readonly logs?: string[]) {
+    super("6028: This operation isn't supported by the farm's custody mode.")
+  }
+}
+
+export class InvalidPnftAccounts extends Error {
+  static readonly code = 6029
+  readonly code = 6029
+  readonly name = "InvalidPnftAccounts"
+  readonly msg =
+    "Programmable NFTs need their Token Metadata accounts as the first remaining accounts."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6029: Programmable NFTs need their Token Metadata accounts as the first remaining accounts."
+    )
+  }
+}
+
+export class InvalidMerkleProof extends Error {
+  static readonly code = 6030
+  readonly code = 6030
+  readonly name = "InvalidMerkleProof"
+  readonly msg = "The merkle proof doesn't match the whitelisted root."
+
+  constructor(readonly logs?: string[]) {
+    super("6030: The merkle proof doesn't match the whitelisted root.")
+  }
+}
+
+export class WhitelistDelisted extends Error {
+  static readonly code = 6031
+  readonly code = 6031
+  readonly name = "WhitelistDelisted"
+  readonly msg = "This whitelist has been delisted."
+
+  constructor(readonly logs?: string[]) {
+    super("6031: This whitelist has been delisted.")
+  }
+}
+
+export class WhitelistHasStakes extends Error {
+  static readonly code = 6032
+  readonly code = 6032
+  readonly name = "WhitelistHasStakes"
+  readonly msg = "This whitelist still has active stakes."
+
+  constructor(readonly logs?: string[]) {
+    super("6032: This whitelist still has active stakes.")
+  }
+}
+
+export class InvalidDelistTime extends Error {
+  static readonly code = 6033
+  readonly code = 6033
+  readonly name = "InvalidDelistTime"
+  readonly msg = "A whitelist can't be delisted in the past."
+
+  constructor(readonly logs?: string[]) {
+    super("6033: A whitelist can't be delisted in the past.")
+  }
+}
+
+export class InvalidBuffConfig extends Error {
+  static readonly code = 6034
+  readonly code = 6034
+  readonly name = "InvalidBuffConfig"
+  readonly msg = "Invalid buff settings."
+
+  constructor(readonly logs?: string[]) {
+    super("6034: Invalid buff settings.")
+  }
+}
+
+export class InvalidBuffCount extends Error {
+  static readonly code = 6035
+  readonly code = 6035
+  readonly name = "InvalidBuffCount"
+  readonly msg =
+    "A buff can boost at least one stake and at most its whitelist's arity."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6035: A buff can boost at least one stake and at most its whitelist's arity."
+    )
+  }
+}
+
+export class TooManyExpiringBuffs extends Error {
+  static readonly code = 6036
+  readonly code = 6036
+  readonly name = "TooManyExpiringBuffs"
+  readonly msg = "Too many expiring buffs are active for this farmer."
+
+  constructor(readonly logs?: string[]) {
+    super("6036: Too many expiring buffs are active for this farmer.")
+  }
+}
+
+export class UnconsumableBuff extends Error {
+  static readonly code = 6037
+  readonly code = 6037
+  readonly name = "UnconsumableBuff"
+  readonly msg = "Programmable NFTs can't be used as consumable buffs."
+
+  constructor(readonly logs?: string[]) {
+    super("6037: Programmable NFTs can't be used as consumable buffs.")
+  }
+}
+
+export class ExpiringBuffNotStackable extends Error {
+  static readonly code = 6038
+  readonly code = 6038
+  readonly name = "ExpiringBuffNotStackable"
+  readonly msg = "Expiring buffs can't be stacked with other buffs."
+
+  constructor(readonly logs?: string[]) {
+    super("6038: Expiring buffs can't be stacked with other buffs.")
+  }
+}
+
+export class RewardsLocked extends Error {
+  static readonly code = 6039
+  readonly code = 6039
+  readonly name = "RewardsLocked"
+  readonly msg =
+    "Rewards can't be claimed while a stake that can be unstaked early is locked."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6039: Rewards can't be claimed while a stake that can be unstaked early is locked."
+    )
+  }
+}
+
+export class InvalidGemLevelAccounts extends Error {
+  static readonly code = 6040
+  readonly code = 6040
+  readonly name = "InvalidGemLevelAccounts"
+  readonly msg =
+    "Gem level accounts must be given in the same order as the levels."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6040: Gem level accounts must be given in the same order as the levels."
+    )
+  }
+}
+
+export class InvalidRewardRates extends Error {
+  static readonly code = 6041
+  readonly code = 6041
+  readonly name = "InvalidRewardRates"
+  readonly msg = "Reward rates can only be set for the farm's rewards."
+
+  constructor(readonly logs?: string[]) {
+    super("6041: Reward rates can only be set for the farm's rewards.")
+  }
+}
+
+export class TooManyWhitelists extends Error {
+  static readonly code = 6042
+  readonly code = 6042
+  readonly name = "TooManyWhitelists"
+  readonly msg = "This farmer has stakes under too many whitelists."
+
+  constructor(readonly logs?: string[]) {
+    super("6042: This farmer has stakes under too many whitelists.")
+  }
+}
+
+export class InvalidWhitelistAccounts extends Error {
+  static readonly code = 6043
+  readonly code = 6043
+  readonly name = "InvalidWhitelistAccounts"
+  readonly msg =
+    "The farmer's whitelists must be given in order before the reward accounts."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6043: The farmer's whitelists must be given in order before the reward accounts."
+    )
+  }
+}
+
+export class DelistedStakes extends Error {
+  static readonly code = 6044
+  readonly code = 6044
+  readonly name = "DelistedStakes"
+  readonly msg = "Stakes under a delisted whitelist must be refreshed first."
+
+  constructor(readonly logs?: string[]) {
+    super("6044: Stakes under a delisted whitelist must be refreshed first.")
+  }
+}
+
+export class UnverifiedReceiptFarmer extends Error {
+  static readonly code = 6045
+  readonly code = 6045
+  readonly name = "UnverifiedReceiptFarmer"
+  readonly msg =
+    "Legacy receipts must follow their farmer, which must belong to the farm."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6045: Legacy receipts must follow their farmer, which must belong to the farm."
+    )
+  }
+}
+
+export class InvalidStakeAmount extends Error {
+  static readonly code = 6046
+  readonly code = 6046
+  readonly name = "InvalidStakeAmount"
+  readonly msg =
+    "Can't stake more than the wallet holds, or more than one of an NFT."
+
+  constructor(readonly logs?: string[]) {
+    super(
+      "6046: Can't stake more than the wallet holds, or more than one of an NFT."
+    )
+  }
+}
+
+export class UnsupportedPnft extends Error {
+  static readonly code = 6047
+  readonly code = 6047
+  readonly name = "UnsupportedPnft"
+  readonly msg = "Programmable NFTs must be staked and unstaked one at a time."
+
+  constructor(readonly logs?: string[]) {
+    super("6047: Programmable NFTs must be staked and unstaked one at a time.")
+  }
+}
+
+export class InvalidPermissions extends Error {
+  static readonly code = 6048
+  readonly code = 6048
+  readonly name = "InvalidPermissions"
+  readonly msg = "Managers can only be given the permissions of `FarmManager`."
+
+  constructor(readonly logs?: string[]) {
+    super("6048: Managers can only be given the permissions of `FarmManager`.")
+  }
+}
+
 export function fromCode(code: number, logs?: string[]): CustomError | null {
   switch (code) {
     case 6000:
@@ -184,6 +646,78 @@ export function fromCode(code: number, logs?: string[]): CustomError | null {
       return new InvalidTripEffect(logs)
     case 6012:
       return new ArithmeticError(logs)
+    case 6013:
+      return new InvalidRewardMint(logs)
+    case 6014:
+      return new TooManyRewards(logs)
+    case 6015:
+      return new RewardAlreadyAdded(logs)
+    case 6016:
+      return new InvalidRewardAccounts(logs)
+    case 6017:
+      return new InvalidSchedule(logs)
+    case 6018:
+      return new InvalidEmissionMode(logs)
+    case 6019:
+      return new FarmPaused(logs)
+    case 6020:
+      return new NotPendingAuthority(logs)
+    case 6021:
+      return new MissingPermission(logs)
+    case 6022:
+      return new InvalidPenalty(logs)
+    case 6023:
+      return new EarlyUnstakeDisabled(logs)
+    case 6024:
+      return new LockMismatch(logs)
+    case 6025:
+      return new InvalidUnstakeAmount(logs)
+    case 6026:
+      return new InvalidStakeAccounts(logs)
+    case 6027:
+      return new InvalidFreezeAccounts(logs)
+    case 6028:
+      return new UnsupportedCustody(logs)
+    case 6029:
+      return new InvalidPnftAccounts(logs)
+    case 6030:
+      return new InvalidMerkleProof(logs)
+    case 6031:
+      return new WhitelistDelisted(logs)
+    case 6032:
+      return new WhitelistHasStakes(logs)
+    case 6033:
+      return new InvalidDelistTime(logs)
+    case 6034:
+      return new InvalidBuffConfig(logs)
+    case 6035:
+      return new InvalidBuffCount(logs)
+    case 6036:
+      return new TooManyExpiringBuffs(logs)
+    case 6037:
+      return new UnconsumableBuff(logs)
+    case 6038:
+      return new ExpiringBuffNotStackable(logs)
+    case 6039:
+      return new RewardsLocked(logs)
+    case 6040:
+      return new InvalidGemLevelAccounts(logs)
+    case 6041:
+      return new InvalidRewardRates(logs)
+    case 6042:
+      return new TooManyWhitelists(logs)
+    case 6043:
+      return new InvalidWhitelistAccounts(logs)
+    case 6044:
+      return new DelistedStakes(logs)
+    case 6045:
+      return new UnverifiedReceiptFarmer(logs)
+    case 6046:
+      return new InvalidStakeAmount(logs)
+    case 6047:
+      return new UnsupportedPnft(logs)
+    case 6048:
+      return new InvalidPermissions(logs)
   }
 
   return null
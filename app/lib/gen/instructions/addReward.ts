@@ -0,0 +1,37 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface AddRewardAccounts {
+  farm: PublicKey
+  rewardMint: PublicKey
+  farmVault: PublicKey
+  authority: PublicKey
+  rent: PublicKey
+  systemProgram: PublicKey
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+}
+
+export function addReward(accounts: AddRewardAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.rewardMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.farmVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    { pubkey: accounts.rent, isSigner: false, isWritable: false },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+  ]
+  const identifier = Buffer.from([4, 114, 188, 164, 149, 249, 198, 237])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
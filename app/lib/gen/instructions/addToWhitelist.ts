@@ -5,8 +5,9 @@ import * as types from "../types" // eslint-disable-line @typescript-eslint/no-u
 import { PROGRAM_ID } from "../programId"
 
 export interface AddToWhitelistArgs {
-  rewardRate: number
+  rewardRates: Array<BN>
   whitelistType: types.WhitelistTypeKind
+  buffConfig: types.BuffConfigFields | null
 }
 
 export interface AddToWhitelistAccounts {
@@ -19,8 +20,9 @@ export interface AddToWhitelistAccounts {
 }
 
 export const layout = borsh.struct([
-  borsh.f64("rewardRate"),
+  borsh.array(borsh.u128(), 3, "rewardRates"),
   types.WhitelistType.layout("whitelistType"),
+  borsh.option(types.BuffConfig.layout(), "buffConfig"),
 ])
 
 export function addToWhitelist(
@@ -39,8 +41,11 @@ export function addToWhitelist(
   const buffer = Buffer.alloc(1000)
   const len = layout.encode(
     {
-      rewardRate: args.rewardRate,
+      rewardRates: args.rewardRates,
       whitelistType: args.whitelistType.toEncodable(),
+      buffConfig:
+        (args.buffConfig && types.BuffConfig.toEncodable(args.buffConfig)) ||
+        null,
     },
     buffer
   )
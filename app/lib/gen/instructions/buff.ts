@@ -0,0 +1,58 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface BuffArgs {
+  count: number
+}
+
+export interface BuffAccounts {
+  farm: PublicKey
+  farmer: PublicKey
+  buffMint: PublicKey
+  buffWhitelist: PublicKey
+  buffUserAta: PublicKey
+  buffVault: PublicKey
+  buffRecord: PublicKey
+  authority: PublicKey
+  rent: PublicKey
+  systemProgram: PublicKey
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u8("count")])
+
+export function buff(args: BuffArgs, accounts: BuffAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmer, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffWhitelist, isSigner: false, isWritable: false },
+    { pubkey: accounts.buffUserAta, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffRecord, isSigner: false, isWritable: true },
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    { pubkey: accounts.rent, isSigner: false, isWritable: false },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+  ]
+  const identifier = Buffer.from([98, 47, 15, 166, 147, 47, 113, 190])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      count: args.count,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
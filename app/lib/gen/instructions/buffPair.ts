@@ -11,6 +11,7 @@ export interface BuffPairAccounts {
   buffWhitelist: PublicKey
   buffUserAta: PublicKey
   buffVault: PublicKey
+  buffRecord: PublicKey
   mintA: PublicKey
   mintAReceipt: PublicKey
   mintB: PublicKey
@@ -26,10 +27,11 @@ export function buffPair(accounts: BuffPairAccounts) {
   const keys: Array<AccountMeta> = [
     { pubkey: accounts.farm, isSigner: false, isWritable: true },
     { pubkey: accounts.farmer, isSigner: false, isWritable: true },
-    { pubkey: accounts.buffMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.buffMint, isSigner: false, isWritable: true },
     { pubkey: accounts.buffWhitelist, isSigner: false, isWritable: false },
     { pubkey: accounts.buffUserAta, isSigner: false, isWritable: true },
     { pubkey: accounts.buffVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffRecord, isSigner: false, isWritable: true },
     { pubkey: accounts.mintA, isSigner: false, isWritable: false },
     { pubkey: accounts.mintAReceipt, isSigner: false, isWritable: true },
     { pubkey: accounts.mintB, isSigner: false, isWritable: false },
@@ -7,9 +7,6 @@ import { PROGRAM_ID } from "../programId"
 export interface ClaimRewardsAccounts {
   farm: PublicKey
   farmer: PublicKey
-  rewardMint: PublicKey
-  farmRewardVault: PublicKey
-  farmerRewardVault: PublicKey
   authority: PublicKey
   rent: PublicKey
   systemProgram: PublicKey
@@ -21,9 +18,6 @@ export function claimRewards(accounts: ClaimRewardsAccounts) {
   const keys: Array<AccountMeta> = [
     { pubkey: accounts.farm, isSigner: false, isWritable: true },
     { pubkey: accounts.farmer, isSigner: false, isWritable: true },
-    { pubkey: accounts.rewardMint, isSigner: false, isWritable: false },
-    { pubkey: accounts.farmRewardVault, isSigner: false, isWritable: true },
-    { pubkey: accounts.farmerRewardVault, isSigner: false, isWritable: true },
     { pubkey: accounts.authority, isSigner: true, isWritable: true },
     { pubkey: accounts.rent, isSigner: false, isWritable: false },
     { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
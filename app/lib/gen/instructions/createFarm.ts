@@ -4,6 +4,11 @@ import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript
 import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
+export interface CreateFarmArgs {
+  mode: types.EmissionModeKind
+  custody: types.CustodyKind
+}
+
 export interface CreateFarmAccounts {
   farm: PublicKey
   rewardMint: PublicKey
@@ -15,7 +20,12 @@ export interface CreateFarmAccounts {
   associatedTokenProgram: PublicKey
 }
 
-export function createFarm(accounts: CreateFarmAccounts) {
+export const layout = borsh.struct([
+  types.EmissionMode.layout("mode"),
+  types.Custody.layout("custody"),
+])
+
+export function createFarm(args: CreateFarmArgs, accounts: CreateFarmAccounts) {
   const keys: Array<AccountMeta> = [
     { pubkey: accounts.farm, isSigner: false, isWritable: true },
     { pubkey: accounts.rewardMint, isSigner: false, isWritable: false },
@@ -31,7 +41,15 @@ export function createFarm(accounts: CreateFarmAccounts) {
     },
   ]
   const identifier = Buffer.from([74, 59, 128, 160, 87, 174, 153, 194])
-  const data = identifier
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      mode: args.mode.toEncodable(),
+      custody: args.custody.toEncodable(),
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
   const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
   return ix
 }
@@ -10,6 +10,7 @@ export interface DebuffPairAccounts {
   buffMint: PublicKey
   buffUserAta: PublicKey
   buffVault: PublicKey
+  buffRecord: PublicKey
   mintA: PublicKey
   mintAReceipt: PublicKey
   mintB: PublicKey
@@ -25,11 +26,12 @@ export function debuffPair(accounts: DebuffPairAccounts) {
     { pubkey: accounts.buffMint, isSigner: false, isWritable: false },
     { pubkey: accounts.buffUserAta, isSigner: false, isWritable: true },
     { pubkey: accounts.buffVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffRecord, isSigner: false, isWritable: true },
     { pubkey: accounts.mintA, isSigner: false, isWritable: false },
     { pubkey: accounts.mintAReceipt, isSigner: false, isWritable: true },
     { pubkey: accounts.mintB, isSigner: false, isWritable: false },
     { pubkey: accounts.mintBReceipt, isSigner: false, isWritable: true },
-    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
   ]
   const identifier = Buffer.from([251, 106, 212, 199, 136, 102, 33, 176])
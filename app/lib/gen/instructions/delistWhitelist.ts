@@ -0,0 +1,41 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface DelistWhitelistArgs {
+  atTs: BN
+}
+
+export interface DelistWhitelistAccounts {
+  farm: PublicKey
+  farmManager: PublicKey
+  whitelistProof: PublicKey
+  authority: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u64("atTs")])
+
+export function delistWhitelist(
+  args: DelistWhitelistArgs,
+  accounts: DelistWhitelistAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: false },
+    { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
+    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: true },
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([231, 140, 120, 30, 205, 246, 156, 75])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      atTs: args.atTs,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,33 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface ForceDebuffAccounts {
+  farm: PublicKey
+  farmer: PublicKey
+  buffMint: PublicKey
+  buffUserAta: PublicKey
+  buffVault: PublicKey
+  buffRecord: PublicKey
+  authority: PublicKey
+  tokenProgram: PublicKey
+}
+
+export function forceDebuff(accounts: ForceDebuffAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmer, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.buffUserAta, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.buffRecord, isSigner: false, isWritable: true },
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([188, 41, 43, 99, 89, 92, 8, 30])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -5,7 +5,7 @@ import * as types from "../types" // eslint-disable-line @typescript-eslint/no-u
 import { PROGRAM_ID } from "../programId"
 
 export interface FundRewardArgs {
-  amount: number
+  amount: BN
 }
 
 export interface FundRewardAccounts {
@@ -18,7 +18,7 @@ export interface FundRewardAccounts {
   tokenProgram: PublicKey
 }
 
-export const layout = borsh.struct([borsh.f64("amount")])
+export const layout = borsh.struct([borsh.u64("amount")])
 
 export function fundReward(args: FundRewardArgs, accounts: FundRewardAccounts) {
   const keys: Array<AccountMeta> = [
@@ -1,27 +1,88 @@
 export { createFarm } from "./createFarm"
-export type { CreateFarmAccounts } from "./createFarm"
+export type { CreateFarmArgs, CreateFarmAccounts } from "./createFarm"
+export { proposeAuthority } from "./proposeAuthority"
+export type { ProposeAuthorityAccounts } from "./proposeAuthority"
+export { acceptAuthority } from "./acceptAuthority"
+export type { AcceptAuthorityAccounts } from "./acceptAuthority"
+export { setPaused } from "./setPaused"
+export type { SetPausedArgs, SetPausedAccounts } from "./setPaused"
+export { updateFarm } from "./updateFarm"
+export type { UpdateFarmArgs, UpdateFarmAccounts } from "./updateFarm"
 export { addManager } from "./addManager"
-export type { AddManagerAccounts } from "./addManager"
+export type { AddManagerArgs, AddManagerAccounts } from "./addManager"
+export { removeManager } from "./removeManager"
+export type { RemoveManagerAccounts } from "./removeManager"
+export { addReward } from "./addReward"
+export type { AddRewardAccounts } from "./addReward"
 export { addToWhitelist } from "./addToWhitelist"
 export type {
   AddToWhitelistArgs,
   AddToWhitelistAccounts,
 } from "./addToWhitelist"
+export { updateWhitelist } from "./updateWhitelist"
+export type {
+  UpdateWhitelistArgs,
+  UpdateWhitelistAccounts,
+} from "./updateWhitelist"
+export { delistWhitelist } from "./delistWhitelist"
+export type {
+  DelistWhitelistArgs,
+  DelistWhitelistAccounts,
+} from "./delistWhitelist"
 export { removeFromWhitelist } from "./removeFromWhitelist"
-export type { RemoveFromWhitelistAccounts } from "./removeFromWhitelist"
+export type {
+  RemoveFromWhitelistArgs,
+  RemoveFromWhitelistAccounts,
+} from "./removeFromWhitelist"
 export { fundReward } from "./fundReward"
 export type { FundRewardArgs, FundRewardAccounts } from "./fundReward"
+export { updateRewardBudget } from "./updateRewardBudget"
+export type {
+  UpdateRewardBudgetArgs,
+  UpdateRewardBudgetAccounts,
+} from "./updateRewardBudget"
+export { updateSchedule } from "./updateSchedule"
+export type {
+  UpdateScheduleArgs,
+  UpdateScheduleAccounts,
+} from "./updateSchedule"
 export { createLocks } from "./createLocks"
 export type { CreateLocksArgs, CreateLocksAccounts } from "./createLocks"
+export { setGemLevels } from "./setGemLevels"
+export type { SetGemLevelsArgs, SetGemLevelsAccounts } from "./setGemLevels"
 export { initializeFarmer } from "./initializeFarmer"
 export type { InitializeFarmerAccounts } from "./initializeFarmer"
 export { stake } from "./stake"
 export type { StakeArgs, StakeAccounts } from "./stake"
+export { stakeMany } from "./stakeMany"
+export type { StakeManyAccounts } from "./stakeMany"
 export { unstake } from "./unstake"
 export type { UnstakeAccounts } from "./unstake"
+export { unstakeMany } from "./unstakeMany"
+export type { UnstakeManyAccounts } from "./unstakeMany"
+export { unstakePartial } from "./unstakePartial"
+export type {
+  UnstakePartialArgs,
+  UnstakePartialAccounts,
+} from "./unstakePartial"
+export { earlyUnstake } from "./earlyUnstake"
+export type { EarlyUnstakeAccounts } from "./earlyUnstake"
 export { buffPair } from "./buffPair"
 export type { BuffPairAccounts } from "./buffPair"
 export { debuffPair } from "./debuffPair"
 export type { DebuffPairAccounts } from "./debuffPair"
+export { buff } from "./buff"
+export type { BuffArgs, BuffAccounts } from "./buff"
+export { debuff } from "./debuff"
+export type { DebuffAccounts } from "./debuff"
+export { forceDebuff } from "./forceDebuff"
+export type { ForceDebuffAccounts } from "./forceDebuff"
+export { refreshReceipt } from "./refreshReceipt"
+export type {
+  RefreshReceiptArgs,
+  RefreshReceiptAccounts,
+} from "./refreshReceipt"
 export { claimRewards } from "./claimRewards"
 export type { ClaimRewardsAccounts } from "./claimRewards"
+export { migrateAccounts } from "./migrateAccounts"
+export type { MigrateAccountsAccounts } from "./migrateAccounts"
@@ -0,0 +1,51 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface RefreshReceiptArgs {
+  merkleProof: types.MerkleProofFields | null
+}
+
+export interface RefreshReceiptAccounts {
+  farm: PublicKey
+  farmer: PublicKey
+  stakeReceipt: PublicKey
+  gemMint: PublicKey
+  whitelistProof: PublicKey
+  gemLevel: PublicKey
+  lock: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.option(types.MerkleProof.layout(), "merkleProof"),
+])
+
+export function refreshReceipt(
+  args: RefreshReceiptArgs,
+  accounts: RefreshReceiptAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmer, isSigner: false, isWritable: true },
+    { pubkey: accounts.stakeReceipt, isSigner: false, isWritable: true },
+    { pubkey: accounts.gemMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: true },
+    { pubkey: accounts.gemLevel, isSigner: false, isWritable: false },
+    { pubkey: accounts.lock, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([252, 175, 34, 252, 162, 101, 8, 226])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      merkleProof:
+        (args.merkleProof && types.MerkleProof.toEncodable(args.merkleProof)) ||
+        null,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
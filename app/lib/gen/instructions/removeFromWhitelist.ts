@@ -4,6 +4,10 @@ import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript
 import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
+export interface RemoveFromWhitelistArgs {
+  force: boolean
+}
+
 export interface RemoveFromWhitelistAccounts {
   farm: PublicKey
   farmManager: PublicKey
@@ -12,7 +16,12 @@ export interface RemoveFromWhitelistAccounts {
   systemProgram: PublicKey
 }
 
-export function removeFromWhitelist(accounts: RemoveFromWhitelistAccounts) {
+export const layout = borsh.struct([borsh.bool("force")])
+
+export function removeFromWhitelist(
+  args: RemoveFromWhitelistArgs,
+  accounts: RemoveFromWhitelistAccounts
+) {
   const keys: Array<AccountMeta> = [
     { pubkey: accounts.farm, isSigner: false, isWritable: false },
     { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
@@ -21,7 +30,14 @@ export function removeFromWhitelist(accounts: RemoveFromWhitelistAccounts) {
     { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
   ]
   const identifier = Buffer.from([7, 144, 216, 239, 243, 236, 193, 235])
-  const data = identifier
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      force: args.force,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
   const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
   return ix
 }
@@ -0,0 +1,43 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface SetGemLevelsArgs {
+  levels: Array<types.GemLevelConfigFields>
+}
+
+export interface SetGemLevelsAccounts {
+  farm: PublicKey
+  farmManager: PublicKey
+  authority: PublicKey
+  systemProgram: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.vec(types.GemLevelConfig.layout(), "levels"),
+])
+
+export function setGemLevels(
+  args: SetGemLevelsArgs,
+  accounts: SetGemLevelsAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: false },
+    { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([116, 14, 195, 211, 10, 220, 114, 11])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      levels: args.levels.map((item) => types.GemLevelConfig.toEncodable(item)),
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,36 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface SetPausedArgs {
+  paused: boolean
+}
+
+export interface SetPausedAccounts {
+  farm: PublicKey
+  farmManager: PublicKey
+  authority: PublicKey
+}
+
+export const layout = borsh.struct([borsh.bool("paused")])
+
+export function setPaused(args: SetPausedArgs, accounts: SetPausedAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([91, 60, 125, 192, 176, 225, 166, 218])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      paused: args.paused,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -6,7 +6,7 @@ import { PROGRAM_ID } from "../programId"
 
 export interface StakeArgs {
   amount: BN
-  level: number
+  merkleProof: types.MerkleProofFields | null
 }
 
 export interface StakeAccounts {
@@ -14,6 +14,7 @@ export interface StakeAccounts {
   farmer: PublicKey
   gemMint: PublicKey
   whitelistProof: PublicKey
+  gemLevel: PublicKey
   farmerVault: PublicKey
   gemOwnerAta: PublicKey
   stakeReceipt: PublicKey
@@ -25,14 +26,18 @@ export interface StakeAccounts {
   associatedTokenProgram: PublicKey
 }
 
-export const layout = borsh.struct([borsh.u64("amount"), borsh.u8("level")])
+export const layout = borsh.struct([
+  borsh.u64("amount"),
+  borsh.option(types.MerkleProof.layout(), "merkleProof"),
+])
 
 export function stake(args: StakeArgs, accounts: StakeAccounts) {
   const keys: Array<AccountMeta> = [
     { pubkey: accounts.farm, isSigner: false, isWritable: true },
     { pubkey: accounts.farmer, isSigner: false, isWritable: true },
     { pubkey: accounts.gemMint, isSigner: false, isWritable: false },
-    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: false },
+    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: true },
+    { pubkey: accounts.gemLevel, isSigner: false, isWritable: false },
     { pubkey: accounts.farmerVault, isSigner: false, isWritable: true },
     { pubkey: accounts.gemOwnerAta, isSigner: false, isWritable: true },
     { pubkey: accounts.stakeReceipt, isSigner: false, isWritable: true },
@@ -52,7 +57,9 @@ export function stake(args: StakeArgs, accounts: StakeAccounts) {
   const len = layout.encode(
     {
       amount: args.amount,
-      level: args.level,
+      merkleProof:
+        (args.merkleProof && types.MerkleProof.toEncodable(args.merkleProof)) ||
+        null,
     },
     buffer
   )
@@ -10,6 +10,7 @@ export interface UnstakeAccounts {
   gemMint: PublicKey
   stakeReceipt: PublicKey
   lock: PublicKey
+  whitelistProof: PublicKey
   farmerVault: PublicKey
   gemOwnerAta: PublicKey
   owner: PublicKey
@@ -23,9 +24,10 @@ export function unstake(accounts: UnstakeAccounts) {
     { pubkey: accounts.gemMint, isSigner: false, isWritable: false },
     { pubkey: accounts.stakeReceipt, isSigner: false, isWritable: true },
     { pubkey: accounts.lock, isSigner: false, isWritable: false },
+    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: false },
     { pubkey: accounts.farmerVault, isSigner: false, isWritable: true },
     { pubkey: accounts.gemOwnerAta, isSigner: false, isWritable: true },
-    { pubkey: accounts.owner, isSigner: true, isWritable: false },
+    { pubkey: accounts.owner, isSigner: true, isWritable: true },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
   ]
   const identifier = Buffer.from([90, 95, 107, 42, 205, 124, 50, 225])
@@ -0,0 +1,53 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface UnstakePartialArgs {
+  amount: BN
+}
+
+export interface UnstakePartialAccounts {
+  farm: PublicKey
+  farmer: PublicKey
+  gemMint: PublicKey
+  stakeReceipt: PublicKey
+  lock: PublicKey
+  whitelistProof: PublicKey
+  farmerVault: PublicKey
+  gemOwnerAta: PublicKey
+  owner: PublicKey
+  tokenProgram: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u64("amount")])
+
+export function unstakePartial(
+  args: UnstakePartialArgs,
+  accounts: UnstakePartialAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmer, isSigner: false, isWritable: true },
+    { pubkey: accounts.gemMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.stakeReceipt, isSigner: false, isWritable: true },
+    { pubkey: accounts.lock, isSigner: false, isWritable: false },
+    { pubkey: accounts.whitelistProof, isSigner: false, isWritable: false },
+    { pubkey: accounts.farmerVault, isSigner: false, isWritable: true },
+    { pubkey: accounts.gemOwnerAta, isSigner: false, isWritable: true },
+    { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([91, 27, 226, 57, 112, 31, 78, 184])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      amount: args.amount,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,34 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface UpdateFarmArgs {
+  config: types.FarmConfigFields
+}
+
+export interface UpdateFarmAccounts {
+  farm: PublicKey
+  authority: PublicKey
+}
+
+export const layout = borsh.struct([types.FarmConfig.layout("config")])
+
+export function updateFarm(args: UpdateFarmArgs, accounts: UpdateFarmAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([94, 37, 19, 249, 121, 53, 154, 189])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      config: types.FarmConfig.toEncodable(args.config),
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
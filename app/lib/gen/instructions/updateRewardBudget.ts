@@ -0,0 +1,41 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface UpdateRewardBudgetArgs {
+  budgetRate: BN
+}
+
+export interface UpdateRewardBudgetAccounts {
+  farm: PublicKey
+  farmManager: PublicKey
+  rewardMint: PublicKey
+  authority: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u128("budgetRate")])
+
+export function updateRewardBudget(
+  args: UpdateRewardBudgetArgs,
+  accounts: UpdateRewardBudgetAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
+    { pubkey: accounts.rewardMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([13, 64, 248, 153, 105, 137, 140, 224])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      budgetRate: args.budgetRate,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
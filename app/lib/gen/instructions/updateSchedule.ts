@@ -0,0 +1,41 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface UpdateScheduleArgs {
+  startTs: BN
+  endTs: BN
+}
+
+export interface UpdateScheduleAccounts {
+  farm: PublicKey
+  farmManager: PublicKey
+  authority: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u64("startTs"), borsh.u64("endTs")])
+
+export function updateSchedule(
+  args: UpdateScheduleArgs,
+  accounts: UpdateScheduleAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.farm, isSigner: false, isWritable: true },
+    { pubkey: accounts.farmManager, isSigner: false, isWritable: false },
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([121, 160, 17, 111, 16, 230, 228, 70])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      startTs: args.startTs,
+      endTs: args.endTs,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
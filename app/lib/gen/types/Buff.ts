@@ -5,53 +5,86 @@ import * as borsh from "@project-serum/borsh"
 
 export interface BuffFields {
   key: PublicKey
-  factor: number
+  category: number
+  stacking: types.BuffStackingKind
+  factor: BN
+  expiresAt: BN | null
 }
 
 export interface BuffJSON {
   key: string
-  factor: number
+  category: number
+  stacking: types.BuffStackingJSON
+  factor: string
+  expiresAt: string | null
 }
 
 export class Buff {
   readonly key: PublicKey
-  readonly factor: number
+  readonly category: number
+  readonly stacking: types.BuffStackingKind
+  readonly factor: BN
+  readonly expiresAt: BN | null
 
   constructor(fields: BuffFields) {
     this.key = fields.key
+    this.category = fields.category
+    this.stacking = fields.stacking
     this.factor = fields.factor
+    this.expiresAt = fields.expiresAt
   }
 
   static layout(property?: string) {
-    return borsh.struct([borsh.publicKey("key"), borsh.f64("factor")], property)
+    return borsh.struct(
+      [
+        borsh.publicKey("key"),
+        borsh.u8("category"),
+        types.BuffStacking.layout("stacking"),
+        borsh.u128("factor"),
+        borsh.option(borsh.u64(), "expiresAt"),
+      ],
+      property
+    )
   }
 
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   static fromDecoded(obj: any) {
     return new Buff({
       key: obj.key,
+      category: obj.category,
+      stacking: types.BuffStacking.fromDecoded(obj.stacking),
       factor: obj.factor,
+      expiresAt: obj.expiresAt,
     })
   }
 
   static toEncodable(fields: BuffFields) {
     return {
       key: fields.key,
+      category: fields.category,
+      stacking: fields.stacking.toEncodable(),
       factor: fields.factor,
+      expiresAt: fields.expiresAt,
     }
   }
 
   toJSON(): BuffJSON {
     return {
       key: this.key.toString(),
-      factor: this.factor,
+      category: this.category,
+      stacking: this.stacking.toJSON(),
+      factor: this.factor.toString(),
+      expiresAt: (this.expiresAt && this.expiresAt.toString()) || null,
     }
   }
 
   static fromJSON(obj: BuffJSON): Buff {
     return new Buff({
       key: new PublicKey(obj.key),
-      factor: obj.factor,
+      category: obj.category,
+      stacking: types.BuffStacking.fromJSON(obj.stacking),
+      factor: new BN(obj.factor),
+      expiresAt: (obj.expiresAt && new BN(obj.expiresAt)) || null,
     })
   }
 
@@ -0,0 +1,64 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface BuffCategoryFields {
+  stacking: types.BuffStackingKind
+  maxStack: number
+}
+
+export interface BuffCategoryJSON {
+  stacking: types.BuffStackingJSON
+  maxStack: number
+}
+
+export class BuffCategory {
+  readonly stacking: types.BuffStackingKind
+  readonly maxStack: number
+
+  constructor(fields: BuffCategoryFields) {
+    this.stacking = fields.stacking
+    this.maxStack = fields.maxStack
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [types.BuffStacking.layout("stacking"), borsh.u8("maxStack")],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new BuffCategory({
+      stacking: types.BuffStacking.fromDecoded(obj.stacking),
+      maxStack: obj.maxStack,
+    })
+  }
+
+  static toEncodable(fields: BuffCategoryFields) {
+    return {
+      stacking: fields.stacking.toEncodable(),
+      maxStack: fields.maxStack,
+    }
+  }
+
+  toJSON(): BuffCategoryJSON {
+    return {
+      stacking: this.stacking.toJSON(),
+      maxStack: this.maxStack,
+    }
+  }
+
+  static fromJSON(obj: BuffCategoryJSON): BuffCategory {
+    return new BuffCategory({
+      stacking: types.BuffStacking.fromJSON(obj.stacking),
+      maxStack: obj.maxStack,
+    })
+  }
+
+  toEncodable() {
+    return BuffCategory.toEncodable(this)
+  }
+}
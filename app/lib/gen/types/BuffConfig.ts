@@ -0,0 +1,85 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface BuffConfigFields {
+  arity: number
+  duration: BN | null
+  consumable: boolean
+  category: number
+}
+
+export interface BuffConfigJSON {
+  arity: number
+  duration: string | null
+  consumable: boolean
+  category: number
+}
+
+export class BuffConfig {
+  readonly arity: number
+  readonly duration: BN | null
+  readonly consumable: boolean
+  readonly category: number
+
+  constructor(fields: BuffConfigFields) {
+    this.arity = fields.arity
+    this.duration = fields.duration
+    this.consumable = fields.consumable
+    this.category = fields.category
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [
+        borsh.u8("arity"),
+        borsh.option(borsh.u64(), "duration"),
+        borsh.bool("consumable"),
+        borsh.u8("category"),
+      ],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new BuffConfig({
+      arity: obj.arity,
+      duration: obj.duration,
+      consumable: obj.consumable,
+      category: obj.category,
+    })
+  }
+
+  static toEncodable(fields: BuffConfigFields) {
+    return {
+      arity: fields.arity,
+      duration: fields.duration,
+      consumable: fields.consumable,
+      category: fields.category,
+    }
+  }
+
+  toJSON(): BuffConfigJSON {
+    return {
+      arity: this.arity,
+      duration: (this.duration && this.duration.toString()) || null,
+      consumable: this.consumable,
+      category: this.category,
+    }
+  }
+
+  static fromJSON(obj: BuffConfigJSON): BuffConfig {
+    return new BuffConfig({
+      arity: obj.arity,
+      duration: (obj.duration && new BN(obj.duration)) || null,
+      consumable: obj.consumable,
+      category: obj.category,
+    })
+  }
+
+  toEncodable() {
+    return BuffConfig.toEncodable(this)
+  }
+}
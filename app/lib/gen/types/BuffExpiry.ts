@@ -0,0 +1,76 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface BuffExpiryFields {
+  buffMint: PublicKey
+  expiresAt: BN
+  boost: Array<BN>
+}
+
+export interface BuffExpiryJSON {
+  buffMint: string
+  expiresAt: string
+  boost: Array<string>
+}
+
+export class BuffExpiry {
+  readonly buffMint: PublicKey
+  readonly expiresAt: BN
+  readonly boost: Array<BN>
+
+  constructor(fields: BuffExpiryFields) {
+    this.buffMint = fields.buffMint
+    this.expiresAt = fields.expiresAt
+    this.boost = fields.boost
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [
+        borsh.publicKey("buffMint"),
+        borsh.u64("expiresAt"),
+        borsh.array(borsh.u128(), 3, "boost"),
+      ],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new BuffExpiry({
+      buffMint: obj.buffMint,
+      expiresAt: obj.expiresAt,
+      boost: obj.boost,
+    })
+  }
+
+  static toEncodable(fields: BuffExpiryFields) {
+    return {
+      buffMint: fields.buffMint,
+      expiresAt: fields.expiresAt,
+      boost: fields.boost,
+    }
+  }
+
+  toJSON(): BuffExpiryJSON {
+    return {
+      buffMint: this.buffMint.toString(),
+      expiresAt: this.expiresAt.toString(),
+      boost: this.boost.map((item) => item.toString()),
+    }
+  }
+
+  static fromJSON(obj: BuffExpiryJSON): BuffExpiry {
+    return new BuffExpiry({
+      buffMint: new PublicKey(obj.buffMint),
+      expiresAt: new BN(obj.expiresAt),
+      boost: obj.boost.map((item) => new BN(item)),
+    })
+  }
+
+  toEncodable() {
+    return BuffExpiry.toEncodable(this)
+  }
+}
@@ -0,0 +1,88 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface AdditiveJSON {
+  kind: "Additive"
+}
+
+export class Additive {
+  static readonly discriminator = 0
+  static readonly kind = "Additive"
+  readonly discriminator = 0
+  readonly kind = "Additive"
+
+  toJSON(): AdditiveJSON {
+    return {
+      kind: "Additive",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Additive: {},
+    }
+  }
+}
+
+export interface MultiplicativeJSON {
+  kind: "Multiplicative"
+}
+
+export class Multiplicative {
+  static readonly discriminator = 1
+  static readonly kind = "Multiplicative"
+  readonly discriminator = 1
+  readonly kind = "Multiplicative"
+
+  toJSON(): MultiplicativeJSON {
+    return {
+      kind: "Multiplicative",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Multiplicative: {},
+    }
+  }
+}
+
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export function fromDecoded(obj: any): types.BuffStackingKind {
+  if (typeof obj !== "object") {
+    throw new Error("Invalid enum object")
+  }
+
+  if ("Additive" in obj) {
+    return new Additive()
+  }
+  if ("Multiplicative" in obj) {
+    return new Multiplicative()
+  }
+
+  throw new Error("Invalid enum object")
+}
+
+export function fromJSON(obj: types.BuffStackingJSON): types.BuffStackingKind {
+  switch (obj.kind) {
+    case "Additive": {
+      return new Additive()
+    }
+    case "Multiplicative": {
+      return new Multiplicative()
+    }
+  }
+}
+
+export function layout(property?: string) {
+  const ret = borsh.rustEnum([
+    borsh.struct([], "Additive"),
+    borsh.struct([], "Multiplicative"),
+  ])
+  if (property !== undefined) {
+    return ret.replicate(property)
+  }
+  return ret
+}
@@ -0,0 +1,88 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface VaultJSON {
+  kind: "Vault"
+}
+
+export class Vault {
+  static readonly discriminator = 0
+  static readonly kind = "Vault"
+  readonly discriminator = 0
+  readonly kind = "Vault"
+
+  toJSON(): VaultJSON {
+    return {
+      kind: "Vault",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Vault: {},
+    }
+  }
+}
+
+export interface FreezeJSON {
+  kind: "Freeze"
+}
+
+export class Freeze {
+  static readonly discriminator = 1
+  static readonly kind = "Freeze"
+  readonly discriminator = 1
+  readonly kind = "Freeze"
+
+  toJSON(): FreezeJSON {
+    return {
+      kind: "Freeze",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Freeze: {},
+    }
+  }
+}
+
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export function fromDecoded(obj: any): types.CustodyKind {
+  if (typeof obj !== "object") {
+    throw new Error("Invalid enum object")
+  }
+
+  if ("Vault" in obj) {
+    return new Vault()
+  }
+  if ("Freeze" in obj) {
+    return new Freeze()
+  }
+
+  throw new Error("Invalid enum object")
+}
+
+export function fromJSON(obj: types.CustodyJSON): types.CustodyKind {
+  switch (obj.kind) {
+    case "Vault": {
+      return new Vault()
+    }
+    case "Freeze": {
+      return new Freeze()
+    }
+  }
+}
+
+export function layout(property?: string) {
+  const ret = borsh.rustEnum([
+    borsh.struct([], "Vault"),
+    borsh.struct([], "Freeze"),
+  ])
+  if (property !== undefined) {
+    return ret.replicate(property)
+  }
+  return ret
+}
@@ -0,0 +1,145 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface DisabledJSON {
+  kind: "Disabled"
+}
+
+export class Disabled {
+  static readonly discriminator = 0
+  static readonly kind = "Disabled"
+  readonly discriminator = 0
+  readonly kind = "Disabled"
+
+  toJSON(): DisabledJSON {
+    return {
+      kind: "Disabled",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Disabled: {},
+    }
+  }
+}
+
+export type PenaltyFields = {
+  bps: number
+}
+export type PenaltyValue = {
+  bps: number
+}
+
+export interface PenaltyJSON {
+  kind: "Penalty"
+  value: {
+    bps: number
+  }
+}
+
+export class Penalty {
+  static readonly discriminator = 1
+  static readonly kind = "Penalty"
+  readonly discriminator = 1
+  readonly kind = "Penalty"
+  readonly value: PenaltyValue
+
+  constructor(value: PenaltyFields) {
+    this.value = {
+      bps: value.bps,
+    }
+  }
+
+  toJSON(): PenaltyJSON {
+    return {
+      kind: "Penalty",
+      value: {
+        bps: this.value.bps,
+      },
+    }
+  }
+
+  toEncodable() {
+    return {
+      Penalty: {
+        bps: this.value.bps,
+      },
+    }
+  }
+}
+
+export interface ForfeitBonusJSON {
+  kind: "ForfeitBonus"
+}
+
+export class ForfeitBonus {
+  static readonly discriminator = 2
+  static readonly kind = "ForfeitBonus"
+  readonly discriminator = 2
+  readonly kind = "ForfeitBonus"
+
+  toJSON(): ForfeitBonusJSON {
+    return {
+      kind: "ForfeitBonus",
+    }
+  }
+
+  toEncodable() {
+    return {
+      ForfeitBonus: {},
+    }
+  }
+}
+
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export function fromDecoded(obj: any): types.EarlyUnstakeKind {
+  if (typeof obj !== "object") {
+    throw new Error("Invalid enum object")
+  }
+
+  if ("Disabled" in obj) {
+    return new Disabled()
+  }
+  if ("Penalty" in obj) {
+    const val = obj["Penalty"]
+    return new Penalty({
+      bps: val["bps"],
+    })
+  }
+  if ("ForfeitBonus" in obj) {
+    return new ForfeitBonus()
+  }
+
+  throw new Error("Invalid enum object")
+}
+
+export function fromJSON(obj: types.EarlyUnstakeJSON): types.EarlyUnstakeKind {
+  switch (obj.kind) {
+    case "Disabled": {
+      return new Disabled()
+    }
+    case "Penalty": {
+      return new Penalty({
+        bps: obj.value.bps,
+      })
+    }
+    case "ForfeitBonus": {
+      return new ForfeitBonus()
+    }
+  }
+}
+
+export function layout(property?: string) {
+  const ret = borsh.rustEnum([
+    borsh.struct([], "Disabled"),
+    borsh.struct([borsh.u16("bps")], "Penalty"),
+    borsh.struct([], "ForfeitBonus"),
+  ])
+  if (property !== undefined) {
+    return ret.replicate(property)
+  }
+  return ret
+}
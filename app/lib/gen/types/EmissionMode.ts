@@ -0,0 +1,88 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface RateJSON {
+  kind: "Rate"
+}
+
+export class Rate {
+  static readonly discriminator = 0
+  static readonly kind = "Rate"
+  readonly discriminator = 0
+  readonly kind = "Rate"
+
+  toJSON(): RateJSON {
+    return {
+      kind: "Rate",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Rate: {},
+    }
+  }
+}
+
+export interface PoolJSON {
+  kind: "Pool"
+}
+
+export class Pool {
+  static readonly discriminator = 1
+  static readonly kind = "Pool"
+  readonly discriminator = 1
+  readonly kind = "Pool"
+
+  toJSON(): PoolJSON {
+    return {
+      kind: "Pool",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Pool: {},
+    }
+  }
+}
+
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export function fromDecoded(obj: any): types.EmissionModeKind {
+  if (typeof obj !== "object") {
+    throw new Error("Invalid enum object")
+  }
+
+  if ("Rate" in obj) {
+    return new Rate()
+  }
+  if ("Pool" in obj) {
+    return new Pool()
+  }
+
+  throw new Error("Invalid enum object")
+}
+
+export function fromJSON(obj: types.EmissionModeJSON): types.EmissionModeKind {
+  switch (obj.kind) {
+    case "Rate": {
+      return new Rate()
+    }
+    case "Pool": {
+      return new Pool()
+    }
+  }
+}
+
+export function layout(property?: string) {
+  const ret = borsh.rustEnum([
+    borsh.struct([], "Rate"),
+    borsh.struct([], "Pool"),
+  ])
+  if (property !== undefined) {
+    return ret.replicate(property)
+  }
+  return ret
+}
@@ -0,0 +1,86 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface FarmConfigFields {
+  levelDecimals: number
+  buffCategories: Array<types.BuffCategoryFields>
+  reserveSecs: BN
+}
+
+export interface FarmConfigJSON {
+  levelDecimals: number
+  buffCategories: Array<types.BuffCategoryJSON>
+  reserveSecs: string
+}
+
+export class FarmConfig {
+  readonly levelDecimals: number
+  readonly buffCategories: Array<types.BuffCategory>
+  readonly reserveSecs: BN
+
+  constructor(fields: FarmConfigFields) {
+    this.levelDecimals = fields.levelDecimals
+    this.buffCategories = fields.buffCategories.map((item) =>
+      new types.BuffCategory({ ...item })
+    )
+    this.reserveSecs = fields.reserveSecs
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [
+        borsh.u8("levelDecimals"),
+        borsh.array(types.BuffCategory.layout(), 4, "buffCategories"),
+        borsh.u64("reserveSecs"),
+      ],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new FarmConfig({
+      levelDecimals: obj.levelDecimals,
+      buffCategories: obj.buffCategories.map(
+        (
+          item: any /* eslint-disable-line @typescript-eslint/no-explicit-any */
+        ) => types.BuffCategory.fromDecoded(item)
+      ),
+      reserveSecs: obj.reserveSecs,
+    })
+  }
+
+  static toEncodable(fields: FarmConfigFields) {
+    return {
+      levelDecimals: fields.levelDecimals,
+      buffCategories: fields.buffCategories.map((item) =>
+        types.BuffCategory.toEncodable(item)
+      ),
+      reserveSecs: fields.reserveSecs,
+    }
+  }
+
+  toJSON(): FarmConfigJSON {
+    return {
+      levelDecimals: this.levelDecimals,
+      buffCategories: this.buffCategories.map((item) => item.toJSON()),
+      reserveSecs: this.reserveSecs.toString(),
+    }
+  }
+
+  static fromJSON(obj: FarmConfigJSON): FarmConfig {
+    return new FarmConfig({
+      levelDecimals: obj.levelDecimals,
+      buffCategories: obj.buffCategories.map((item) =>
+        types.BuffCategory.fromJSON(item)
+      ),
+      reserveSecs: new BN(obj.reserveSecs),
+    })
+  }
+
+  toEncodable() {
+    return FarmConfig.toEncodable(this)
+  }
+}
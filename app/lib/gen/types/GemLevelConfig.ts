@@ -0,0 +1,61 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface GemLevelConfigFields {
+  mint: PublicKey
+  level: number
+}
+
+export interface GemLevelConfigJSON {
+  mint: string
+  level: number
+}
+
+export class GemLevelConfig {
+  readonly mint: PublicKey
+  readonly level: number
+
+  constructor(fields: GemLevelConfigFields) {
+    this.mint = fields.mint
+    this.level = fields.level
+  }
+
+  static layout(property?: string) {
+    return borsh.struct([borsh.publicKey("mint"), borsh.u8("level")], property)
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new GemLevelConfig({
+      mint: obj.mint,
+      level: obj.level,
+    })
+  }
+
+  static toEncodable(fields: GemLevelConfigFields) {
+    return {
+      mint: fields.mint,
+      level: fields.level,
+    }
+  }
+
+  toJSON(): GemLevelConfigJSON {
+    return {
+      mint: this.mint.toString(),
+      level: this.level,
+    }
+  }
+
+  static fromJSON(obj: GemLevelConfigJSON): GemLevelConfig {
+    return new GemLevelConfig({
+      mint: new PublicKey(obj.mint),
+      level: obj.level,
+    })
+  }
+
+  toEncodable() {
+    return GemLevelConfig.toEncodable(this)
+  }
+}
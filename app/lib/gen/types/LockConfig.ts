@@ -5,30 +5,39 @@ import * as borsh from "@project-serum/borsh"
 
 export interface LockConfigFields {
   duration: BN
-  bonusFactor: number
+  bonusBps: number
   cooldown: BN
+  earlyUnstake: types.EarlyUnstakeKind
 }
 
 export interface LockConfigJSON {
   duration: string
-  bonusFactor: number
+  bonusBps: number
   cooldown: string
+  earlyUnstake: types.EarlyUnstakeJSON
 }
 
 export class LockConfig {
   readonly duration: BN
-  readonly bonusFactor: number
+  readonly bonusBps: number
   readonly cooldown: BN
+  readonly earlyUnstake: types.EarlyUnstakeKind
 
   constructor(fields: LockConfigFields) {
     this.duration = fields.duration
-    this.bonusFactor = fields.bonusFactor
+    this.bonusBps = fields.bonusBps
     this.cooldown = fields.cooldown
+    this.earlyUnstake = fields.earlyUnstake
   }
 
   static layout(property?: string) {
     return borsh.struct(
-      [borsh.u64("duration"), borsh.u8("bonusFactor"), borsh.u64("cooldown")],
+      [
+        borsh.u64("duration"),
+        borsh.u16("bonusBps"),
+        borsh.u64("cooldown"),
+        types.EarlyUnstake.layout("earlyUnstake"),
+      ],
       property
     )
   }
@@ -37,32 +46,36 @@ export class LockConfig {
   static fromDecoded(obj: any) {
     return new LockConfig({
       duration: obj.duration,
-      bonusFactor: obj.bonusFactor,
+      bonusBps: obj.bonusBps,
       cooldown: obj.cooldown,
+      earlyUnstake: types.EarlyUnstake.fromDecoded(obj.earlyUnstake),
     })
   }
 
   static toEncodable(fields: LockConfigFields) {
     return {
       duration: fields.duration,
-      bonusFactor: fields.bonusFactor,
+      bonusBps: fields.bonusBps,
       cooldown: fields.cooldown,
+      earlyUnstake: fields.earlyUnstake.toEncodable(),
     }
   }
 
   toJSON(): LockConfigJSON {
     return {
       duration: this.duration.toString(),
-      bonusFactor: this.bonusFactor,
+      bonusBps: this.bonusBps,
       cooldown: this.cooldown.toString(),
+      earlyUnstake: this.earlyUnstake.toJSON(),
     }
   }
 
   static fromJSON(obj: LockConfigJSON): LockConfig {
     return new LockConfig({
       duration: new BN(obj.duration),
-      bonusFactor: obj.bonusFactor,
+      bonusBps: obj.bonusBps,
       cooldown: new BN(obj.cooldown),
+      earlyUnstake: types.EarlyUnstake.fromJSON(obj.earlyUnstake),
     })
   }
 
@@ -0,0 +1,67 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface MerkleProofFields {
+  rewardRates: Array<BN>
+  path: Array<Array<number>>
+}
+
+export interface MerkleProofJSON {
+  rewardRates: Array<string>
+  path: Array<Array<number>>
+}
+
+export class MerkleProof {
+  readonly rewardRates: Array<BN>
+  readonly path: Array<Array<number>>
+
+  constructor(fields: MerkleProofFields) {
+    this.rewardRates = fields.rewardRates
+    this.path = fields.path
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [
+        borsh.array(borsh.u128(), 3, "rewardRates"),
+        borsh.vec(borsh.array(borsh.u8(), 32), "path"),
+      ],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new MerkleProof({
+      rewardRates: obj.rewardRates,
+      path: obj.path,
+    })
+  }
+
+  static toEncodable(fields: MerkleProofFields) {
+    return {
+      rewardRates: fields.rewardRates,
+      path: fields.path,
+    }
+  }
+
+  toJSON(): MerkleProofJSON {
+    return {
+      rewardRates: this.rewardRates.map((item) => item.toString()),
+      path: this.path,
+    }
+  }
+
+  static fromJSON(obj: MerkleProofJSON): MerkleProof {
+    return new MerkleProof({
+      rewardRates: obj.rewardRates.map((item) => new BN(item)),
+      path: obj.path,
+    })
+  }
+
+  toEncodable() {
+    return MerkleProof.toEncodable(this)
+  }
+}
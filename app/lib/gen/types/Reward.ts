@@ -5,30 +5,54 @@ import * as borsh from "@project-serum/borsh"
 
 export interface RewardFields {
   mint: PublicKey
-  reserved: number
-  available: number
+  reserved: BN
+  available: BN
+  budgetRate: BN
+  rewardPerWeight: BN
+  totalWeight: BN
+  owed: BN
 }
 
 export interface RewardJSON {
   mint: string
-  reserved: number
-  available: number
+  reserved: string
+  available: string
+  budgetRate: string
+  rewardPerWeight: string
+  totalWeight: string
+  owed: string
 }
 
 export class Reward {
   readonly mint: PublicKey
-  readonly reserved: number
-  readonly available: number
+  readonly reserved: BN
+  readonly available: BN
+  readonly budgetRate: BN
+  readonly rewardPerWeight: BN
+  readonly totalWeight: BN
+  readonly owed: BN
 
   constructor(fields: RewardFields) {
     this.mint = fields.mint
     this.reserved = fields.reserved
     this.available = fields.available
+    this.budgetRate = fields.budgetRate
+    this.rewardPerWeight = fields.rewardPerWeight
+    this.totalWeight = fields.totalWeight
+    this.owed = fields.owed
   }
 
   static layout(property?: string) {
     return borsh.struct(
-      [borsh.publicKey("mint"), borsh.f64("reserved"), borsh.f64("available")],
+      [
+        borsh.publicKey("mint"),
+        borsh.u128("reserved"),
+        borsh.u128("available"),
+        borsh.u128("budgetRate"),
+        borsh.u128("rewardPerWeight"),
+        borsh.u128("totalWeight"),
+        borsh.u128("owed"),
+      ],
       property
     )
   }
@@ -39,6 +63,10 @@ export class Reward {
       mint: obj.mint,
       reserved: obj.reserved,
       available: obj.available,
+      budgetRate: obj.budgetRate,
+      rewardPerWeight: obj.rewardPerWeight,
+      totalWeight: obj.totalWeight,
+      owed: obj.owed,
     })
   }
 
@@ -47,22 +75,34 @@ export class Reward {
       mint: fields.mint,
       reserved: fields.reserved,
       available: fields.available,
+      budgetRate: fields.budgetRate,
+      rewardPerWeight: fields.rewardPerWeight,
+      totalWeight: fields.totalWeight,
+      owed: fields.owed,
     }
   }
 
   toJSON(): RewardJSON {
     return {
       mint: this.mint.toString(),
-      reserved: this.reserved,
-      available: this.available,
+      reserved: this.reserved.toString(),
+      available: this.available.toString(),
+      budgetRate: this.budgetRate.toString(),
+      rewardPerWeight: this.rewardPerWeight.toString(),
+      totalWeight: this.totalWeight.toString(),
+      owed: this.owed.toString(),
     }
   }
 
   static fromJSON(obj: RewardJSON): Reward {
     return new Reward({
       mint: new PublicKey(obj.mint),
-      reserved: obj.reserved,
-      available: obj.available,
+      reserved: new BN(obj.reserved),
+      available: new BN(obj.available),
+      budgetRate: new BN(obj.budgetRate),
+      rewardPerWeight: new BN(obj.rewardPerWeight),
+      totalWeight: new BN(obj.totalWeight),
+      owed: new BN(obj.owed),
     })
   }
 
@@ -0,0 +1,61 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface ScheduleFields {
+  startTs: BN
+  endTs: BN
+}
+
+export interface ScheduleJSON {
+  startTs: string
+  endTs: string
+}
+
+export class Schedule {
+  readonly startTs: BN
+  readonly endTs: BN
+
+  constructor(fields: ScheduleFields) {
+    this.startTs = fields.startTs
+    this.endTs = fields.endTs
+  }
+
+  static layout(property?: string) {
+    return borsh.struct([borsh.u64("startTs"), borsh.u64("endTs")], property)
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new Schedule({
+      startTs: obj.startTs,
+      endTs: obj.endTs,
+    })
+  }
+
+  static toEncodable(fields: ScheduleFields) {
+    return {
+      startTs: fields.startTs,
+      endTs: fields.endTs,
+    }
+  }
+
+  toJSON(): ScheduleJSON {
+    return {
+      startTs: this.startTs.toString(),
+      endTs: this.endTs.toString(),
+    }
+  }
+
+  static fromJSON(obj: ScheduleJSON): Schedule {
+    return new Schedule({
+      startTs: new BN(obj.startTs),
+      endTs: new BN(obj.endTs),
+    })
+  }
+
+  toEncodable() {
+    return Schedule.toEncodable(this)
+  }
+}
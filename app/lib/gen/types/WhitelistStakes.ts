@@ -0,0 +1,64 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@project-serum/borsh"
+
+export interface WhitelistStakesFields {
+  whitelist: PublicKey
+  stakes: number
+}
+
+export interface WhitelistStakesJSON {
+  whitelist: string
+  stakes: number
+}
+
+export class WhitelistStakes {
+  readonly whitelist: PublicKey
+  readonly stakes: number
+
+  constructor(fields: WhitelistStakesFields) {
+    this.whitelist = fields.whitelist
+    this.stakes = fields.stakes
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [borsh.publicKey("whitelist"), borsh.u32("stakes")],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new WhitelistStakes({
+      whitelist: obj.whitelist,
+      stakes: obj.stakes,
+    })
+  }
+
+  static toEncodable(fields: WhitelistStakesFields) {
+    return {
+      whitelist: fields.whitelist,
+      stakes: fields.stakes,
+    }
+  }
+
+  toJSON(): WhitelistStakesJSON {
+    return {
+      whitelist: this.whitelist.toString(),
+      stakes: this.stakes,
+    }
+  }
+
+  static fromJSON(obj: WhitelistStakesJSON): WhitelistStakes {
+    return new WhitelistStakes({
+      whitelist: new PublicKey(obj.whitelist),
+      stakes: obj.stakes,
+    })
+  }
+
+  toEncodable() {
+    return WhitelistStakes.toEncodable(this)
+  }
+}
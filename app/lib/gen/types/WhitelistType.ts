@@ -72,6 +72,52 @@ export class Buff {
   }
 }
 
+export interface CollectionJSON {
+  kind: "Collection"
+}
+
+export class Collection {
+  static readonly discriminator = 3
+  static readonly kind = "Collection"
+  readonly discriminator = 3
+  readonly kind = "Collection"
+
+  toJSON(): CollectionJSON {
+    return {
+      kind: "Collection",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Collection: {},
+    }
+  }
+}
+
+export interface MerkleRootJSON {
+  kind: "MerkleRoot"
+}
+
+export class MerkleRoot {
+  static readonly discriminator = 4
+  static readonly kind = "MerkleRoot"
+  readonly discriminator = 4
+  readonly kind = "MerkleRoot"
+
+  toJSON(): MerkleRootJSON {
+    return {
+      kind: "MerkleRoot",
+    }
+  }
+
+  toEncodable() {
+    return {
+      MerkleRoot: {},
+    }
+  }
+}
+
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 export function fromDecoded(obj: any): types.WhitelistTypeKind {
   if (typeof obj !== "object") {
@@ -87,6 +133,12 @@ export function fromDecoded(obj: any): types.WhitelistTypeKind {
   if ("Buff" in obj) {
     return new Buff()
   }
+  if ("Collection" in obj) {
+    return new Collection()
+  }
+  if ("MerkleRoot" in obj) {
+    return new MerkleRoot()
+  }
 
   throw new Error("Invalid enum object")
 }
@@ -104,6 +156,12 @@ export function fromJSON(
     case "Buff": {
       return new Buff()
     }
+    case "Collection": {
+      return new Collection()
+    }
+    case "MerkleRoot": {
+      return new MerkleRoot()
+    }
   }
 }
 
@@ -112,6 +170,8 @@ export function layout(property?: string) {
     borsh.struct([], "Creator"),
     borsh.struct([], "Mint"),
     borsh.struct([], "Buff"),
+    borsh.struct([], "Collection"),
+    borsh.struct([], "MerkleRoot"),
   ])
   if (property !== undefined) {
     return ret.replicate(property)
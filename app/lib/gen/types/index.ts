@@ -1,18 +1,71 @@
+import * as EmissionMode from "./EmissionMode"
+import * as Custody from "./Custody"
+import * as BuffStacking from "./BuffStacking"
 import * as WhitelistType from "./WhitelistType"
+import * as EarlyUnstake from "./EarlyUnstake"
 
-export { LockConfig } from "./LockConfig"
-export type { LockConfigFields, LockConfigJSON } from "./LockConfig"
 export { Reward } from "./Reward"
 export type { RewardFields, RewardJSON } from "./Reward"
+export { Schedule } from "./Schedule"
+export type { ScheduleFields, ScheduleJSON } from "./Schedule"
+export { BuffCategory } from "./BuffCategory"
+export type { BuffCategoryFields, BuffCategoryJSON } from "./BuffCategory"
+export { FarmConfig } from "./FarmConfig"
+export type { FarmConfigFields, FarmConfigJSON } from "./FarmConfig"
+export { BuffExpiry } from "./BuffExpiry"
+export type { BuffExpiryFields, BuffExpiryJSON } from "./BuffExpiry"
+export { WhitelistStakes } from "./WhitelistStakes"
+export type {
+  WhitelistStakesFields,
+  WhitelistStakesJSON,
+} from "./WhitelistStakes"
 export { Buff } from "./Buff"
 export type { BuffFields, BuffJSON } from "./Buff"
+export { BuffConfig } from "./BuffConfig"
+export type { BuffConfigFields, BuffConfigJSON } from "./BuffConfig"
+export { MerkleProof } from "./MerkleProof"
+export type { MerkleProofFields, MerkleProofJSON } from "./MerkleProof"
+export { LockConfig } from "./LockConfig"
+export type { LockConfigFields, LockConfigJSON } from "./LockConfig"
+export { GemLevelConfig } from "./GemLevelConfig"
+export type { GemLevelConfigFields, GemLevelConfigJSON } from "./GemLevelConfig"
+export { EmissionMode }
+
+export type EmissionModeKind = EmissionMode.Rate | EmissionMode.Pool
+export type EmissionModeJSON = EmissionMode.RateJSON | EmissionMode.PoolJSON
+export { Custody }
+
+export type CustodyKind = Custody.Vault | Custody.Freeze
+export type CustodyJSON = Custody.VaultJSON | Custody.FreezeJSON
+export { BuffStacking }
+
+export type BuffStackingKind =
+  | BuffStacking.Additive
+  | BuffStacking.Multiplicative
+export type BuffStackingJSON =
+  | BuffStacking.AdditiveJSON
+  | BuffStacking.MultiplicativeJSON
 export { WhitelistType }
 
 export type WhitelistTypeKind =
   | WhitelistType.Creator
   | WhitelistType.Mint
   | WhitelistType.Buff
+  | WhitelistType.Collection
+  | WhitelistType.MerkleRoot
 export type WhitelistTypeJSON =
   | WhitelistType.CreatorJSON
   | WhitelistType.MintJSON
   | WhitelistType.BuffJSON
+  | WhitelistType.CollectionJSON
+  | WhitelistType.MerkleRootJSON
+export { EarlyUnstake }
+
+export type EarlyUnstakeKind =
+  | EarlyUnstake.Disabled
+  | EarlyUnstake.Penalty
+  | EarlyUnstake.ForfeitBonus
+export type EarlyUnstakeJSON =
+  | EarlyUnstake.DisabledJSON
+  | EarlyUnstake.PenaltyJSON
+  | EarlyUnstake.ForfeitBonusJSON
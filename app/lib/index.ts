@@ -6,7 +6,7 @@ import {
   SYSVAR_RENT_PUBKEY,
 } from "@solana/web3.js"
 
-import { Farm, StakeReceipt } from "./gen/accounts"
+import { Farm, Farmer, StakeReceipt } from "./gen/accounts"
 import {
   addManager,
   addToWhitelist,
@@ -22,16 +22,17 @@ import {
 } from "./gen/instructions"
 import { debuffPair } from "./gen/instructions/debuffPair"
 import {
+  findBuffRecordAddress,
   findWhitelistProofAddress,
   findFarmAddress,
   findFarmerAddress,
   findFarmManagerAddress,
+  findGemLevelAddress,
   findLockAddress,
   findStakeReceiptAddress,
 } from "./pda"
 import { tryFindCreator } from "./utils"
 import {
-  TripMap,
   ICreateFarm,
   IAddManager,
   ICreateLocks,
@@ -46,28 +47,32 @@ import {
   IUnstake,
 } from "./types"
 
+// Mirrors `utils::PRECISION`, which scales reward rates and accrued rewards.
+export const PRECISION = new BN(10).pow(new BN(12))
+
+// Mirrors `state::MAX_REWARDS`.
+const MAX_REWARDS = 3
+
+// Mirrors the `FarmManager` permission bits.
+export const ManagerPermissions = {
+  Whitelist: 1 << 0,
+  Locks: 1 << 1,
+  Funding: 1 << 2,
+  Pause: 1 << 3,
+  All: 0b1111,
+}
+
 export const StakingProgram = (connection: Connection) => {
   const systemProgram = web3.SystemProgram.programId
   const tokenProgram = utils.token.TOKEN_PROGRAM_ID
   const associatedTokenProgram = utils.token.ASSOCIATED_PROGRAM_ID
   const rent = SYSVAR_RENT_PUBKEY
 
-  const tripMap: TripMap = {
-    None: 0,
-    Drip: 0,
-    Groovy: 0,
-    Geometric: 0,
-    Interdimensional: 0,
-    "2x": 1,
-    "3x": 2,
-    "4x": 3,
-    Nirvana: 4,
-    Space: 5,
-  }
-
   const createCreateFarmInstruction = async ({
     rewardMint,
     authority,
+    mode,
+    custody,
   }: ICreateFarm) => {
     const farm = findFarmAddress({
       authority,
@@ -84,25 +89,31 @@ export const StakingProgram = (connection: Connection) => {
       owner: farm,
     })
 
-    const createFarmIx = createFarm({
-      farm,
-      rewardMint,
-      farmVault,
-      authority,
+    const createFarmIx = createFarm(
+      { mode, custody },
+      {
+        farm,
+        rewardMint,
+        farmVault,
+        authority,
 
-      rent,
-      systemProgram,
-      tokenProgram,
-      associatedTokenProgram,
-    })
+        rent,
+        systemProgram,
+        tokenProgram,
+        associatedTokenProgram,
+      }
+    )
 
-    const addManagerIx = addManager({
-      farm,
-      farmManager,
-      authority,
-      managerAuthority: authority,
-      systemProgram,
-    })
+    const addManagerIx = addManager(
+      { permissions: ManagerPermissions.All },
+      {
+        farm,
+        farmManager,
+        authority,
+        managerAuthority: authority,
+        systemProgram,
+      }
+    )
 
     return { ix: [createFarmIx, addManagerIx] }
   }
@@ -111,19 +122,23 @@ export const StakingProgram = (connection: Connection) => {
     farm,
     farmAuthority,
     newManagerAuthority,
+    permissions,
   }: IAddManager) => {
     const farmManager = findFarmManagerAddress({
       farm,
       authority: newManagerAuthority,
     })
 
-    const ix = addManager({
-      farm,
-      farmManager,
-      managerAuthority: newManagerAuthority,
-      authority: farmAuthority,
-      systemProgram,
-    })
+    const ix = addManager(
+      { permissions },
+      {
+        farm,
+        farmManager,
+        managerAuthority: newManagerAuthority,
+        authority: farmAuthority,
+        systemProgram,
+      }
+    )
 
     return { ix }
   }
@@ -161,22 +176,21 @@ export const StakingProgram = (connection: Connection) => {
   const createFundRewardInstruction = async ({
     amount,
     farm,
+    rewardMint,
     authority,
   }: IFundReward) => {
-    const farmAccount = await Farm.fetch(connection, farm)
-
     const farmManager = findFarmManagerAddress({
       farm,
       authority: authority,
     })
 
     const farmVault = await utils.token.associatedAddress({
-      mint: farmAccount.reward.mint,
+      mint: rewardMint,
       owner: farm,
     })
 
     const managerAta = await utils.token.associatedAddress({
-      mint: farmAccount.reward.mint,
+      mint: rewardMint,
       owner: authority,
     })
 
@@ -185,7 +199,7 @@ export const StakingProgram = (connection: Connection) => {
       {
         farm,
         farmManager,
-        mint: farmAccount.reward.mint,
+        mint: rewardMint,
         farmVault,
         managerAta,
         authority: authority,
@@ -200,8 +214,9 @@ export const StakingProgram = (connection: Connection) => {
     farm,
     creatorOrMint,
     authority,
-    rewardRate,
+    rewardRates,
     whitelistType,
+    buffConfig,
   }: IAddToWhitelist) => {
     const farmManager = findFarmManagerAddress({
       farm,
@@ -209,12 +224,20 @@ export const StakingProgram = (connection: Connection) => {
     })
     const whitelistProof = findWhitelistProofAddress({ creatorOrMint, farm })
 
-    const { tokenAmount, intervalInSeconds } = rewardRate
+    // Rates are per second, scaled by `PRECISION`. Rewards the farm doesn't
+    // have must be left at zero.
+    const rates = Array.from({ length: MAX_REWARDS }, (_, index) => {
+      if (!rewardRates[index]) {
+        return new BN(0)
+      }
+
+      const { tokenAmount, intervalInSeconds } = rewardRates[index]
 
-    const rate = tokenAmount / intervalInSeconds
+      return new BN(tokenAmount).mul(PRECISION).divn(intervalInSeconds)
+    })
 
     const ix = addToWhitelist(
-      { rewardRate: rate, whitelistType },
+      { rewardRates: rates, whitelistType, buffConfig },
       {
         farm,
         farmManager,
@@ -239,13 +262,16 @@ export const StakingProgram = (connection: Connection) => {
     })
     const farmManager = findFarmManagerAddress({ farm, authority })
 
-    const ix = removeFromWhitelist({
-      farm,
-      authority,
-      whitelistProof,
-      systemProgram,
-      farmManager,
-    })
+    const ix = removeFromWhitelist(
+      { force: false },
+      {
+        farm,
+        authority,
+        whitelistProof,
+        systemProgram,
+        farmManager,
+      }
+    )
 
     return { ix }
   }
@@ -308,29 +334,29 @@ export const StakingProgram = (connection: Connection) => {
 
     const stakeReceipt = findStakeReceiptAddress({ farmer, mint })
 
-    console.log("level", tripMap[args.tripEffect])
-    const ix = stake(
-      { amount: args.amount, level: tripMap[args.tripEffect] },
-      {
-        farm,
-        farmer,
+    // The gem's level, if the farm set one, is read from its rarity table.
+    const gemLevel = findGemLevelAddress({ farm, mint })
 
-        gemMint: mint,
-        whitelistProof,
-        farmerVault,
-        gemOwnerAta,
+    const ix = stake(args, {
+      farm,
+      farmer,
 
-        lock,
-        stakeReceipt,
+      gemMint: mint,
+      whitelistProof,
+      gemLevel,
+      farmerVault,
+      gemOwnerAta,
 
-        owner,
+      stakeReceipt,
+      lock,
 
-        rent,
-        systemProgram,
-        tokenProgram,
-        associatedTokenProgram,
-      }
-    )
+      owner,
+
+      rent,
+      systemProgram,
+      tokenProgram,
+      associatedTokenProgram,
+    })
 
     foundMetadata && ix.keys.push(metadata)
 
@@ -344,23 +370,42 @@ export const StakingProgram = (connection: Connection) => {
     const farmer = findFarmerAddress({ farm, owner: authority })
 
     const farmData = await Farm.fetch(connection, farm)
+    const farmerData = await Farmer.fetch(connection, farmer)
 
-    const farmRewardVault = await utils.token.associatedAddress({
-      mint: farmData.reward.mint,
-      owner: farm,
-    })
+    // The whitelists the farmer's stakes are under come first, so the program
+    // can check none of them was delisted.
+    const whitelistMetas: AccountMeta[] = farmerData.whitelists.map(
+      ({ whitelist }) => ({
+        pubkey: whitelist,
+        isSigner: false,
+        isWritable: false,
+      })
+    )
 
-    const farmerRewardVault = await utils.token.associatedAddress({
-      mint: farmData.reward.mint,
-      owner: authority,
-    })
+    // Then a (mint, farm vault, farmer vault) group per reward.
+    const rewardMetas = await Promise.all(
+      farmData.rewards.map(async ({ mint }) => {
+        const farmRewardVault = await utils.token.associatedAddress({
+          mint,
+          owner: farm,
+        })
+
+        const farmerRewardVault = await utils.token.associatedAddress({
+          mint,
+          owner: authority,
+        })
+
+        return [
+          { pubkey: mint, isSigner: false, isWritable: false },
+          { pubkey: farmRewardVault, isSigner: false, isWritable: true },
+          { pubkey: farmerRewardVault, isSigner: false, isWritable: true },
+        ]
+      })
+    )
 
     const ix = claimRewards({
       farm,
       farmer,
-      rewardMint: farmData.reward.mint,
-      farmRewardVault,
-      farmerRewardVault,
       authority,
       rent,
       systemProgram,
@@ -368,6 +413,11 @@ export const StakingProgram = (connection: Connection) => {
       associatedTokenProgram,
     })
 
+    ix.keys.push(
+      ...whitelistMetas,
+      ...rewardMetas.reduce((metas, group) => metas.concat(group))
+    )
+
     return { ix }
   }
 
@@ -386,7 +436,10 @@ export const StakingProgram = (connection: Connection) => {
 
     const stakeReceipt = findStakeReceiptAddress({ farmer, mint })
 
-    const lock = (await StakeReceipt.fetch(connection, stakeReceipt)).lock
+    const { lock, whitelist: whitelistProof } = await StakeReceipt.fetch(
+      connection,
+      stakeReceipt
+    )
 
     const ix = unstake({
       farm,
@@ -394,12 +447,18 @@ export const StakingProgram = (connection: Connection) => {
       gemMint: mint,
       stakeReceipt,
       lock,
+      whitelistProof,
       farmerVault,
       gemOwnerAta,
       owner,
       tokenProgram,
     })
 
+    // The IDL leaves the whitelist read-only, since legacy receipts point to
+    // the default address, but its stake count is updated when there is one.
+    ix.keys.find(({ pubkey }) => pubkey.equals(whitelistProof)).isWritable =
+      !whitelistProof.equals(PublicKey.default)
+
     return { ix }
   }
 
@@ -437,16 +496,14 @@ export const StakingProgram = (connection: Connection) => {
       creatorOrMint: creatorAddress,
     })
 
+    const buffRecord = findBuffRecordAddress({ farmer, buffMint })
+
     const [
-      { mint: mintA, receipt: mintAReceipt, whitelist: mintAWhitelist },
-      { mint: mintB, receipt: mintBReceipt, whitelist: mintBWhitelist },
+      { mint: mintA, receipt: mintAReceipt },
+      { mint: mintB, receipt: mintBReceipt },
     ] = pair.map((mint) => {
       const receipt = findStakeReceiptAddress({ farmer, mint })
-      const whitelist = findWhitelistProofAddress({
-        farm,
-        creatorOrMint: mint,
-      })
-      return { mint, receipt, whitelist }
+      return { mint, receipt }
     })
 
     const ix = buffPair({
@@ -454,9 +511,10 @@ export const StakingProgram = (connection: Connection) => {
       farmer,
 
       buffMint,
+      buffWhitelist,
       buffUserAta,
       buffVault,
-      buffWhitelist,
+      buffRecord,
 
       mintA,
       mintAReceipt,
@@ -495,16 +553,14 @@ export const StakingProgram = (connection: Connection) => {
       owner: farmer,
     })
 
+    const buffRecord = findBuffRecordAddress({ farmer, buffMint })
+
     const [
       { mint: mintA, receipt: mintAReceipt },
       { mint: mintB, receipt: mintBReceipt },
     ] = pair.map((mint) => {
       const receipt = findStakeReceiptAddress({ farmer, mint })
-      const whitelist = findWhitelistProofAddress({
-        farm,
-        creatorOrMint: mint,
-      })
-      return { mint, receipt, whitelist }
+      return { mint, receipt }
     })
 
     const ix = debuffPair({
@@ -512,8 +568,9 @@ export const StakingProgram = (connection: Connection) => {
       farmer,
 
       buffMint,
-      buffVault,
       buffUserAta,
+      buffVault,
+      buffRecord,
 
       mintA,
       mintAReceipt,
@@ -69,6 +69,30 @@ export const findLockAddress = ({
     PROGRAM_ID
   )[0];
 
+export const findGemLevelAddress = ({
+  farm,
+  mint,
+}: {
+  farm: PublicKey;
+  mint: PublicKey;
+}): PublicKey =>
+  utils.publicKey.findProgramAddressSync(
+    [Buffer.from("gem_level"), farm.toBuffer(), mint.toBuffer()],
+    PROGRAM_ID
+  )[0];
+
+export const findBuffRecordAddress = ({
+  farmer,
+  buffMint,
+}: {
+  farmer: PublicKey;
+  buffMint: PublicKey;
+}): PublicKey =>
+  utils.publicKey.findProgramAddressSync(
+    [Buffer.from("buff_record"), farmer.toBuffer(), buffMint.toBuffer()],
+    PROGRAM_ID
+  )[0];
+
 export const findStakeReceiptAddress = ({
   farmer,
   mint,
@@ -2,28 +2,25 @@ import { BN } from "@project-serum/anchor"
 import { PublicKey } from "@solana/web3.js"
 
 import { StakeArgs } from "./gen/instructions/stake"
-import { WhitelistTypeKind } from "./gen/types"
+import {
+  BuffConfigFields,
+  CustodyKind,
+  EmissionModeKind,
+  WhitelistTypeKind,
+} from "./gen/types"
 import { LockConfigFields } from "./gen/types/LockConfig"
 
-export type TripEffect =
-  | "None"
-  | "Groovy"
-  | "Geometric"
-  | "Drip"
-  | "Interdimensional"
-  | "2x"
-  | "3x"
-  | "4x"
-  | "Nirvana"
-  | "Space"
-
-export type TripMap = {
-  [K in TripEffect]: number
+// Rewards paid for `tokenAmount` base units over `intervalInSeconds`.
+export type RewardRate = {
+  tokenAmount: number
+  intervalInSeconds: number
 }
 
 export interface ICreateFarm {
   authority: PublicKey
   rewardMint: PublicKey
+  mode: EmissionModeKind
+  custody: CustodyKind
 }
 
 export interface IAddToWhitelist {
@@ -31,10 +28,9 @@ export interface IAddToWhitelist {
   creatorOrMint: PublicKey
   authority: PublicKey
   whitelistType: WhitelistTypeKind
-  rewardRate: {
-    tokenAmount: number
-    intervalInSeconds: number
-  }
+  // One rate per farm reward, in the order they were added.
+  rewardRates: RewardRate[]
+  buffConfig: BuffConfigFields | null
 }
 
 export interface IRemoveFromWhitelist {
@@ -50,8 +46,9 @@ export interface ICreateLocks {
 }
 
 export interface IFundReward {
-  amount: number
+  amount: BN
   farm: PublicKey
+  rewardMint: PublicKey
   authority: PublicKey
 }
 
@@ -59,6 +56,7 @@ export interface IAddManager {
   farm: PublicKey
   newManagerAuthority: PublicKey
   farmAuthority: PublicKey
+  permissions: number
 }
 
 export interface IInitializeFarmer {
@@ -70,7 +68,7 @@ export interface IStake {
   farm: PublicKey
   mint: PublicKey
   lock: PublicKey
-  args: Omit<StakeArgs, "level"> & { tripEffect: TripEffect }
+  args: StakeArgs
   owner: PublicKey
 }
 
@@ -21,7 +21,9 @@ export const findFarmLocks = async (
   connection: web3.Connection,
   farm: web3.PublicKey
 ): Promise<LockAccount[]> => {
-  const dataSize = 8 + Lock.layout.span;
+  // Mirrors `Lock::LEN`. The layout has no fixed span, since the early unstake
+  // variants differ in size.
+  const dataSize = 8 + 53;
   const filters = [
     { dataSize },
     accountFilter(Lock.discriminator),
@@ -22,6 +22,7 @@ import useStaking, {
 } from "@/hooks/useStaking"
 import { LoadingIcon } from "@/components/icons/LoadingIcon"
 import { web3 } from "@project-serum/anchor"
+import { PRECISION } from "lib"
 import NFTSelectInput from "@/components/NFTSelectInput/NFTSelectInput"
 import { useWallet } from "@solana/wallet-adapter-react"
 import { useRouter } from "next/router"
@@ -136,7 +137,7 @@ export default function Home() {
     notBuffed: StakeReceiptWithMetadata[]
   } = stakeReceipts?.reduce(
     (acc, curr) => {
-      const buffer = curr.buff
+      const buffer = curr.buffs[0]
 
       if (!buffer) {
         acc.notBuffed.push(curr)
@@ -164,11 +165,19 @@ export default function Home() {
     [farmLocks]
   )
 
+  // Rewards accrued up to the farmer's last update, in $OOO.
   const accrued = useMemo(() => {
     return farmerAccount
-      ? ((new Date().getTime() / 1000 - farmerAccount?.lastUpdate.toNumber()) *
-          farmerAccount?.totalRewardRate) /
-          1e2
+      ? farmerAccount.accruedRewards[0].div(PRECISION).toNumber() / 1e2
+      : null
+  }, [farmerAccount])
+
+  const dailyRate = useMemo(() => {
+    return farmerAccount
+      ? farmerAccount.totalRewardRates[0]
+          .muln(86400)
+          .div(PRECISION)
+          .toNumber() / 1e2
       : null
   }, [farmerAccount])
 
@@ -308,7 +317,7 @@ export default function Home() {
                   </Text>
                 ) : null}
 
-                {farmerAccount?.totalRewardRate ? (
+                {dailyRate ? (
                   <Text>
                     Your earning rate:{" "}
                     <b
@@ -316,9 +325,7 @@ export default function Home() {
                         fontSize: "1.6rem",
                       }}
                     >
-                      {((farmerAccount?.totalRewardRate / 1e2) * 86400).toFixed(
-                        2
-                      )}{" "}
+                      {dailyRate.toFixed(2)}{" "}
                     </b>
                     $OOO per day
                   </Text>
@@ -466,7 +473,8 @@ export default function Home() {
                                     84_600 /
                                     7
                                   ).toFixed(0)}{" "}
-                                  weeks | Bonus: +{lock.bonusFactor}% emission
+                                  weeks | Bonus: +{lock.bonusBps / 100}%
+                                  emission
                                 </option>
                               ))}
                           </Select>
@@ -40,4 +40,112 @@ pub enum StakingError {
 
     #[msg("An arithmetic error occurred.")]
     ArithmeticError,
+
+    #[msg("This mint is not one of the farm's rewards.")]
+    InvalidRewardMint,
+
+    #[msg("The farm already distributes the maximum number of rewards.")]
+    TooManyRewards,
+
+    #[msg("This mint is already one of the farm's rewards.")]
+    RewardAlreadyAdded,
+
+    #[msg("Reward accounts must be given as (mint, farm vault, farmer vault) groups.")]
+    InvalidRewardAccounts,
+
+    #[msg("Invalid schedule. Running campaigns can only be extended.")]
+    InvalidSchedule,
+
+    #[msg("This operation is not supported by the farm's emission mode.")]
+    InvalidEmissionMode,
+
+    #[msg("The farm is paused.")]
+    FarmPaused,
+
+    #[msg("Only the proposed authority can accept the farm.")]
+    NotPendingAuthority,
+
+    #[msg("This manager doesn't have the permission to do that.")]
+    MissingPermission,
+
+    #[msg("Penalties can't be over 10000 basis points.")]
+    InvalidPenalty,
+
+    #[msg("This lock doesn't allow unstaking early.")]
+    EarlyUnstakeDisabled,
+
+    #[msg("A running stake can only be topped up with the same lock.")]
+    LockMismatch,
+
+    #[msg("Can't unstake zero or more than what's staked.")]
+    InvalidUnstakeAmount,
+
+    #[msg("Gem accounts must be given as one group per gem, in the documented order.")]
+    InvalidStakeAccounts,
+
+    #[msg("Frozen gems need their master edition and the Token Metadata program as the last remaining accounts.")]
+    InvalidFreezeAccounts,
+
+    #[msg("This operation isn't supported by the farm's custody mode.")]
+    UnsupportedCustody,
+
+    #[msg("Programmable NFTs need their Token Metadata accounts as the first remaining accounts.")]
+    InvalidPnftAccounts,
+
+    #[msg("The merkle proof doesn't match the whitelisted root.")]
+    InvalidMerkleProof,
+
+    #[msg("This whitelist has been delisted.")]
+    WhitelistDelisted,
+
+    #[msg("This whitelist still has active stakes.")]
+    WhitelistHasStakes,
+
+    #[msg("A whitelist can't be delisted in the past.")]
+    InvalidDelistTime,
+
+    #[msg("Invalid buff settings.")]
+    InvalidBuffConfig,
+
+    #[msg("A buff can boost at least one stake and at most its whitelist's arity.")]
+    InvalidBuffCount,
+
+    #[msg("Too many expiring buffs are active for this farmer.")]
+    TooManyExpiringBuffs,
+
+    #[msg("Programmable NFTs can't be used as consumable buffs.")]
+    UnconsumableBuff,
+
+    #[msg("Expiring buffs can't be stacked with other buffs.")]
+    ExpiringBuffNotStackable,
+
+    #[msg("Rewards can't be claimed while a stake that can be unstaked early is locked.")]
+    RewardsLocked,
+
+    #[msg("Gem level accounts must be given in the same order as the levels.")]
+    InvalidGemLevelAccounts,
+
+    #[msg("Reward rates can only be set for the farm's rewards.")]
+    InvalidRewardRates,
+
+    #[msg("This farmer has stakes under too many whitelists.")]
+    TooManyWhitelists,
+
+    #[msg("The farmer's whitelists must be given in order before the reward accounts.")]
+    InvalidWhitelistAccounts,
+
+    #[msg("Stakes under a delisted whitelist must be refreshed first.")]
+    DelistedStakes,
+
+    #[msg("Legacy receipts must follow their farmer, which must belong to the farm.")]
+    UnverifiedReceiptFarmer,
+
+    #[msg("Can't stake more than the wallet holds, or more than one of an NFT.")]
+    InvalidStakeAmount,
+
+    #[msg("Programmable NFTs must be staked and unstaked one at a time.")]
+    UnsupportedPnft,
+
+    #[msg("Managers can only be given the permissions of `FarmManager`.")]
+    InvalidPermissions,
 }
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::*, utils};
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        constraint = farm.pending_authority == Some(new_authority.key())
+            @ StakingError::NotPendingAuthority
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// CHECK: The outgoing authority, which gets back the rent of its manager account.
+    #[account(mut, address = farm.authority)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: The outgoing authority's manager account, if it has one. It's closed so the
+    /// old authority can't keep managing the farm.
+    #[account(
+        mut,
+        seeds = [
+            FarmManager::PREFIX,
+            farm.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub authority_manager: UncheckedAccount<'info>,
+
+    pub new_authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let authority_manager = &ctx.accounts.authority_manager;
+
+    if *authority_manager.owner == crate::ID {
+        utils::close_account(authority_manager, &ctx.accounts.authority)?;
+    }
+
+    let farm = &mut ctx.accounts.farm;
+
+    farm.authority = ctx.accounts.new_authority.key();
+    farm.pending_authority = None;
+
+    msg!("Farm {} is now owned by {}", farm.key(), farm.authority);
+
+    Ok(())
+}
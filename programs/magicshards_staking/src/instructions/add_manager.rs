@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::state::*;
+use crate::{error::StakingError, state::*};
 
 #[derive(Accounts)]
 pub struct AddManager<'info> {
@@ -13,7 +13,7 @@ pub struct AddManager<'info> {
         seeds = [
             FarmManager::PREFIX,
             farm.key().as_ref(),
-            authority.key().as_ref(),
+            manager_authority.key().as_ref(),
         ],
         bump,
     )]
@@ -27,10 +27,13 @@ pub struct AddManager<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<AddManager>) -> Result<()> {
+pub fn handler(ctx: Context<AddManager>, permissions: u8) -> Result<()> {
+    require!(permissions & !FarmManager::ALL == 0, StakingError::InvalidPermissions);
+
     *ctx.accounts.farm_manager = FarmManager {
         farm: ctx.accounts.farm.key(),
         authority: ctx.accounts.manager_authority.key(),
+        permissions,
     };
 
     Ok(())
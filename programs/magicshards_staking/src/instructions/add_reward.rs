@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::*};
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AddReward<'info> {
+    #[account(mut, has_one = authority)]
+    pub farm: Account<'info, Farm>,
+
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+    )]
+    pub farm_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn handler(ctx: Context<AddReward>) -> Result<()> {
+    ctx.accounts.farm.add_reward(ctx.accounts.reward_mint.key())?;
+
+    msg!(
+        "Added reward {} to farm {}",
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.farm.key()
+    );
+
+    Ok(())
+}
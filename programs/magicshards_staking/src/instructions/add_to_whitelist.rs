@@ -1,14 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
 
-use crate::state::*;
+use crate::{ error::StakingError, state::* };
 
 #[derive(Accounts)]
 pub struct AddToWhitelist<'info> {
   pub farm: Account<'info, Farm>,
 
   #[account(
+    has_one = farm,
     has_one = authority,
+    constraint = farm_manager.can(FarmManager::WHITELIST) @ StakingError::MissingPermission,
     seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
     bump
   )]
@@ -23,7 +25,7 @@ pub struct AddToWhitelist<'info> {
   )]
   pub whitelist_proof: Account<'info, WhitelistProof>,
 
-  /// CHECK: Collection creator or mint address.
+  /// CHECK: Collection creator, collection mint, token mint or merkle root.
   pub creator_or_mint: UncheckedAccount<'info>,
 
   #[account(mut)]
@@ -34,19 +36,35 @@ pub struct AddToWhitelist<'info> {
 
 pub fn handler(
   ctx: Context<AddToWhitelist>,
-  reward_rate: f64,
-  whitelist_type: WhitelistType
+  reward_rates: [u128; MAX_REWARDS],
+  whitelist_type: WhitelistType,
+  buff_config: Option<BuffConfig>
 ) -> Result<()> {
   if let WhitelistType::Mint = whitelist_type {
     let data = ctx.accounts.creator_or_mint.try_borrow_mut_data()?;
     Mint::try_deserialize(&mut &**data)?;
   }
 
+  // Only buffs are configurable, and they default to pairs.
+  let buff_config = match (whitelist_type, buff_config) {
+    (WhitelistType::Buff, Some(buff_config)) => buff_config,
+    (_, Some(_)) => {
+      return err!(StakingError::InvalidWhitelistType);
+    }
+    (_, None) => BuffConfig::default(),
+  };
+
+  buff_config.validate()?;
+  ctx.accounts.farm.validate_rates(&reward_rates)?;
+
   *ctx.accounts.whitelist_proof = WhitelistProof {
-    reward_rate,
+    reward_rates,
     ty: whitelist_type,
     farm: ctx.accounts.farm.key(),
     whitelisted_address: ctx.accounts.creator_or_mint.key(),
+    active_stakes: 0,
+    delisted_at: None,
+    buff_config,
   };
 
   Ok(())
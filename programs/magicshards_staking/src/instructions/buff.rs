@@ -0,0 +1,145 @@
+use anchor_lang::{ error::ErrorCode, prelude::* };
+use anchor_spl::{
+  associated_token::AssociatedToken,
+  token::{ Mint, Token, TokenAccount },
+};
+
+use crate::{ error::StakingError, state::*, utils::{ self, now_ts } };
+
+#[derive(Accounts)]
+pub struct BuffStakes<'info> {
+  #[account(mut, constraint = !farm.paused @ StakingError::FarmPaused)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(mut, has_one = farm)]
+  pub farmer: Account<'info, Farmer>,
+
+  // Burned from when the buff is consumable.
+  #[account(mut)]
+  pub buff_mint: Account<'info, Mint>,
+
+  #[account(
+    has_one = farm,
+    constraint = buff_whitelist.ty == WhitelistType::Buff @ StakingError::InvalidWhitelistType
+  )]
+  pub buff_whitelist: Account<'info, WhitelistProof>,
+
+  #[account(
+    mut,
+    associated_token::authority = authority,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_user_ata: Box<Account<'info, TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::authority = farmer,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_vault: Box<Account<'info, TokenAccount>>,
+
+  #[account(
+    init,
+    payer = authority,
+    space = 8 + BuffRecord::LEN,
+    seeds = [BuffRecord::PREFIX, farmer.key().as_ref(), buff_mint.key().as_ref()],
+    bump
+  )]
+  pub buff_record: Box<Account<'info, BuffRecord>>,
+
+  #[account(mut, address = farmer.owner)]
+  pub authority: Signer<'info>,
+
+  pub rent: Sysvar<'info, Rent>,
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, Token>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  // Remaining accounts are the receipts to boost, followed by the buff's metadata and, for
+  // pNFTs, the rest of its Token Metadata accounts.
+}
+
+/// Loads one of `farmer`'s receipts given in the remaining accounts.
+pub(crate) fn read_receipt(
+  account: &AccountInfo,
+  farmer: &Pubkey,
+  program_id: &Pubkey
+) -> Result<StakeReceipt> {
+  require_keys_eq!(*account.owner, *program_id, ErrorCode::AccountOwnedByWrongProgram);
+  require!(account.is_writable, ErrorCode::ConstraintMut);
+
+  let receipt = StakeReceipt::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+  require_keys_eq!(receipt.farmer, *farmer, StakingError::InvalidStakeAccounts);
+
+  Ok(receipt)
+}
+
+/// Boosts `count` of the farmer's stakes with a single buff token, up to the arity of its
+/// whitelist.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, BuffStakes<'info>>, count: u8) -> Result<()> {
+  let count = count as usize;
+
+  require!(
+    count > 0 && count <= ctx.accounts.buff_whitelist.buff_config.arity as usize,
+    StakingError::InvalidBuffCount
+  );
+  require_gte!(ctx.remaining_accounts.len(), count, StakingError::InvalidStakeAccounts);
+
+  let (receipts, remaining_accounts) = ctx.remaining_accounts.split_at(count);
+
+  WhitelistProof::validate(
+    &ctx.accounts.buff_whitelist,
+    &ctx.accounts.buff_mint,
+    ctx.program_id,
+    remaining_accounts.first()
+  )?;
+
+  let now = now_ts()?;
+
+  require!(!ctx.accounts.buff_whitelist.is_delisted(now), StakingError::WhitelistDelisted);
+
+  let farmer = ctx.accounts.farmer.key();
+  let buff_key = ctx.accounts.buff_mint.key();
+  let buff_factor = ctx.accounts.buff_whitelist.buff_factor();
+  let buff_config = ctx.accounts.buff_whitelist.buff_config;
+  let expires_at = buff_config.expires_at(now);
+  let category = ctx.accounts.farm.config.buff_category(buff_config.category)?;
+  let buff = Buff {
+    key: buff_key,
+    category: buff_config.category,
+    stacking: category.stacking,
+    factor: buff_factor,
+    expires_at,
+  };
+  let mut increment = [0; MAX_REWARDS];
+
+  // Each receipt is written back before the next one is read, so passing one twice fails
+  // with `GemAlreadyBuffed`.
+  for stake_receipt in receipts {
+    let mut receipt = read_receipt(stake_receipt, &farmer, ctx.program_id)?;
+
+    let increments = receipt.try_buff(buff, category.max_stack)?;
+
+    for (total, rate) in increment.iter_mut().zip(increments) {
+      *total = total.checked_add(rate).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    let mut data = stake_receipt.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+  }
+
+  utils::buff_tokens!(ctx.accounts).lock(buff_config.consumable, remaining_accounts)?;
+
+  *ctx.accounts.buff_record = BuffRecord {
+    farmer,
+    buff_mint: buff_key,
+    receipts: receipts.iter().map(|receipt| receipt.key()).collect(),
+    factor: buff_factor,
+    buffed_at: now,
+    expires_at,
+    consumed: buff_config.consumable,
+  };
+
+  ctx.accounts.farmer.add_buff(&mut ctx.accounts.farm, buff_key, &increment, expires_at)
+}
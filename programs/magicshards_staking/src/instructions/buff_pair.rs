@@ -1,22 +1,25 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
   associated_token::AssociatedToken,
-  token::{ Mint, Token, TokenAccount, Transfer },
+  token::{ Mint, Token, TokenAccount },
 };
 
-use crate::{ error::StakingError, state::* };
+use crate::{ error::StakingError, state::*, utils::{ self, now_ts } };
 
 #[derive(Accounts)]
 pub struct BuffPair<'info> {
-  #[account(mut)]
+  #[account(mut, constraint = !farm.paused @ StakingError::FarmPaused)]
   pub farm: Account<'info, Farm>,
 
   #[account(mut, has_one = farm)]
   pub farmer: Account<'info, Farmer>,
 
+  // Burned from when the buff is consumable.
+  #[account(mut)]
   pub buff_mint: Account<'info, Mint>,
 
   #[account(
+        has_one = farm,
         constraint =
             buff_whitelist.ty == WhitelistType::Buff
             @ StakingError::InvalidWhitelistType
@@ -38,6 +41,15 @@ pub struct BuffPair<'info> {
   )]
   pub buff_vault: Box<Account<'info, TokenAccount>>,
 
+  #[account(
+    init,
+    payer = authority,
+    space = 8 + BuffRecord::LEN,
+    seeds = [BuffRecord::PREFIX, farmer.key().as_ref(), buff_mint.key().as_ref()],
+    bump
+  )]
+  pub buff_record: Box<Account<'info, BuffRecord>>,
+
   pub mint_a: Account<'info, Mint>,
 
   #[account(
@@ -51,6 +63,8 @@ pub struct BuffPair<'info> {
     )]
   pub mint_a_receipt: Box<Account<'info, StakeReceipt>>,
 
+  // Both receipts would be deserialized separately, so the same one would be buffed twice.
+  #[account(constraint = mint_b.key() != mint_a.key() @ StakingError::InvalidStakeAccounts)]
   pub mint_b: Account<'info, Mint>,
 
   #[account(
@@ -73,42 +87,54 @@ pub struct BuffPair<'info> {
   pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-impl<'info> BuffPair<'info> {
-  pub fn lock_buff(&self) -> Result<()> {
-    let ctx = CpiContext::new(self.token_program.to_account_info(), Transfer {
-      from: self.buff_user_ata.to_account_info(),
-      to: self.buff_vault.to_account_info(),
-      authority: self.authority.to_account_info(),
-    });
-
-    anchor_spl::token::transfer(ctx, 1)
-  }
-}
-
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, BuffPair<'info>>) -> Result<()> {
   WhitelistProof::validate(
     &ctx.accounts.buff_whitelist,
     &ctx.accounts.buff_mint,
     ctx.program_id,
-    ctx.remaining_accounts
+    ctx.remaining_accounts.first()
   )?;
 
-  let buff_key = ctx.accounts.buff_mint.key();
-  let buff_factor = ctx.accounts.buff_whitelist.reward_rate;
-
-  let mint_a_increment = ctx.accounts.mint_a_receipt.try_buff(buff_key, buff_factor)?;
-
-  let mint_b_increment = ctx.accounts.mint_b_receipt.try_buff(buff_key, buff_factor)?;
+  let now = now_ts()?;
 
-  msg!("{:?} {:?}", mint_a_increment, mint_b_increment);
-  ctx.accounts.lock_buff()?;
+  require!(!ctx.accounts.buff_whitelist.is_delisted(now), StakingError::WhitelistDelisted);
+  require_gte!(ctx.accounts.buff_whitelist.buff_config.arity, 2, StakingError::InvalidBuffCount);
 
-  let increment = mint_a_increment + mint_b_increment;
-
-  ctx.accounts.farmer.update_accrued_rewards()?;
-
-  // farm.reward.try_reserve(increment)?;
-  ctx.accounts.farmer.increase_reward_rate(increment)?;
+  let buff_key = ctx.accounts.buff_mint.key();
+  let buff_factor = ctx.accounts.buff_whitelist.buff_factor();
+  let buff_config = ctx.accounts.buff_whitelist.buff_config;
+  let expires_at = buff_config.expires_at(now);
+  let category = ctx.accounts.farm.config.buff_category(buff_config.category)?;
+  let buff = Buff {
+    key: buff_key,
+    category: buff_config.category,
+    stacking: category.stacking,
+    factor: buff_factor,
+    expires_at,
+  };
+
+  let mint_a_increment = ctx.accounts.mint_a_receipt.try_buff(buff, category.max_stack)?;
+  let mint_b_increment = ctx.accounts.mint_b_receipt.try_buff(buff, category.max_stack)?;
+
+  utils::buff_tokens!(ctx.accounts).lock(buff_config.consumable, ctx.remaining_accounts)?;
+
+  *ctx.accounts.buff_record = BuffRecord {
+    farmer: ctx.accounts.farmer.key(),
+    buff_mint: buff_key,
+    receipts: vec![ctx.accounts.mint_a_receipt.key(), ctx.accounts.mint_b_receipt.key()],
+    factor: buff_factor,
+    buffed_at: now,
+    expires_at,
+    consumed: buff_config.consumable,
+  };
+
+  let mut increment = [0; MAX_REWARDS];
+
+  for (index, increment) in increment.iter_mut().enumerate() {
+    *increment = mint_a_increment[index]
+      .checked_add(mint_b_increment[index])
+      .ok_or(StakingError::ArithmeticError)?;
+  }
 
-  Ok(())
+  ctx.accounts.farmer.add_buff(&mut ctx.accounts.farm, buff_key, &increment, expires_at)
 }
\ No newline at end of file
@@ -1,34 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ associated_token::AssociatedToken, token::{ Mint, Token, TokenAccount } };
+use anchor_spl::{
+  associated_token::{ get_associated_token_address, AssociatedToken },
+  token::{ Mint, Token, TokenAccount },
+};
 
-use crate::{ state::*, utils };
+use crate::{ error::StakingError, state::*, utils };
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
-  #[account(mut)]
+  #[account(mut, constraint = !farm.paused @ StakingError::FarmPaused)]
   pub farm: Account<'info, Farm>,
 
   #[account(mut, has_one = farm)]
   pub farmer: Account<'info, Farmer>,
 
-  #[account(address = farm.reward.mint)]
-  pub reward_mint: Account<'info, Mint>,
-
-  #[account(
-        mut,
-        associated_token::mint = reward_mint,
-        associated_token::authority = farm,
-    )]
-  pub farm_reward_vault: Account<'info, TokenAccount>,
-
-  #[account(
-    init_if_needed,
-    payer = authority,
-    associated_token::mint = reward_mint,
-    associated_token::authority = authority
-  )]
-  pub farmer_reward_vault: Account<'info, TokenAccount>,
-
+  // Pays for the farmer's reward accounts that don't exist yet.
   #[account(mut, address = farmer.owner)]
   pub authority: Signer<'info>,
 
@@ -36,27 +22,88 @@ pub struct ClaimRewards<'info> {
   pub system_program: Program<'info, System>,
   pub token_program: Program<'info, Token>,
   pub associated_token_program: Program<'info, AssociatedToken>,
+  // Remaining accounts are the proofs of the farmer's `whitelists`, in order, then
+  // (reward_mint, farm_reward_vault, farmer_reward_vault) groups, one for each reward being
+  // claimed. The farmer's vault is created if it's an empty ATA.
 }
 
 impl<'info> ClaimRewards<'info> {
-  pub fn transfer_reward(&self, amount: f64) -> Result<()> {
+  pub fn transfer_reward(
+    &self,
+    farm_reward_vault: AccountInfo<'info>,
+    farmer_reward_vault: AccountInfo<'info>,
+    amount: u64
+  ) -> Result<()> {
     let cpi_ctx = utils::transfer_spl_ctx(
-      self.farm_reward_vault.to_account_info(),
-      self.farmer_reward_vault.to_account_info(),
+      farm_reward_vault,
+      farmer_reward_vault,
       self.farm.to_account_info(),
       self.token_program.to_account_info()
     );
 
-    anchor_spl::token::transfer(cpi_ctx.with_signer(&[&self.farm.seeds()]), amount as u64)
+    anchor_spl::token::transfer(cpi_ctx.with_signer(&[&self.farm.seeds()]), amount)
   }
 }
 
-pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
-  let reward = ctx.accounts.farmer.claim_accrued()?;
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>) -> Result<()> {
+  let now = utils::now_ts()?;
+  let whitelists = ctx.accounts.farmer.whitelists.len().min(ctx.remaining_accounts.len());
+  let (proofs, reward_accounts) = ctx.remaining_accounts.split_at(whitelists);
+
+  require!(
+    !reward_accounts.is_empty() && reward_accounts.len() % 3 == 0,
+    StakingError::InvalidRewardAccounts
+  );
+  require!(ctx.accounts.farmer.can_claim(now), StakingError::RewardsLocked);
 
-  ctx.accounts.transfer_reward(reward)?;
+  ctx.accounts.farmer.check_delistings(proofs, ctx.program_id, now)?;
 
-  msg!("Claimed {} tokens", reward);
+  for group in reward_accounts.chunks(3) {
+    let reward_mint = Account::<Mint>::try_from(&group[0])?;
+    let farm_reward_vault = Account::<TokenAccount>::try_from(&group[1])?;
+
+    if group[2].data_is_empty() {
+      require_keys_eq!(
+        group[2].key(),
+        get_associated_token_address(&ctx.accounts.authority.key(), &reward_mint.key()),
+        StakingError::InvalidRewardAccounts
+      );
+
+      utils::create_vault!(
+        ctx.accounts,
+        ctx.accounts.authority.to_account_info(),
+        group[2].clone(),
+        ctx.accounts.authority.to_account_info(),
+        group[0].clone()
+      )?;
+    }
+
+    let farmer_reward_vault = Account::<TokenAccount>::try_from(&group[2])?;
+    let farm = ctx.accounts.farm.key();
+    let index = ctx.accounts.farm.reward_index(&reward_mint.key())?;
+
+    require_keys_eq!(
+      farm_reward_vault.key(),
+      get_associated_token_address(&farm, &reward_mint.key()),
+      StakingError::InvalidRewardAccounts
+    );
+    require_keys_eq!(farmer_reward_vault.mint, reward_mint.key(), StakingError::InvalidRewardAccounts);
+    require_keys_eq!(
+      farmer_reward_vault.owner,
+      ctx.accounts.authority.key(),
+      StakingError::InvalidRewardAccounts
+    );
+
+    let reward = ctx.accounts.farmer.claim_accrued(&mut ctx.accounts.farm, index)?;
+
+    ctx.accounts.transfer_reward(
+      farm_reward_vault.to_account_info(),
+      farmer_reward_vault.to_account_info(),
+      reward
+    )?;
+
+    msg!("Claimed {} {} tokens", reward, reward_mint.key());
+  }
 
   Ok(())
-}
\ No newline at end of file
+}
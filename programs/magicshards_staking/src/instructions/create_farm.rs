@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token::*};
 
-use crate::state::*;
+use crate::{state::*, utils::now_ts};
 
 #[derive(Accounts)]
 pub struct CreateFarm<'info> {
@@ -34,12 +34,24 @@ pub struct CreateFarm<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler(ctx: Context<CreateFarm>) -> Result<()> {
+pub fn handler(ctx: Context<CreateFarm>, mode: EmissionMode, custody: Custody) -> Result<()> {
     let reward = Reward::new(ctx.accounts.reward_mint.key());
+    let now = now_ts()?;
 
     *ctx.accounts.farm = Farm {
-        reward,
+        rewards: vec![reward],
+        mode,
+        custody,
+        schedule: Schedule::ALWAYS,
+        // Same reading the clock would have if the window had been open since 0.
+        emission_seconds: now,
+        emission_updated_at: now,
+        pool_checkpoint: now,
         authority: ctx.accounts.authority.key(),
+        pending_authority: None,
+        creator: ctx.accounts.authority.key(),
+        paused: false,
+        config: FarmConfig::default(),
         bump: [*ctx.bumps.get("farm").unwrap()],
     };
 
@@ -1,12 +1,18 @@
 use anchor_lang::{prelude::*, Discriminator};
 
-use crate::{state::*, utils};
+use crate::{error::StakingError, state::*, utils};
 
 #[derive(Accounts)]
 pub struct CreateLocks<'info> {
     pub farm: Account<'info, Farm>,
 
-    #[account(has_one = farm, has_one = authority)]
+    #[account(
+        has_one = farm,
+        has_one = authority,
+        constraint = farm_manager.can(FarmManager::LOCKS) @ StakingError::MissingPermission,
+        seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+        bump,
+    )]
     pub farm_manager: Account<'info, FarmManager>,
 
     pub authority: Signer<'info>,
@@ -19,8 +25,9 @@ pub struct CreateLocks<'info> {
 #[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
 pub struct LockConfig {
     pub duration: u64,
-    pub bonus_factor: u8,
+    pub bonus_bps: u16,
     pub cooldown: u64,
+    pub early_unstake: EarlyUnstake,
 }
 
 pub fn handler<'info>(
@@ -33,11 +40,16 @@ pub fn handler<'info>(
     for LockConfig {
         duration,
         cooldown,
-        bonus_factor,
+        bonus_bps,
+        early_unstake,
     } in lock_configs
     {
         let lock = next_account_info(&mut remaining_accs)?;
 
+        if let EarlyUnstake::Penalty { bps } = early_unstake {
+            require_gte!(utils::BPS, bps as u128, StakingError::InvalidPenalty);
+        }
+
         // Calculate bump.
         let (_, bump) = Pubkey::find_program_address(
             &[
@@ -70,7 +82,8 @@ pub fn handler<'info>(
             farm,
             duration,
             cooldown,
-            bonus_factor,
+            bonus_bps,
+            early_unstake,
         };
 
         // Write discriminator.
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ Mint, Token, TokenAccount };
+
+use crate::{
+  error::StakingError,
+  instructions::buff::read_receipt,
+  state::*,
+  utils,
+};
+
+#[derive(Accounts)]
+pub struct DebuffStakes<'info> {
+  #[account(mut)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(mut, has_one = farm)]
+  pub farmer: Account<'info, Farmer>,
+
+  pub buff_mint: Account<'info, Mint>,
+
+  #[account(
+    mut,
+    associated_token::authority = authority,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_user_ata: Box<Account<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::authority = farmer,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_vault: Box<Account<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    close = authority,
+    has_one = farmer,
+    has_one = buff_mint,
+    seeds = [BuffRecord::PREFIX, farmer.key().as_ref(), buff_mint.key().as_ref()],
+    bump
+  )]
+  pub buff_record: Box<Account<'info, BuffRecord>>,
+
+  #[account(mut, address = farmer.owner)]
+  pub authority: Signer<'info>,
+
+  pub token_program: Program<'info, Token>,
+  // Remaining accounts are the receipts in the buff record, in the same order, followed by
+  // the buff's Token Metadata accounts if it's a pNFT.
+}
+
+/// Reverts a buff given with `buff` on every stake it boosted, and sends the token back
+/// unless it was consumed.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DebuffStakes<'info>>) -> Result<()> {
+  let recorded = &ctx.accounts.buff_record.receipts;
+
+  require_gte!(ctx.remaining_accounts.len(), recorded.len(), StakingError::InvalidStakeAccounts);
+
+  let (receipts, remaining_accounts) = ctx.remaining_accounts.split_at(recorded.len());
+  let farmer = ctx.accounts.farmer.key();
+  let buff_key = ctx.accounts.buff_mint.key();
+  let mut decrement = [0; MAX_REWARDS];
+
+  for (stake_receipt, recorded) in receipts.iter().zip(recorded) {
+    require_keys_eq!(stake_receipt.key(), *recorded, StakingError::InvalidStakeAccounts);
+
+    let mut receipt = read_receipt(stake_receipt, &farmer, ctx.program_id)?;
+
+    for (total, rate) in decrement.iter_mut().zip(receipt.try_debuff(&buff_key)?) {
+      *total = total.checked_add(rate).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    let mut data = stake_receipt.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+  }
+
+  if !ctx.accounts.buff_record.consumed {
+    utils::buff_tokens!(ctx.accounts).unlock(&ctx.accounts.farmer.seeds(), remaining_accounts)?;
+  }
+
+  let expires = ctx.accounts.buff_record.expires_at.is_some();
+
+  ctx.accounts.farmer.remove_buff(&mut ctx.accounts.farm, &buff_key, &decrement, expires)
+}
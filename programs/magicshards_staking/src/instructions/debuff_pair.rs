@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{ Mint, Token, TokenAccount, Transfer };
+use anchor_spl::token::{ Mint, Token, TokenAccount };
 
-use crate::{ state::* };
+use crate::{ error::StakingError, state::*, utils };
 
 #[derive(Accounts)]
 pub struct DebuffPair<'info> {
@@ -27,11 +27,19 @@ pub struct DebuffPair<'info> {
     )]
   pub buff_vault: Box<Account<'info, TokenAccount>>,
 
+  /// CHECK: The buff's record, read by hand and closed with `BuffRecord::take`.
+  #[account(
+    mut,
+    seeds = [BuffRecord::PREFIX, farmer.key().as_ref(), buff_mint.key().as_ref()],
+    bump
+  )]
+  pub buff_record: UncheckedAccount<'info>,
+
   pub mint_a: Account<'info, Mint>,
 
   #[account(
         mut,
-        constraint = matches!(mint_a_receipt.buff, Some(Buff{ key: buff_mint, ..})),
+        constraint = mint_a_receipt.has_buff(&buff_mint.key()) @ StakingError::GemNotBuffed,
         seeds = [
           StakeReceipt::PREFIX,
           farmer.key().as_ref(),
@@ -41,11 +49,12 @@ pub struct DebuffPair<'info> {
     )]
   pub mint_a_receipt: Box<Account<'info, StakeReceipt>>,
 
+  #[account(constraint = mint_b.key() != mint_a.key() @ StakingError::InvalidStakeAccounts)]
   pub mint_b: Account<'info, Mint>,
 
   #[account(
         mut,
-        constraint = matches!(mint_b_receipt.buff, Some(Buff{ key: buff_mint, ..})),
+        constraint = mint_b_receipt.has_buff(&buff_mint.key()) @ StakingError::GemNotBuffed,
         seeds = [
           StakeReceipt::PREFIX,
           farmer.key().as_ref(),
@@ -55,34 +64,55 @@ pub struct DebuffPair<'info> {
     )]
   pub mint_b_receipt: Box<Account<'info, StakeReceipt>>,
 
-  #[account(address = farmer.owner)]
+  // Pays for the token record when the buff is a pNFT.
+  #[account(mut, address = farmer.owner)]
   pub authority: Signer<'info>,
 
   pub token_program: Program<'info, Token>,
 }
 
 impl<'info> DebuffPair<'info> {
-  pub fn unlock_buff(&self) -> Result<()> {
-    let ctx = CpiContext::new(self.token_program.to_account_info(), Transfer {
-      to: self.buff_user_ata.to_account_info(),
-      from: self.buff_vault.to_account_info(),
-      authority: self.farmer.to_account_info(),
-    });
-
-    anchor_spl::token::transfer(ctx.with_signer(&[&self.farmer.seeds()]), 1)
+  /// Closes the buff record, if there is one, after checking it's for these two receipts.
+  pub fn take_record(&self, program_id: &Pubkey) -> Result<Option<BuffRecord>> {
+    let record = BuffRecord::take(
+      &self.buff_record.to_account_info(),
+      &self.authority.to_account_info(),
+      program_id
+    )?;
+
+    if let Some(record) = &record {
+      let receipts = [self.mint_a_receipt.key(), self.mint_b_receipt.key()];
+
+      require!(record.receipts == receipts, StakingError::InvalidStakeAccounts);
+    }
+
+    Ok(record)
   }
 }
 
-pub fn handler(ctx: Context<DebuffPair>) -> Result<()> {
-  let mint_a_decrement = ctx.accounts.mint_a_receipt.try_debuff()?;
-  let mint_b_decrement = ctx.accounts.mint_b_receipt.try_debuff()?;
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DebuffPair<'info>>) -> Result<()> {
+  let buff_key = ctx.accounts.buff_mint.key();
+  let mint_a_decrement = ctx.accounts.mint_a_receipt.try_debuff(&buff_key)?;
+  let mint_b_decrement = ctx.accounts.mint_b_receipt.try_debuff(&buff_key)?;
 
-  ctx.accounts.unlock_buff()?;
+  // Pairs buffed before records existed were neither consumable nor expiring.
+  let record = ctx.accounts.take_record(ctx.program_id)?;
+  let consumed = record.as_ref().map_or(false, |record| record.consumed);
+  let expires = record.as_ref().map_or(false, |record| record.expires_at.is_some());
 
-  let decrement = mint_a_decrement + mint_b_decrement;
+  if !consumed {
+    let farmer_seeds = ctx.accounts.farmer.seeds();
 
-  ctx.accounts.farmer.update_accrued_rewards()?;
-  ctx.accounts.farmer.decrease_reward_rate(decrement)?;
+    utils::buff_tokens!(ctx.accounts).unlock(&farmer_seeds, ctx.remaining_accounts)?;
+  }
+
+  let mut decrement = [0; MAX_REWARDS];
 
-  Ok(())
-}
\ No newline at end of file
+  for (index, decrement) in decrement.iter_mut().enumerate() {
+    *decrement = mint_a_decrement[index]
+      .checked_add(mint_b_decrement[index])
+      .ok_or(StakingError::ArithmeticError)?;
+  }
+
+  ctx.accounts.farmer.remove_buff(&mut ctx.accounts.farm, &buff_key, &decrement, expires)
+}
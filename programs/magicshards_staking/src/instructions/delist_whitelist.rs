@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::*, utils::now_ts };
+
+#[derive(Accounts)]
+pub struct DelistWhitelist<'info> {
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    has_one = farm,
+    has_one = authority,
+    constraint = farm_manager.can(FarmManager::WHITELIST) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
+  pub farm_manager: Account<'info, FarmManager>,
+
+  #[account(mut, has_one = farm)]
+  pub whitelist_proof: Account<'info, WhitelistProof>,
+
+  pub authority: Signer<'info>,
+}
+
+/// Stops new stakes under the whitelist from `at_ts`, and running ones from earning. Running
+/// stakes can't be claimed or unstaked until `refresh_receipt` takes back what they earned
+/// since. A pending delisting can be moved, but not one that already took effect.
+pub fn handler(ctx: Context<DelistWhitelist>, at_ts: u64) -> Result<()> {
+  let now = now_ts()?;
+  let whitelist_proof = &mut ctx.accounts.whitelist_proof;
+
+  require_gte!(at_ts, now, StakingError::InvalidDelistTime);
+  require!(!whitelist_proof.is_delisted(now), StakingError::WhitelistDelisted);
+
+  whitelist_proof.delisted_at = Some(at_ts);
+
+  msg!("Delisting {} at {}", whitelist_proof.whitelisted_address, at_ts);
+
+  Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::*, utils::{ self, now_ts } };
+
+/// Unstakes before the lock is over, forfeiting part of what the stake earned as configured
+/// by the lock. Once the lock is over it's the same as `unstake`.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
+  let now = now_ts()?;
+  let lock_end = ctx.accounts.lock_end()?;
+
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+
+  if now < lock_end {
+    let Unstake { farm, farmer, stake_receipt, lock, .. } = &mut *ctx.accounts;
+
+    stake_receipt.settle_earnings(farmer)?;
+
+    let mut penalties = [0; MAX_REWARDS];
+
+    for (index, penalty) in penalties.iter_mut().enumerate() {
+      // Claims are blocked while the lock runs, so the earnings are normally all still
+      // accrued. Debts the farm couldn't cover yet are the exception.
+      let earned = stake_receipt.earned[index].min(farmer.accrued_rewards[index]);
+
+      *penalty = match lock.early_unstake {
+        EarlyUnstake::Disabled => {
+          return err!(StakingError::EarlyUnstakeDisabled);
+        }
+        EarlyUnstake::Penalty { bps } => {
+          utils::mul_div(earned, bps as u128, utils::BPS)?
+        }
+        // Without the bonus, the stake would have earned `earned * BPS / (BPS + bonus)`.
+        EarlyUnstake::ForfeitBonus => {
+          let bonus_bps = utils::BPS + (lock.bonus_bps as u128);
+          earned - utils::mul_div(earned, utils::BPS, bonus_bps)?
+        }
+      };
+    }
+
+    // What's still reserved for the rest of the lock is given back by `close_stake`.
+    farmer.forfeit(&mut farm.rewards, &penalties, &[0; MAX_REWARDS])?;
+
+    msg!("Unstaked early, forfeiting {:?} rewards.", penalties);
+  }
+
+  ctx.accounts.close_stake(now, ctx.program_id, ctx.remaining_accounts)
+}
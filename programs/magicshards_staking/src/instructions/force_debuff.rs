@@ -0,0 +1,375 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ Mint, Token, TokenAccount };
+
+use crate::{
+  error::StakingError,
+  instructions::buff::read_receipt,
+  state::*,
+  utils,
+};
+
+#[derive(Accounts)]
+pub struct ForceDebuff<'info> {
+  #[account(mut)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(mut, has_one = farm)]
+  pub farmer: Account<'info, Farmer>,
+
+  pub buff_mint: Account<'info, Mint>,
+
+  #[account(
+    mut,
+    associated_token::authority = authority,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_user_ata: Box<Account<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::authority = farmer,
+    associated_token::mint = buff_mint
+  )]
+  pub buff_vault: Box<Account<'info, TokenAccount>>,
+
+  /// CHECK: The buff's record, read by hand and closed with `BuffRecord::take`.
+  #[account(
+    mut,
+    seeds = [BuffRecord::PREFIX, farmer.key().as_ref(), buff_mint.key().as_ref()],
+    bump
+  )]
+  pub buff_record: UncheckedAccount<'info>,
+
+  #[account(mut, address = farmer.owner)]
+  pub authority: Signer<'info>,
+
+  pub token_program: Program<'info, Token>,
+  // Remaining accounts are the receipts in the buff record, in the same order, or the two
+  // receipts of a legacy pair, followed by the buff's Token Metadata accounts if it's a pNFT.
+}
+
+/// What taking a buff off its receipts changes for the farmer.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ForcedDebuff {
+  // Boost of the running stakes, which the farmer still earns.
+  pub running: [u128; MAX_REWARDS],
+  // Boost of the stakes that aren't running anymore. The farmer dropped it with their rates
+  // when they were closed.
+  pub stopped: [u128; MAX_REWARDS],
+  pub expires: bool,
+}
+
+/// Takes the buff from `buff_key` off every receipt holding it, whatever their state.
+/// Receipts that don't hold it are left as they are.
+pub(crate) fn settle(receipts: &mut [StakeReceipt], buff_key: &Pubkey) -> Result<ForcedDebuff> {
+  let mut settled = ForcedDebuff::default();
+
+  for receipt in receipts.iter_mut() {
+    let buff = match receipt.buffs.iter().find(|buff| buff.key == *buff_key) {
+      Some(buff) => *buff,
+      None => continue,
+    };
+
+    let totals = if receipt.is_running() { &mut settled.running } else { &mut settled.stopped };
+
+    for (total, rate) in totals.iter_mut().zip(receipt.force_debuff(buff_key)?) {
+      *total = total.checked_add(rate).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    settled.expires |= buff.expires_at.is_some();
+  }
+
+  Ok(settled)
+}
+
+/// Checks the receipts given for the buff: the ones in its record, in the same order, or two
+/// different receipts holding it for a legacy pair without a record.
+pub(crate) fn check_receipts(
+  record: Option<&BuffRecord>,
+  keys: &[Pubkey],
+  receipts: &[StakeReceipt],
+  buff_key: &Pubkey
+) -> Result<()> {
+  for (index, (key, receipt)) in keys.iter().zip(receipts).enumerate() {
+    match record {
+      Some(record) => {
+        require_keys_eq!(*key, record.receipts[index], StakingError::InvalidStakeAccounts);
+      }
+      // Nothing else says which stakes a legacy pair boosted, so both must hold the buff.
+      None => {
+        require!(receipt.has_buff(buff_key), StakingError::GemNotBuffed);
+        require!(!keys[..index].contains(key), StakingError::InvalidStakeAccounts);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Recovers a buff token whatever the state of the stakes it boosted, for when `debuff`
+/// can't because one of them isn't running anymore. Only the boost the farmer still earns
+/// comes off its rates.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ForceDebuff<'info>>) -> Result<()> {
+  let record = BuffRecord::take(
+    &ctx.accounts.buff_record.to_account_info(),
+    &ctx.accounts.authority.to_account_info(),
+    ctx.program_id
+  )?;
+  // Buffs applied before records existed were always pairs.
+  let count = record.as_ref().map_or(2, |record| record.receipts.len());
+
+  require_gte!(ctx.remaining_accounts.len(), count, StakingError::InvalidStakeAccounts);
+
+  let (receipt_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(count);
+  let farmer = ctx.accounts.farmer.key();
+  let buff_key = ctx.accounts.buff_mint.key();
+  let keys: Vec<Pubkey> = receipt_accounts.iter().map(|account| account.key()).collect();
+  let mut receipts = receipt_accounts
+    .iter()
+    .map(|account| read_receipt(account, &farmer, ctx.program_id))
+    .collect::<Result<Vec<_>>>()?;
+
+  check_receipts(record.as_ref(), &keys, &receipts, &buff_key)?;
+
+  let settled = settle(&mut receipts, &buff_key)?;
+
+  for (stake_receipt, receipt) in receipt_accounts.iter().zip(&receipts) {
+    let mut data = stake_receipt.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+  }
+
+  let consumed = record.as_ref().map_or(false, |record| record.consumed);
+  let expires = settled.expires || record.as_ref().map_or(false, |r| r.expires_at.is_some());
+
+  if expires {
+    ctx.accounts.farmer.untrack_buff_boost(&buff_key, &settled.stopped);
+  }
+
+  if !consumed {
+    utils::buff_tokens!(ctx.accounts).unlock(&ctx.accounts.farmer.seeds(), remaining_accounts)?;
+  }
+
+  ctx.accounts.farmer.remove_buff(&mut ctx.accounts.farm, &buff_key, &settled.running, expires)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ state::fixtures, utils::PRECISION };
+
+  const X2: u128 = 2 * PRECISION;
+
+  fn receipt(running: bool) -> StakeReceipt {
+    StakeReceipt { end_ts: if running { None } else { Some(100) }, ..fixtures::receipt(PRECISION) }
+  }
+
+  /// Buffs `receipt` as `buff` would, then stops it if it shouldn't be running.
+  fn buffed(running: bool, buff: Buff) -> StakeReceipt {
+    let mut receipt = receipt(true);
+
+    receipt.try_buff(buff, 1).unwrap();
+    receipt.end_ts = if running { None } else { Some(100) };
+
+    receipt
+  }
+
+  fn buff(expires_at: Option<u64>) -> Buff {
+    Buff { expires_at, ..fixtures::buff(0, BuffStacking::Multiplicative, X2) }
+  }
+
+  fn farmer(expiry: Option<BuffExpiry>) -> Farmer {
+    Farmer { buff_expiries: expiry.into_iter().collect(), ..fixtures::farmer(0) }
+  }
+
+  fn record(receipts: &[Pubkey], consumed: bool) -> BuffRecord {
+    BuffRecord {
+      farmer: Pubkey::new_unique(),
+      buff_mint: Pubkey::new_unique(),
+      receipts: receipts.to_vec(),
+      factor: X2,
+      buffed_at: 0,
+      expires_at: None,
+      consumed,
+    }
+  }
+
+  fn assert_unbuffed(receipt: &StakeReceipt) {
+    assert!(!receipt.is_buffed());
+    assert_eq!(receipt.reward_rates, [PRECISION, 0, 0]);
+  }
+
+  #[test]
+  fn running_stake_drops_its_boost() {
+    let buff = buff(None);
+    let mut receipts = [buffed(true, buff)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled, ForcedDebuff {
+      running: [PRECISION, 0, 0],
+      stopped: [0; MAX_REWARDS],
+      expires: false,
+    });
+    assert_unbuffed(&receipts[0]);
+  }
+
+  #[test]
+  fn stopped_stake_leaves_the_farmer_alone() {
+    let buff = buff(None);
+    let mut receipts = [buffed(false, buff)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled, ForcedDebuff {
+      running: [0; MAX_REWARDS],
+      stopped: [PRECISION, 0, 0],
+      expires: false,
+    });
+    assert_unbuffed(&receipts[0]);
+    assert!(!receipts[0].is_running());
+  }
+
+  #[test]
+  fn running_stake_with_expiring_buff() {
+    let buff = buff(Some(50));
+    let mut receipts = [buffed(true, buff)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled, ForcedDebuff {
+      running: [PRECISION, 0, 0],
+      stopped: [0; MAX_REWARDS],
+      expires: true,
+    });
+    assert_unbuffed(&receipts[0]);
+  }
+
+  #[test]
+  fn stopped_stake_with_expiring_buff() {
+    let buff = buff(Some(50));
+    let mut receipts = [buffed(false, buff)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled, ForcedDebuff {
+      running: [0; MAX_REWARDS],
+      stopped: [PRECISION, 0, 0],
+      expires: true,
+    });
+    assert_unbuffed(&receipts[0]);
+  }
+
+  #[test]
+  fn mixed_stakes_are_split() {
+    let buff = buff(None);
+    let mut receipts = [buffed(true, buff), buffed(false, buff), buffed(true, buff)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled.running, [2 * PRECISION, 0, 0]);
+    assert_eq!(settled.stopped, [PRECISION, 0, 0]);
+    receipts.iter().for_each(assert_unbuffed);
+  }
+
+  #[test]
+  fn stakes_without_the_buff_are_skipped() {
+    let buff = buff(None);
+    let other = self::buff(None);
+    let mut receipts = [receipt(true), receipt(false), buffed(true, other)];
+
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled, ForcedDebuff::default());
+    assert_unbuffed(&receipts[0]);
+    assert_unbuffed(&receipts[1]);
+    assert!(receipts[2].has_buff(&other.key));
+    assert_eq!(receipts[2].reward_rates, [X2, 0, 0]);
+  }
+
+  #[test]
+  fn other_buffs_stay_on_a_stopped_stake() {
+    let buff = buff(None);
+    let other = self::buff(None);
+    let mut receipt = receipt(true);
+
+    receipt.try_buff(buff, 1).unwrap();
+    receipt.try_buff(Buff { category: 1, ..other }, 1).unwrap();
+    receipt.end_ts = Some(100);
+
+    let mut receipts = [receipt];
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    // 1 * 2 * 2 back to 1 * 2.
+    assert_eq!(settled.stopped, [2 * PRECISION, 0, 0]);
+    assert!(receipts[0].has_buff(&other.key));
+    assert_eq!(receipts[0].reward_rates, [X2, 0, 0]);
+  }
+
+  #[test]
+  fn stopped_boost_is_untracked_from_the_expiry() {
+    let buff_mint = Pubkey::new_unique();
+    let mut farmer = farmer(Some(BuffExpiry {
+      buff_mint,
+      expires_at: 50,
+      boost: [3 * PRECISION, 0, 0],
+    }));
+
+    farmer.untrack_buff_boost(&buff_mint, &[PRECISION, 0, 0]);
+
+    assert_eq!(farmer.buff_expiries[0].boost, [2 * PRECISION, 0, 0]);
+    assert_eq!(farmer.total_reward_rates, [0; MAX_REWARDS]);
+  }
+
+  #[test]
+  fn expired_boost_has_nothing_to_untrack() {
+    let mut farmer = farmer(None);
+
+    farmer.untrack_buff_boost(&Pubkey::new_unique(), &[PRECISION, 0, 0]);
+
+    assert!(farmer.buff_expiries.is_empty());
+  }
+
+  #[test]
+  fn legacy_pair_needs_two_receipts_holding_the_buff() {
+    let buff = buff(None);
+    let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let pair = [buffed(true, buff), buffed(false, buff)];
+
+    assert!(check_receipts(None, &keys, &pair, &buff.key).is_ok());
+
+    let unbuffed = [buffed(true, buff), receipt(true)];
+    assert!(check_receipts(None, &keys, &unbuffed, &buff.key).is_err());
+
+    // The same receipt twice would take the buff off once and the boost off twice.
+    assert!(check_receipts(None, &[keys[0], keys[0]], &pair, &buff.key).is_err());
+  }
+
+  #[test]
+  fn recorded_receipts_must_be_given_in_order() {
+    let buff = buff(None);
+    let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let record = record(&keys, false);
+    // Recorded receipts are taken as they are, even if they don't hold the buff anymore.
+    let receipts = [buffed(true, buff), receipt(false)];
+
+    assert!(check_receipts(Some(&record), &keys, &receipts, &buff.key).is_ok());
+    assert!(check_receipts(Some(&record), &[keys[1], keys[0]], &receipts, &buff.key).is_err());
+
+    let stranger = [keys[0], Pubkey::new_unique()];
+    assert!(check_receipts(Some(&record), &stranger, &receipts, &buff.key).is_err());
+  }
+
+  #[test]
+  fn consumed_buff_is_taken_off_all_the_same() {
+    let buff = buff(None);
+    let keys = [Pubkey::new_unique()];
+    let record = record(&keys, true);
+    let mut receipts = [buffed(false, buff)];
+
+    check_receipts(Some(&record), &keys, &receipts, &buff.key).unwrap();
+    let settled = settle(&mut receipts, &buff.key).unwrap();
+
+    assert_eq!(settled.stopped, [PRECISION, 0, 0]);
+    assert_unbuffed(&receipts[0]);
+  }
+}
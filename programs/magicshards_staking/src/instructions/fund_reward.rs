@@ -1,17 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{ Mint, Token, TokenAccount };
 
-use crate::{ state::*, utils };
+use crate::{ error::StakingError, state::*, utils };
 
 #[derive(Accounts)]
 pub struct FundReward<'info> {
   #[account(mut)]
   pub farm: Account<'info, Farm>,
 
-  #[account(has_one = authority, has_one = farm)]
+  #[account(
+    has_one = authority,
+    has_one = farm,
+    constraint = farm_manager.can(FarmManager::FUNDING) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
   pub farm_manager: Account<'info, FarmManager>,
 
-  #[account(address = farm.reward.mint)]
   pub mint: Account<'info, Mint>,
 
   #[account(
@@ -33,7 +38,9 @@ pub struct FundReward<'info> {
   pub token_program: Program<'info, Token>,
 }
 
-pub fn handler(ctx: Context<FundReward>, amount: f64) -> Result<()> {
+pub fn handler(ctx: Context<FundReward>, amount: u64) -> Result<()> {
+  let index = ctx.accounts.farm.reward_index(&ctx.accounts.mint.key())?;
+
   let cpi_ctx = utils::transfer_spl_ctx(
     ctx.accounts.manager_ata.to_account_info(),
     ctx.accounts.farm_vault.to_account_info(),
@@ -41,9 +48,9 @@ pub fn handler(ctx: Context<FundReward>, amount: f64) -> Result<()> {
     ctx.accounts.token_program.to_account_info()
   );
 
-  anchor_spl::token::transfer(cpi_ctx, amount as u64)?;
+  anchor_spl::token::transfer(cpi_ctx, amount)?;
 
-  // ctx.accounts.farm.reward.try_fund(amount)?;
+  ctx.accounts.farm.rewards[index].try_fund(utils::to_scaled(amount)?)?;
 
   Ok(())
 }
\ No newline at end of file
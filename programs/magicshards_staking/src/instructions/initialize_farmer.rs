@@ -28,7 +28,7 @@ pub struct InitializeFarmer<'info> {
 pub fn handler(ctx: Context<InitializeFarmer>) -> Result<()> {
     let bump = ctx.bumps.get("farmer").unwrap();
 
-    *ctx.accounts.farmer = Farmer::new(ctx.accounts.farm.key(), ctx.accounts.owner.key(), *bump)?;
+    *ctx.accounts.farmer = Farmer::new(&ctx.accounts.farm, ctx.accounts.owner.key(), *bump)?;
 
     Ok(())
 }
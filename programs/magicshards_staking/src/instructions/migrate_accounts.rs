@@ -0,0 +1,314 @@
+use anchor_lang::{ error::ErrorCode, prelude::*, Discriminator };
+use anchor_spl::token::TokenAccount;
+
+use crate::{ error::StakingError, state::{ legacy::*, * }, utils };
+
+#[derive(Accounts)]
+pub struct MigrateAccounts<'info> {
+  /// CHECK: May still be in the legacy layout, so it's parsed by hand in the handler.
+  #[account(mut, owner = crate::ID)]
+  pub farm: UncheckedAccount<'info>,
+
+  #[account(constraint = farm_vault.owner == farm.key())]
+  pub farm_vault: Account<'info, TokenAccount>,
+
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  // Remaining accounts can be any number of this farm's Farmer, StakeReceipt, Lock,
+  // WhitelistProof and FarmManager accounts. Legacy receipts must come after their Farmer.
+  // Legacy managers that aren't at their manager authority's address must be followed by
+  // that address, where they're recreated.
+}
+
+impl<'info> MigrateAccounts<'info> {
+  /// Resizes `account` to fit `migrated` and writes it over the legacy data. The extra rent
+  /// is paid by the authority.
+  fn rewrite<T: AccountSerialize + Discriminator>(
+    &self,
+    account: &AccountInfo<'info>,
+    space: usize,
+    migrated: T
+  ) -> Result<()> {
+    utils::resize_account(
+      account,
+      space,
+      self.authority.to_account_info(),
+      self.system_program.to_account_info()
+    )?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+
+    migrated.try_serialize(&mut writer)
+  }
+
+  /// Creates the PDA `account` from `seeds` and writes `migrated` to it. The rent is paid by
+  /// the authority.
+  fn recreate<T: AccountSerialize + Discriminator>(
+    &self,
+    account: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    migrated: T
+  ) -> Result<()> {
+    utils::initialize_pda(
+      seeds,
+      space,
+      &crate::ID,
+      account.clone(),
+      self.authority.to_account_info(),
+      self.system_program.to_account_info()
+    )?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+
+    migrated.try_serialize(&mut writer)
+  }
+}
+
+fn read_legacy<T: AnchorDeserialize>(account: &AccountInfo) -> Result<T> {
+  let data = account.try_borrow_data()?;
+
+  T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+/// Records `account` if it's a Farmer, in either layout, after checking it belongs to `farm`.
+/// Legacy receipts don't reference their farm, so their farmer is what ties them to it.
+fn track_farmer(account: &AccountInfo, farm: &Pubkey, farmers: &mut Vec<Pubkey>) -> Result<()> {
+  let data = account.try_borrow_data()?;
+
+  if data.len() >= 40 && data[..8] == Farmer::discriminator() {
+    // The farm is the first field in both layouts.
+    require_keys_eq!(Pubkey::new(&data[8..40]), *farm);
+
+    farmers.push(account.key());
+  }
+
+  Ok(())
+}
+
+fn check_receipt_farmer(receipt: &LegacyStakeReceipt, farmers: &[Pubkey]) -> Result<()> {
+  require!(farmers.contains(&receipt.farmer), StakingError::UnverifiedReceiptFarmer);
+
+  Ok(())
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, MigrateAccounts<'info>>) -> Result<()> {
+  let farm_info = ctx.accounts.farm.to_account_info();
+  let farm = farm_info.key();
+
+  {
+    let data = farm_info.try_borrow_data()?;
+    require!(data[..8] == Farm::discriminator(), ErrorCode::AccountDiscriminatorMismatch);
+
+    // The authority is the first field in both layouts.
+    let authority = Pubkey::new(&data[8..40]);
+    require_keys_eq!(authority, ctx.accounts.authority.key());
+  }
+
+  if farm_info.data_len() == 8 + LegacyFarm::LEN {
+    let mut migrated = read_legacy::<LegacyFarm>(&farm_info)?.migrate(utils::now_ts()?);
+
+    // The legacy program never tracked funding, so everything in the vault is available.
+    let reward = &mut migrated.rewards[0];
+    require_keys_eq!(ctx.accounts.farm_vault.mint, reward.mint);
+    reward.available = utils::to_scaled(ctx.accounts.farm_vault.amount)?;
+
+    ctx.accounts.rewrite(&farm_info, 8 + Farm::LEN, migrated)?;
+  }
+
+  let mut farm_account = Farm::try_deserialize(&mut &farm_info.try_borrow_data()?[..])?;
+  let mut farmers = vec![];
+  let mut accounts = ctx.remaining_accounts.iter();
+
+  while let Some(account) = accounts.next() {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    require!(account.is_writable, ErrorCode::AccountNotMutable);
+
+    track_farmer(account, &farm, &mut farmers)?;
+
+    let discriminator: [u8; 8] = account
+      .try_borrow_data()?
+      .get(..8)
+      .and_then(|d| d.try_into().ok())
+      .ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+
+    let data_len = account.data_len();
+
+    if discriminator == Farmer::discriminator() && data_len == 8 + LegacyFarmer::LEN {
+      let legacy = read_legacy::<LegacyFarmer>(account)?;
+
+      // Rewards accrued under the legacy program are reserved now. Whatever the vault can't
+      // cover is owed to the farmer until the farm is funded.
+      let mut migrated = legacy.migrate();
+      let reward = &mut farm_account.rewards[0];
+      let accrued = migrated.accrued_rewards[0];
+      let covered = accrued.min(reward.unpromised());
+
+      reward.try_reserve(covered)?;
+      migrated.accrued_rewards[0] = covered;
+
+      if covered < accrued {
+        migrated.owe(reward, 0, accrued - covered)?;
+      }
+
+      ctx.accounts.rewrite(account, 8 + Farmer::LEN, migrated)?;
+    } else if
+      discriminator == WhitelistProof::discriminator() &&
+      data_len == 8 + LegacyWhitelistProof::LEN
+    {
+      let legacy = read_legacy::<LegacyWhitelistProof>(account)?;
+      require_keys_eq!(legacy.farm, farm);
+
+      ctx.accounts.rewrite(account, 8 + WhitelistProof::LEN, legacy.migrate())?;
+    } else if
+      discriminator == StakeReceipt::discriminator() &&
+      data_len == 8 + LegacyStakeReceipt::LEN
+    {
+      let legacy = read_legacy::<LegacyStakeReceipt>(account)?;
+      check_receipt_farmer(&legacy, &farmers)?;
+
+      ctx.accounts.rewrite(account, 8 + StakeReceipt::LEN, legacy.migrate()?)?;
+    } else if discriminator == Lock::discriminator() && data_len == 8 + LegacyLock::LEN {
+      let legacy = read_legacy::<LegacyLock>(account)?;
+      require_keys_eq!(legacy.farm, farm);
+
+      ctx.accounts.rewrite(account, 8 + Lock::LEN, legacy.migrate())?;
+    } else if
+      discriminator == FarmManager::discriminator() &&
+      data_len == 8 + LegacyFarmManager::LEN
+    {
+      let legacy = read_legacy::<LegacyFarmManager>(account)?;
+      require_keys_eq!(legacy.farm, farm);
+
+      let manager_authority = legacy.authority;
+      let (address, bump) = Pubkey::find_program_address(
+        &[FarmManager::PREFIX, farm.as_ref(), manager_authority.as_ref()],
+        &crate::ID
+      );
+
+      if account.key() == address {
+        ctx.accounts.rewrite(account, 8 + FarmManager::LEN, legacy.migrate())?;
+      } else {
+        // The legacy program seeded every manager by the farm authority, so the others can't
+        // be found from their signer. They move to their own address, unless they were
+        // added there again already.
+        let manager = accounts.next().ok_or(ErrorCode::AccountNotEnoughKeys)?;
+        require_keys_eq!(manager.key(), address, ErrorCode::ConstraintSeeds);
+
+        if manager.data_is_empty() {
+          ctx.accounts.recreate(
+            manager,
+            &[FarmManager::PREFIX, farm.as_ref(), manager_authority.as_ref(), &[bump]],
+            8 + FarmManager::LEN,
+            legacy.migrate()
+          )?;
+        }
+
+        utils::close_account(account, &ctx.accounts.authority.to_account_info())?;
+      }
+    } else {
+      msg!("Skipping {}: not a legacy account.", account.key());
+    }
+  }
+
+  let mut data = farm_info.try_borrow_mut_data()?;
+  let mut writer: &mut [u8] = &mut data;
+
+  farm_account.try_serialize(&mut writer)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn account_data(discriminator: [u8; 8], farm: &Pubkey) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+
+    data.extend_from_slice(farm.as_ref());
+    data.resize(8 + LegacyFarmer::LEN, 0);
+    data
+  }
+
+  fn receipt(farmer: Pubkey) -> LegacyStakeReceipt {
+    LegacyStakeReceipt {
+      farmer,
+      mint: Pubkey::new_unique(),
+      lock: Pubkey::new_unique(),
+      start_ts: 0,
+      end_ts: None,
+      amount: 1,
+      reward_rate: 1.0,
+      buff: None,
+    }
+  }
+
+  #[test]
+  fn receipts_are_migrated_after_their_farmer() {
+    let (farm, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut lamports, mut data) = (1, account_data(Farmer::discriminator(), &farm));
+    let account = AccountInfo::new(
+      &key,
+      false,
+      true,
+      &mut lamports,
+      &mut data,
+      &crate::ID,
+      false,
+      0
+    );
+    let mut farmers = vec![];
+
+    assert!(check_receipt_farmer(&receipt(key), &farmers).is_err());
+
+    track_farmer(&account, &farm, &mut farmers).unwrap();
+
+    assert!(check_receipt_farmer(&receipt(key), &farmers).is_ok());
+    assert!(check_receipt_farmer(&receipt(Pubkey::new_unique()), &farmers).is_err());
+  }
+
+  #[test]
+  fn farmers_of_other_farms_are_refused() {
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (1, account_data(Farmer::discriminator(), &key));
+    let account = AccountInfo::new(
+      &key,
+      false,
+      true,
+      &mut lamports,
+      &mut data,
+      &crate::ID,
+      false,
+      0
+    );
+    let mut farmers = vec![];
+
+    assert!(track_farmer(&account, &Pubkey::new_unique(), &mut farmers).is_err());
+    assert!(farmers.is_empty());
+  }
+
+  #[test]
+  fn only_farmers_vouch_for_receipts() {
+    let (farm, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    // Locks also start with their farm.
+    let (mut lamports, mut data) = (1, account_data(Lock::discriminator(), &farm));
+    let account = AccountInfo::new(
+      &key,
+      false,
+      true,
+      &mut lamports,
+      &mut data,
+      &crate::ID,
+      false,
+      0
+    );
+    let mut farmers = vec![];
+
+    track_farmer(&account, &farm, &mut farmers).unwrap();
+
+    assert!(check_receipt_farmer(&receipt(key), &farmers).is_err());
+  }
+}
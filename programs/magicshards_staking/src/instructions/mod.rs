@@ -1,25 +1,65 @@
+pub mod accept_authority;
 pub mod add_manager;
+pub mod add_reward;
 pub mod add_to_whitelist;
+pub mod buff;
 pub mod buff_pair;
 pub mod claim_rewards;
 pub mod create_farm;
 pub mod create_locks;
+pub mod debuff;
 pub mod debuff_pair;
+pub mod delist_whitelist;
+pub mod early_unstake;
+pub mod force_debuff;
 pub mod fund_reward;
 pub mod initialize_farmer;
+pub mod migrate_accounts;
+pub mod propose_authority;
+pub mod refresh_receipt;
 pub mod remove_from_whitelist;
+pub mod remove_manager;
+pub mod set_gem_levels;
+pub mod set_paused;
 pub mod stake;
+pub mod stake_many;
 pub mod unstake;
+pub mod unstake_many;
+pub mod unstake_partial;
+pub mod update_farm;
+pub mod update_reward_budget;
+pub mod update_schedule;
+pub mod update_whitelist;
 
+pub use accept_authority::*;
 pub use add_manager::*;
+pub use add_reward::*;
 pub use add_to_whitelist::*;
+pub use buff::*;
 pub use buff_pair::*;
 pub use claim_rewards::*;
 pub use create_farm::*;
 pub use create_locks::*;
+pub use debuff::*;
 pub use debuff_pair::*;
+pub use delist_whitelist::*;
+pub use early_unstake::*;
+pub use force_debuff::*;
 pub use fund_reward::*;
 pub use initialize_farmer::*;
+pub use migrate_accounts::*;
+pub use propose_authority::*;
+pub use refresh_receipt::*;
 pub use remove_from_whitelist::*;
+pub use remove_manager::*;
+pub use set_gem_levels::*;
+pub use set_paused::*;
 pub use stake::*;
+pub use stake_many::*;
 pub use unstake::*;
+pub use unstake_many::*;
+pub use unstake_partial::*;
+pub use update_farm::*;
+pub use update_reward_budget::*;
+pub use update_schedule::*;
+pub use update_whitelist::*;
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut, has_one = authority)]
+    pub farm: Account<'info, Farm>,
+
+    pub new_authority: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ProposeAuthority>) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+
+    ctx.accounts.farm.pending_authority = Some(new_authority);
+
+    msg!("Proposed {} as the new farm authority", new_authority);
+
+    Ok(())
+}
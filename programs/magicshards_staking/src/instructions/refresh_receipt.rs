@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+  error::StakingError,
+  instructions::stake::stake_reward_rates,
+  state::*,
+  utils::now_ts,
+};
+
+/// Permissionless crank that re-prices a running stake to its whitelist's current rates,
+/// gem level and lock bonus, which also replaces the flat bonus of legacy receipts. Stakes
+/// under a delisted whitelist stop earning from the delisting time, which unblocks their
+/// claims and unstakes.
+#[derive(Accounts)]
+pub struct RefreshReceipt<'info> {
+  #[account(mut)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(mut, has_one = farm)]
+  pub farmer: Account<'info, Farmer>,
+
+  #[account(mut, has_one = farmer, has_one = lock)]
+  pub stake_receipt: Account<'info, StakeReceipt>,
+
+  #[account(address = stake_receipt.mint)]
+  pub gem_mint: Account<'info, Mint>,
+
+  #[account(mut, has_one = farm)]
+  pub whitelist_proof: Account<'info, WhitelistProof>,
+
+  /// CHECK: The gem's rarity table entry, which may not exist. Read by `GemLevel::read`.
+  #[account(seeds = [GemLevel::PREFIX, farm.key().as_ref(), gem_mint.key().as_ref()], bump)]
+  pub gem_level: UncheckedAccount<'info>,
+
+  #[account(has_one = farm)]
+  pub lock: Account<'info, Lock>,
+  // Remaining accounts are the gem's metadata, for creator and collection whitelists.
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, '_, 'info, RefreshReceipt<'info>>,
+  merkle_proof: Option<MerkleProof>
+) -> Result<()> {
+  let now = now_ts()?;
+  let whitelist_proof = &ctx.accounts.whitelist_proof;
+
+  require!(ctx.accounts.stake_receipt.is_running(), StakingError::GemNotStaked);
+  require!(whitelist_proof.ty != WhitelistType::Buff, StakingError::InvalidWhitelistType);
+
+  WhitelistProof::validate(
+    whitelist_proof,
+    &ctx.accounts.gem_mint,
+    ctx.program_id,
+    ctx.remaining_accounts.first()
+  )?;
+
+  let whitelist_key = whitelist_proof.key();
+
+  // Receipts migrated from the legacy program don't know their whitelist yet, so the first
+  // refresh records it and counts the stake.
+  if ctx.accounts.stake_receipt.whitelist == Pubkey::default() {
+    ctx.accounts.stake_receipt.whitelist = whitelist_key;
+    ctx.accounts.whitelist_proof.track_stakes(1)?;
+    ctx.accounts.farmer.track_whitelist(whitelist_key, 1)?;
+  } else {
+    require_keys_eq!(
+      ctx.accounts.stake_receipt.whitelist,
+      whitelist_key,
+      StakingError::InvalidWhitelistType
+    );
+  }
+
+  let whitelist_proof = &ctx.accounts.whitelist_proof;
+  let delisted_at = whitelist_proof.delisted_at.filter(|delisted_at| *delisted_at <= now);
+
+  let reward_rates = match delisted_at {
+    Some(_) => [0; MAX_REWARDS],
+    None => {
+      let base_rates = whitelist_proof.whitelisted_rates(
+        &ctx.accounts.gem_mint.key(),
+        merkle_proof.as_ref()
+      )?;
+
+      stake_reward_rates(
+        &ctx.accounts.farm,
+        &base_rates,
+        &ctx.accounts.lock,
+        ctx.accounts.stake_receipt.amount,
+        GemLevel::read(&ctx.accounts.gem_level, ctx.program_id)?
+      )?
+    }
+  };
+
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+  ctx.accounts.stake_receipt.settle_earnings(&ctx.accounts.farmer)?;
+
+  let start_ts = ctx.accounts.stake_receipt.start_ts;
+  let reserved_until = ctx.accounts.stake_receipt.reserved_until;
+  let legacy = ctx.accounts.stake_receipt.legacy_bonus;
+  let old_rates = ctx.accounts.stake_receipt.reprice_to(reward_rates)?;
+  let new_rates = ctx.accounts.stake_receipt.reward_rates;
+
+  // The rest of the reservation is moved to the new rates.
+  let RefreshReceipt { farm, farmer, stake_receipt, .. } = &mut *ctx.accounts;
+
+  let reserved = stake_receipt.take_reservation(stake_receipt.amount)?;
+  farmer.release_emission(farm, &reserved)?;
+
+  // Whatever accrued at the old rates is kept, except what was accrued after the delisting.
+  // Pool farms emit a fixed budget that was already split among stakers, so only rate farms
+  // take it back.
+  if let Some(delisted_at) = delisted_at {
+    stake_receipt.reserved_until = now;
+
+    if !stake_receipt.delisted {
+      stake_receipt.delisted = true;
+      farmer.untrack_whitelist(&whitelist_key, 1);
+    }
+
+    if farm.mode == EmissionMode::Rate {
+      let delisted_emission = farm.schedule.overlap(delisted_at.max(start_ts), now);
+      let mut clawback = [0; MAX_REWARDS];
+
+      for (clawback, rate) in clawback.iter_mut().zip(old_rates) {
+        *clawback = rate
+          .checked_mul(delisted_emission as u128)
+          .ok_or(StakingError::ArithmeticError)?;
+      }
+
+      farmer.forfeit(&mut farm.rewards, &clawback, &[0; MAX_REWARDS])?;
+    }
+
+    msg!("Whitelist was delisted at {}, stake no longer earns.", delisted_at);
+  } else {
+    let reserved = farmer.reserve_emission(farm, &new_rates, now, reserved_until)?;
+    stake_receipt.add_reservation(&reserved)?;
+  }
+
+  if legacy {
+    ctx.accounts.farmer.decrease_legacy_reward_rate(&mut ctx.accounts.farm, &old_rates)?;
+  } else {
+    ctx.accounts.farmer.decrease_reward_rate(&mut ctx.accounts.farm, &old_rates)?;
+  }
+
+  ctx.accounts.farmer.increase_reward_rate(&mut ctx.accounts.farm, &new_rates)?;
+
+  Ok(())
+}
@@ -1,13 +1,15 @@
 use anchor_lang::prelude::*;
 
-use crate::state::*;
+use crate::{error::StakingError, state::*};
 
 #[derive(Accounts)]
 pub struct RemoveFromWhitelist<'info> {
     pub farm: Account<'info, Farm>,
 
     #[account(
+        has_one = farm,
         has_one = authority,
+        constraint = farm_manager.can(FarmManager::WHITELIST) @ StakingError::MissingPermission,
         seeds = [
             FarmManager::PREFIX,
             farm.key().as_ref(),
@@ -25,10 +27,26 @@ pub struct RemoveFromWhitelist<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<RemoveFromWhitelist>) -> Result<()> {
+/// Closing a whitelist with active stakes needs `force`. Those stakes keep their rates and
+/// can still be unstaked, but can't be refreshed anymore. A delisted whitelist can't be
+/// forced out, since its stakes must be refreshed against it to stop earning.
+pub fn handler(ctx: Context<RemoveFromWhitelist>, force: bool) -> Result<()> {
+    let whitelist_proof = &ctx.accounts.whitelist_proof;
+    let active_stakes = whitelist_proof.active_stakes;
+
+    require!(active_stakes == 0 || force, StakingError::WhitelistHasStakes);
+    require!(
+        active_stakes == 0 || whitelist_proof.delisted_at.is_none(),
+        StakingError::DelistedStakes
+    );
+
+    if active_stakes > 0 {
+        msg!("Force closing with {} active stakes.", active_stakes);
+    }
+
     msg!(
         "Removed {} from whitelist!",
-        ctx.accounts.whitelist_proof.whitelisted_address
+        whitelist_proof.whitelisted_address
     );
     Ok(())
 }
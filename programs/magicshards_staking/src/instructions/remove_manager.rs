@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RemoveManager<'info> {
+    #[account(has_one = authority)]
+    pub farm: Account<'info, Farm>,
+
+    #[account(mut, close = authority, has_one = farm)]
+    pub farm_manager: Account<'info, FarmManager>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveManager>) -> Result<()> {
+    msg!(
+        "Removed manager {} from farm {}",
+        ctx.accounts.farm_manager.authority,
+        ctx.accounts.farm.key()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anchor_lang::{ error::ErrorCode, prelude::*, Discriminator };
+
+use crate::{ error::StakingError, state::*, utils };
+
+#[derive(Accounts)]
+pub struct SetGemLevels<'info> {
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    has_one = farm,
+    has_one = authority,
+    constraint = farm_manager.can(FarmManager::WHITELIST) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
+  pub farm_manager: Account<'info, FarmManager>,
+
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  // Remaining accounts are the GemLevel PDAs, in the same order as the levels. Missing
+  // entries are created.
+}
+
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct GemLevelConfig {
+  pub mint: Pubkey,
+  pub level: u8,
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, '_, 'info, SetGemLevels<'info>>,
+  levels: Vec<GemLevelConfig>
+) -> Result<()> {
+  require_eq!(levels.len(), ctx.remaining_accounts.len(), StakingError::InvalidGemLevelAccounts);
+
+  let farm = ctx.accounts.farm.key();
+
+  for (GemLevelConfig { mint, level }, account) in levels.into_iter().zip(ctx.remaining_accounts) {
+    require_gte!(GemLevel::MAX, level, StakingError::InvalidTripEffect);
+
+    let (address, bump) = Pubkey::find_program_address(
+      &[GemLevel::PREFIX, farm.as_ref(), mint.as_ref()],
+      ctx.program_id
+    );
+
+    require_keys_eq!(account.key(), address, ErrorCode::ConstraintSeeds);
+
+    if account.data_is_empty() {
+      utils::initialize_pda(
+        &[GemLevel::PREFIX, farm.as_ref(), mint.as_ref(), &[bump]],
+        8 + GemLevel::LEN,
+        ctx.program_id,
+        account.clone(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+      )?;
+    } else {
+      require!(account.data_len() > 8, ErrorCode::AccountDidNotDeserialize);
+      require!(
+        account.try_borrow_data()?[..8] == GemLevel::discriminator(),
+        ErrorCode::AccountDiscriminatorMismatch
+      );
+    }
+
+    let entry = GemLevel { farm, mint, level };
+    let mut data = account.try_borrow_mut_data()?;
+
+    entry.try_serialize(&mut &mut data[..])?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::*};
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        has_one = farm,
+        has_one = authority,
+        constraint = farm_manager.can(FarmManager::PAUSE) @ StakingError::MissingPermission,
+        seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+        bump,
+    )]
+    pub farm_manager: Account<'info, FarmManager>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.farm.paused = paused;
+
+    msg!("Farm {} paused: {}", ctx.accounts.farm.key(), paused);
+
+    Ok(())
+}
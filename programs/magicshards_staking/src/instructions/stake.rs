@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ associated_token::AssociatedToken, token::{ Mint, Token, TokenAccount } };
+use anchor_spl::{
+  associated_token::{ get_associated_token_address, AssociatedToken },
+  token::{ Approve, Mint, Token, TokenAccount },
+};
 
 use crate::{ error::*, state::*, utils::* };
 
 #[derive(Accounts)]
 pub struct Stake<'info> {
-  #[account(mut)]
+  #[account(mut, constraint = !farm.paused @ StakingError::FarmPaused)]
   pub farm: Account<'info, Farm>,
 
   #[account(
@@ -23,16 +26,17 @@ pub struct Stake<'info> {
 
   pub gem_mint: Account<'info, Mint>,
 
-  #[account(has_one = farm)]
+  #[account(mut, has_one = farm)]
   pub whitelist_proof: Account<'info, WhitelistProof>,
 
-  #[account(
-    init_if_needed,
-    payer = owner,
-    associated_token::mint = gem_mint,
-    associated_token::authority = farmer
-  )]
-  pub farmer_vault: Box<Account<'info, TokenAccount>>,
+  /// CHECK: The gem's rarity table entry, which may not exist. Read by `GemLevel::read`.
+  #[account(seeds = [GemLevel::PREFIX, farm.key().as_ref(), gem_mint.key().as_ref()], bump)]
+  pub gem_level: UncheckedAccount<'info>,
+
+  /// CHECK: The farmer's token account for the gem, created by `lock_gem` in vault custody.
+  /// Frozen gems stay in the owner's wallet, so it isn't used then.
+  #[account(mut, address = get_associated_token_address(&farmer.key(), &gem_mint.key()))]
+  pub farmer_vault: UncheckedAccount<'info>,
 
   #[account(
         mut,
@@ -62,26 +66,83 @@ pub struct Stake<'info> {
   pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-impl Stake<'_> {
-  pub fn lock_gem(&self, amount: u64) -> Result<()> {
+impl<'info> Stake<'info> {
+  pub fn lock_gem(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+    if self.farm.custody == Custody::Vault && self.farmer_vault.data_is_empty() {
+      create_vault!(
+        self,
+        self.owner.to_account_info(),
+        self.farmer_vault.to_account_info(),
+        self.farmer.to_account_info(),
+        self.gem_mint.to_account_info()
+      )?;
+    }
+
     if amount == 0 {
       return Ok(());
     }
-    let cpi_ctx = transfer_spl_ctx(
-      self.gem_owner_ata.to_account_info(),
-      self.farmer_vault.to_account_info(),
-      self.owner.to_account_info(),
-      self.token_program.to_account_info()
-    );
-
-    anchor_spl::token::transfer(cpi_ctx, amount)
+
+    if let Some(pnft) = PnftAccounts::from_remaining(&self.gem_mint.key(), remaining_accounts)? {
+      require!(self.farm.custody == Custody::Vault, StakingError::UnsupportedCustody);
+
+      let transfer = PnftTransfer {
+        token: self.gem_owner_ata.to_account_info(),
+        token_owner: self.owner.to_account_info(),
+        destination: self.farmer_vault.to_account_info(),
+        destination_owner: self.farmer.to_account_info(),
+        mint: self.gem_mint.to_account_info(),
+        authority: self.owner.to_account_info(),
+        payer: self.owner.to_account_info(),
+        token_program: self.token_program.to_account_info(),
+      };
+
+      return pnft.transfer(transfer, amount, &[]);
+    }
+
+    match self.farm.custody {
+      Custody::Vault => {
+        let cpi_ctx = transfer_spl_ctx(
+          self.gem_owner_ata.to_account_info(),
+          self.farmer_vault.to_account_info(),
+          self.owner.to_account_info(),
+          self.token_program.to_account_info()
+        );
+
+        anchor_spl::token::transfer(cpi_ctx, amount)
+      }
+      // The gem stays in the wallet, but the farmer becomes its delegate and freezes it.
+      // Approvals aren't bounded by the balance, so the amount is checked here.
+      Custody::Freeze => {
+        require!(amount <= self.gem_owner_ata.amount, StakingError::InvalidStakeAmount);
+        require!(
+          self.whitelist_proof.ty == WhitelistType::Mint || amount == 1,
+          StakingError::InvalidStakeAmount
+        );
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), Approve {
+          to: self.gem_owner_ata.to_account_info(),
+          delegate: self.farmer.to_account_info(),
+          authority: self.owner.to_account_info(),
+        });
+
+        anchor_spl::token::approve(cpi_ctx, amount)?;
+
+        FreezeAccounts::from_remaining(
+          self.farmer.to_account_info(),
+          self.gem_owner_ata.to_account_info(),
+          self.gem_mint.to_account_info(),
+          self.token_program.to_account_info(),
+          remaining_accounts
+        )?.freeze(&self.farmer.seeds())
+      }
+    }
   }
 }
 
 pub fn handler<'info>(
   ctx: Context<'_, '_, '_, 'info, Stake<'info>>,
   amount: u64,
-  level: u8
+  merkle_proof: Option<MerkleProof>
 ) -> Result<()> {
   let whitelist_proof = &ctx.accounts.whitelist_proof;
 
@@ -89,7 +150,7 @@ pub fn handler<'info>(
     whitelist_proof,
     &ctx.accounts.gem_mint,
     ctx.program_id,
-    ctx.remaining_accounts
+    ctx.remaining_accounts.first()
   )?;
 
   if let WhitelistType::Buff = whitelist_proof.ty {
@@ -98,28 +159,56 @@ pub fn handler<'info>(
   }
 
   // Lock the nft to the farmer account.
-  ctx.accounts.lock_gem(amount)?;
+  ctx.accounts.lock_gem(amount, ctx.remaining_accounts)?;
 
   let now_ts = now_ts()?;
 
-  let decimals = 2;
-  let emission = level_emission(level, decimals)?;
-  let factor = ctx.accounts.lock.bonus_factor;
-  let base_rate = (amount as f64) * (ctx.accounts.whitelist_proof.reward_rate + (emission as f64));
-  let reward_rate = calculate_reward_rate(base_rate, factor as f64)?;
+  require!(!whitelist_proof.is_delisted(now_ts), StakingError::WhitelistDelisted);
+
+  let base_rates = whitelist_proof.whitelisted_rates(
+    &ctx.accounts.gem_mint.key(),
+    merkle_proof.as_ref()
+  )?;
+  let level = GemLevel::read(&ctx.accounts.gem_level, ctx.program_id)?;
+  let reward_rates = stake_reward_rates(
+    &ctx.accounts.farm,
+    &base_rates,
+    &ctx.accounts.lock,
+    amount,
+    level
+  )?;
+
+  // Settle what was accrued at the previous rate before changing it.
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
 
+  let farmer = &ctx.accounts.farmer;
   let stake_receipt = &mut ctx.accounts.stake_receipt;
+  // Top-ups add to a stake that's already counted.
+  let opens_stake = !stake_receipt.is_running() || stake_receipt.farmer == Pubkey::default();
+  let reserved_until = reserved_until(&ctx.accounts.farm, &ctx.accounts.lock, now_ts);
+  // Emission to reserve for the new tokens, plus the extension for the ones already staked.
+  let mut reservations = vec![(reward_rates, now_ts)];
 
   if stake_receipt.farmer == Pubkey::default() {
     **stake_receipt = StakeReceipt {
       end_ts: None,
       start_ts: now_ts,
+      reserved_until,
+      reserved: [0; MAX_REWARDS],
       lock: ctx.accounts.lock.key(),
+      whitelist: whitelist_proof.key(),
       farmer: ctx.accounts.farmer.key(),
       mint: ctx.accounts.gem_mint.key(),
-      buff: None,
-      reward_rate,
+      unbuffed_rates: reward_rates,
+      earned: [0; MAX_REWARDS],
+      earnings_checkpoint: farmer.accrued_per_rate,
+      buffs: vec![],
+      legacy_bonus: false,
+      delisted: false,
+      reward_rates,
       amount,
+      top_up_amount: 0,
+      top_up_ts: 0,
     };
   } else {
     // Receipt account already existed.
@@ -129,38 +218,127 @@ pub fn handler<'info>(
       Some(end_ts) => {
         let cooldown = ctx.accounts.lock.cooldown;
         require_gte!(now_ts, end_ts + cooldown, StakingError::CooldownIsNotOver);
+        // Reopening keeps the buffs, which must be taken back with their records first.
+        require!(!stake_receipt.is_buffed(), StakingError::GemStillBuffed);
 
         // Here the cooldown is already over, so just update the receipt with the new
         // information.
         stake_receipt.end_ts = None;
         stake_receipt.start_ts = now_ts;
+        stake_receipt.reserved_until = reserved_until;
+        stake_receipt.reserved = [0; MAX_REWARDS];
         stake_receipt.lock = ctx.accounts.lock.key();
-        stake_receipt.reward_rate = reward_rate;
+        stake_receipt.whitelist = whitelist_proof.key();
+        stake_receipt.reward_rates = reward_rates;
         stake_receipt.amount = amount;
+        stake_receipt.top_up_amount = 0;
+        stake_receipt.top_up_ts = 0;
+        stake_receipt.legacy_bonus = false;
+        stake_receipt.delisted = false;
+        stake_receipt.reset_earnings(farmer);
       }
+      // Fungible positions can be topped up. The new tokens get a lock of their own, so
+      // they can't skip it and the tokens already staked keep theirs.
       None => {
-        return err!(StakingError::GemStillStaked);
+        require!(whitelist_proof.ty == WhitelistType::Mint, StakingError::GemStillStaked);
+        require_keys_eq!(stake_receipt.lock, ctx.accounts.lock.key(), StakingError::LockMismatch);
+        require_keys_eq!(
+          stake_receipt.whitelist,
+          whitelist_proof.key(),
+          StakingError::InvalidWhitelistType
+        );
+        require!(!stake_receipt.is_buffed(), StakingError::GemStillBuffed);
+        require!(ctx.accounts.farm.custody == Custody::Vault, StakingError::UnsupportedCustody);
+
+        reservations.push((stake_receipt.reward_rates, stake_receipt.reserved_until.max(now_ts)));
+        stake_receipt.settle_earnings(farmer)?;
+
+        stake_receipt.reserved_until = stake_receipt.reserved_until.max(reserved_until);
+        stake_receipt.top_up(amount, ctx.accounts.lock.duration, now_ts)?;
+
+        for (rate, increment) in stake_receipt.reward_rates.iter_mut().zip(reward_rates) {
+          *rate = rate.checked_add(increment).ok_or(StakingError::ArithmeticError)?;
+        }
       }
     }
   }
 
-  ctx.accounts.farmer.increase_reward_rate(reward_rate as f64)?;
+  if opens_stake {
+    ctx.accounts.whitelist_proof.track_stakes(1)?;
+    ctx.accounts.farmer.track_whitelist(ctx.accounts.whitelist_proof.key(), 1)?;
+  }
+
+  let lock_end = now_ts
+    .checked_add(ctx.accounts.lock.duration)
+    .ok_or(StakingError::ArithmeticError)?;
+
+  ctx.accounts.farmer.track_early_exit(&ctx.accounts.lock, opens_stake as u32, lock_end)?;
+
+  let reserved_until = ctx.accounts.stake_receipt.reserved_until;
+
+  for (rates, from) in reservations {
+    let reserved = ctx.accounts.farmer.reserve_emission(
+      &mut ctx.accounts.farm,
+      &rates,
+      from,
+      reserved_until
+    )?;
+    ctx.accounts.stake_receipt.add_reservation(&reserved)?;
+  }
+
+  ctx.accounts.farmer.increase_reward_rate(&mut ctx.accounts.farm, &reward_rates)?;
 
   Ok(())
 }
 
-fn tokens_per_second(amount: u64, decimals: u32) -> Result<u64> {
-  (10_u64)
+/// Reward rates earned by staking `amount` tokens with these whitelisted rates and lock.
+pub(crate) fn stake_reward_rates(
+  farm: &Farm,
+  base_rates: &[u128; MAX_REWARDS],
+  lock: &Lock,
+  amount: u64,
+  level: u8
+) -> Result<[u128; MAX_REWARDS]> {
+  let decimals = farm.config.level_decimals as u32;
+  let emission = level_emission(level, decimals)?;
+
+  let mut reward_rates = [0; MAX_REWARDS];
+
+  for (index, reward_rate) in reward_rates.iter_mut().enumerate() {
+    // Trip effects only boost the farm's primary reward.
+    let bonus = if index == 0 { emission } else { 0 };
+    let base_rate = base_rates[index]
+      .checked_add(bonus)
+      .and_then(|rate| rate.checked_mul(amount as u128))
+      .ok_or(StakingError::ArithmeticError)?;
+
+    *reward_rate = calculate_reward_rate(base_rate, lock.bonus_bps)?;
+  }
+
+  Ok(reward_rates)
+}
+
+/// End of the period a stake's emission is reserved for, so the farm can't promise more
+/// than it holds. That's the lock period, but at least the farm's reserve window so stakes
+/// without a lock are backed too.
+pub(crate) fn reserved_until(farm: &Farm, lock: &Lock, now: u64) -> u64 {
+  now.saturating_add(lock.duration.max(farm.config.reserve_secs))
+}
+
+fn tokens_per_second(amount: u128, decimals: u32) -> Result<u128> {
+  (10_u128)
     .checked_pow(decimals)
     .ok_or(StakingError::ArithmeticError)?
     .checked_mul(amount)
     .ok_or(StakingError::ArithmeticError)?
+    .checked_mul(PRECISION)
+    .ok_or(StakingError::ArithmeticError)?
     .checked_div(86_400)
     .ok_or_else(|| error!(StakingError::ArithmeticError))
 }
 
-/// Converts a level to a reward_rate (tokens/sec).
-fn level_emission(level: u8, decimals: u32) -> Result<u64> {
+/// Converts a level to a scaled reward_rate (tokens/sec).
+fn level_emission(level: u8, decimals: u32) -> Result<u128> {
   // Bonus emissions for None, 2x, 3x, 4x, Nirvana, Space
   let emissions = [0, 2, 3, 4, 6, 10];
   let emission = emissions.get(level as usize).ok_or(StakingError::InvalidTripEffect)?;
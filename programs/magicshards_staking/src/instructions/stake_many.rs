@@ -0,0 +1,231 @@
+use anchor_lang::{ error::ErrorCode, prelude::* };
+use anchor_spl::{
+  associated_token::{ get_associated_token_address, AssociatedToken },
+  token::{ Mint, Token, TokenAccount },
+};
+
+use crate::{
+  error::StakingError,
+  instructions::stake::{ reserved_until, stake_reward_rates },
+  state::*,
+  utils::{ self, now_ts },
+};
+
+#[derive(Accounts)]
+pub struct StakeMany<'info> {
+  #[account(mut, constraint = !farm.paused @ StakingError::FarmPaused)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    mut,
+    has_one = farm,
+    has_one = owner,
+    seeds = [Farmer::PREFIX, farm.key().as_ref(), owner.key().as_ref()],
+    bump
+  )]
+  pub farmer: Account<'info, Farmer>,
+
+  // Every gem in the batch is staked under the same whitelist and lock.
+  #[account(mut, has_one = farm)]
+  pub whitelist_proof: Account<'info, WhitelistProof>,
+
+  #[account(has_one = farm)]
+  pub lock: Account<'info, Lock>,
+
+  #[account(mut)]
+  pub owner: Signer<'info>,
+
+  pub rent: Sysvar<'info, Rent>,
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, Token>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  // Remaining accounts are (gem_mint, gem_owner_ata, farmer_vault, stake_receipt, metadata,
+  // gem_level) groups, one for each gem. The vault and receipt are created if needed. pNFTs
+  // need their Token Metadata accounts, so they go through `stake`.
+}
+
+impl<'info> StakeMany<'info> {
+  fn lock_gem(&self, owner_ata: &AccountInfo<'info>, vault: &AccountInfo<'info>) -> Result<()> {
+    let cpi_ctx = utils::transfer_spl_ctx(
+      owner_ata.clone(),
+      vault.clone(),
+      self.owner.to_account_info(),
+      self.token_program.to_account_info()
+    );
+
+    anchor_spl::token::transfer(cpi_ctx, 1)
+  }
+
+  /// Creates or reopens the receipt for `mint`, returning it with the new stake's data.
+  fn open_receipt(
+    &self,
+    receipt: &AccountInfo<'info>,
+    mint: Pubkey,
+    reward_rates: [u128; MAX_REWARDS],
+    now: u64,
+    reserved_until: u64,
+    program_id: &Pubkey
+  ) -> Result<StakeReceipt> {
+    let farmer = self.farmer.key();
+    let (pda, bump) = Pubkey::find_program_address(
+      &[StakeReceipt::PREFIX, farmer.as_ref(), mint.as_ref()],
+      program_id
+    );
+
+    require_keys_eq!(receipt.key(), pda, ErrorCode::ConstraintSeeds);
+
+    if receipt.data_is_empty() {
+      utils::initialize_pda(
+        &[StakeReceipt::PREFIX, farmer.as_ref(), mint.as_ref(), &[bump]],
+        8 + StakeReceipt::LEN,
+        program_id,
+        receipt.clone(),
+        self.owner.to_account_info(),
+        self.system_program.to_account_info()
+      )?;
+    } else {
+      require_keys_eq!(*receipt.owner, *program_id, ErrorCode::AccountOwnedByWrongProgram);
+
+      let previous = StakeReceipt::try_deserialize(&mut &receipt.try_borrow_data()?[..])?;
+      let end_ts = previous.end_ts.ok_or(StakingError::GemStillStaked)?;
+
+      require_gte!(now, end_ts + self.lock.cooldown, StakingError::CooldownIsNotOver);
+      // The receipt is rewritten without buffs, which must be taken back first.
+      require!(!previous.is_buffed(), StakingError::GemStillBuffed);
+    }
+
+    Ok(StakeReceipt {
+      farmer,
+      mint,
+      lock: self.lock.key(),
+      whitelist: self.whitelist_proof.key(),
+      start_ts: now,
+      end_ts: None,
+      reserved_until,
+      reserved: [0; MAX_REWARDS],
+      amount: 1,
+      top_up_amount: 0,
+      top_up_ts: 0,
+      reward_rates,
+      unbuffed_rates: reward_rates,
+      earned: [0; MAX_REWARDS],
+      earnings_checkpoint: self.farmer.accrued_per_rate,
+      buffs: vec![],
+      legacy_bonus: false,
+      delisted: false,
+    })
+  }
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>
+) -> Result<()> {
+  require!(
+    !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() % 6 == 0,
+    StakingError::InvalidStakeAccounts
+  );
+  // Only NFT collections can be batched. Mint whitelists are for fungible tokens, which are
+  // staked by amount, and merkle whitelists need a proof per gem.
+  require!(
+    matches!(ctx.accounts.whitelist_proof.ty, WhitelistType::Creator | WhitelistType::Collection),
+    StakingError::InvalidWhitelistType
+  );
+  // Frozen gems need their edition accounts too, which the groups don't carry.
+  require!(ctx.accounts.farm.custody == Custody::Vault, StakingError::UnsupportedCustody);
+
+  let now = now_ts()?;
+
+  require!(!ctx.accounts.whitelist_proof.is_delisted(now), StakingError::WhitelistDelisted);
+
+  let farmer = ctx.accounts.farmer.key();
+  let reserved_until = reserved_until(&ctx.accounts.farm, &ctx.accounts.lock, now);
+  let mut total_rates = [0; MAX_REWARDS];
+
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+
+  for group in ctx.remaining_accounts.chunks(6) {
+    let gem_mint = Account::<Mint>::try_from(&group[0])?;
+    let gem_owner_ata = Account::<TokenAccount>::try_from(&group[1])?;
+    let farmer_vault = &group[2];
+    let stake_receipt = &group[3];
+    let gem_level = &group[5];
+
+    WhitelistProof::validate(
+      &ctx.accounts.whitelist_proof,
+      &gem_mint,
+      ctx.program_id,
+      group.get(4)
+    )?;
+    require!(!utils::is_programmable(&group[4])?, StakingError::UnsupportedPnft);
+
+    require_keys_eq!(gem_owner_ata.mint, gem_mint.key(), StakingError::InvalidStakeAccounts);
+    require_keys_eq!(
+      gem_owner_ata.owner,
+      ctx.accounts.owner.key(),
+      StakingError::InvalidStakeAccounts
+    );
+    require_keys_eq!(
+      farmer_vault.key(),
+      get_associated_token_address(&farmer, &gem_mint.key()),
+      StakingError::InvalidStakeAccounts
+    );
+
+    let (gem_level_address, _) = Pubkey::find_program_address(
+      &[GemLevel::PREFIX, ctx.accounts.farm.key().as_ref(), gem_mint.key().as_ref()],
+      ctx.program_id
+    );
+    require_keys_eq!(gem_level.key(), gem_level_address, ErrorCode::ConstraintSeeds);
+
+    if farmer_vault.data_is_empty() {
+      utils::create_vault!(
+        ctx.accounts,
+        ctx.accounts.owner.to_account_info(),
+        farmer_vault.clone(),
+        ctx.accounts.farmer.to_account_info(),
+        group[0].clone()
+      )?;
+    }
+
+    ctx.accounts.lock_gem(&group[1], farmer_vault)?;
+
+    let reward_rates = stake_reward_rates(
+      &ctx.accounts.farm,
+      &ctx.accounts.whitelist_proof.reward_rates,
+      &ctx.accounts.lock,
+      1,
+      GemLevel::read(gem_level, ctx.program_id)?
+    )?;
+    let mut receipt = ctx.accounts.open_receipt(
+      stake_receipt,
+      gem_mint.key(),
+      reward_rates,
+      now,
+      reserved_until,
+      ctx.program_id
+    )?;
+    let reserved = ctx.accounts.farmer.reserve_emission(
+      &mut ctx.accounts.farm,
+      &reward_rates,
+      now,
+      reserved_until
+    )?;
+
+    receipt.add_reservation(&reserved)?;
+
+    let mut data = stake_receipt.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+
+    for (total, rate) in total_rates.iter_mut().zip(reward_rates) {
+      *total = total.checked_add(rate).ok_or(StakingError::ArithmeticError)?;
+    }
+  }
+
+  let stakes = ctx.remaining_accounts.len() / 6;
+  let lock_end = now.checked_add(ctx.accounts.lock.duration).ok_or(StakingError::ArithmeticError)?;
+
+  ctx.accounts.whitelist_proof.track_stakes(stakes as u64)?;
+  ctx.accounts.farmer.track_whitelist(ctx.accounts.whitelist_proof.key(), stakes as u32)?;
+  ctx.accounts.farmer.track_early_exit(&ctx.accounts.lock, stakes as u32, lock_end)?;
+
+  ctx.accounts.farmer.increase_reward_rate(&mut ctx.accounts.farm, &total_rates)
+}
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{ Mint, Token, TokenAccount };
+use anchor_spl::{
+  associated_token::get_associated_token_address,
+  token::{ Mint, Revoke, Token, TokenAccount },
+};
 
 use crate::error::StakingError;
-use crate::utils::{ self, now_ts };
+use crate::utils::{ self, now_ts, PnftAccounts, PnftTransfer };
 
 use crate::state::*;
 
@@ -43,12 +46,15 @@ pub struct Unstake<'info> {
   #[account(has_one = farm)]
   pub lock: Account<'info, Lock>,
 
-  #[account(
-        mut,
-        associated_token::mint = gem_mint,
-        associated_token::authority = farmer,
-    )]
-  pub farmer_vault: Box<Account<'info, TokenAccount>>,
+  /// CHECK: The whitelist the gem was staked under. It may have been force closed already,
+  /// and `track_unstake` checks that it's writable.
+  #[account(address = stake_receipt.whitelist)]
+  pub whitelist_proof: UncheckedAccount<'info>,
+
+  /// CHECK: The farmer's token account for the gem. Frozen gems never leave the owner's
+  /// wallet, so it doesn't exist in freeze custody.
+  #[account(mut, address = get_associated_token_address(&farmer.key(), &gem_mint.key()))]
+  pub farmer_vault: UncheckedAccount<'info>,
 
   #[account(
         mut,
@@ -57,41 +63,121 @@ pub struct Unstake<'info> {
     )]
   pub gem_owner_ata: Box<Account<'info, TokenAccount>>,
 
+  // Pays for the token record when unstaking a pNFT.
+  #[account(mut)]
   pub owner: Signer<'info>,
 
   pub token_program: Program<'info, Token>,
 }
 
 impl<'info> Unstake<'info> {
-  pub fn release_gems(&self, amount: u64) -> Result<()> {
-    let cpi_ctx = utils::transfer_spl_ctx(
-      self.farmer_vault.to_account_info(),
-      self.gem_owner_ata.to_account_info(),
-      self.farmer.to_account_info(),
-      self.token_program.to_account_info()
+  pub fn release_gems(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+    if let Some(pnft) = PnftAccounts::from_remaining(&self.gem_mint.key(), remaining_accounts)? {
+      let transfer = PnftTransfer {
+        token: self.farmer_vault.to_account_info(),
+        token_owner: self.farmer.to_account_info(),
+        destination: self.gem_owner_ata.to_account_info(),
+        destination_owner: self.owner.to_account_info(),
+        mint: self.gem_mint.to_account_info(),
+        authority: self.farmer.to_account_info(),
+        payer: self.owner.to_account_info(),
+        token_program: self.token_program.to_account_info(),
+      };
+
+      return pnft.transfer(transfer, amount, &[&self.farmer.seeds()]);
+    }
+
+    match self.farm.custody {
+      Custody::Vault => {
+        let cpi_ctx = utils::transfer_spl_ctx(
+          self.farmer_vault.to_account_info(),
+          self.gem_owner_ata.to_account_info(),
+          self.farmer.to_account_info(),
+          self.token_program.to_account_info()
+        );
+
+        anchor_spl::token::transfer(cpi_ctx.with_signer(&[&self.farmer.seeds()]), amount)
+      }
+      // Frozen gems are released all at once, so there's no partial release.
+      Custody::Freeze => {
+        utils::FreezeAccounts::from_remaining(
+          self.farmer.to_account_info(),
+          self.gem_owner_ata.to_account_info(),
+          self.gem_mint.to_account_info(),
+          self.token_program.to_account_info(),
+          remaining_accounts
+        )?.thaw(&self.farmer.seeds())?;
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), Revoke {
+          source: self.gem_owner_ata.to_account_info(),
+          authority: self.owner.to_account_info(),
+        });
+
+        anchor_spl::token::revoke(cpi_ctx)
+      }
+    }
+  }
+
+  pub fn lock_end(&self) -> Result<u64> {
+    self.stake_receipt.lock_end(self.lock.duration)
+  }
+
+  /// Stakes under a delisted whitelist must be refreshed first, or they'd leave with what
+  /// they earned after the delisting.
+  pub fn check_delisting(&self, now: u64, program_id: &Pubkey) -> Result<()> {
+    require!(
+      self.stake_receipt.delisted ||
+        !WhitelistProof::is_delisted_at(&self.whitelist_proof, program_id, now)?,
+      StakingError::DelistedStakes
     );
 
-    anchor_spl::token::transfer(cpi_ctx.with_signer(&[&self.farmer.seeds()]), amount)
+    Ok(())
   }
-}
 
-pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
-  require!(ctx.accounts.stake_receipt.buff.is_none(), StakingError::GemStillBuffed);
+  /// Sends the gems back, stops the receipt from earning and gives back what's left of its
+  /// reservation. Accrual must be settled first.
+  pub fn close_stake(
+    &mut self,
+    now: u64,
+    program_id: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>]
+  ) -> Result<()> {
+    require!(!self.stake_receipt.is_buffed(), StakingError::GemStillBuffed);
+    self.check_delisting(now, program_id)?;
 
-  let now = now_ts()?;
-  let end_ts = ctx.accounts.stake_receipt.start_ts
-    .checked_add(ctx.accounts.lock.duration)
-    .ok_or(StakingError::ArithmeticError)?;
+    self.stake_receipt.settle_earnings(&self.farmer)?;
+
+    let amount = self.stake_receipt.amount;
+    let reward_rates = self.stake_receipt.reward_rates;
+    let reserved = self.stake_receipt.take_reservation(amount)?;
+
+    self.release_gems(amount, remaining_accounts)?;
+    self.farmer.release_emission(&mut self.farm, &reserved)?;
+
+    if self.stake_receipt.legacy_bonus {
+      self.farmer.decrease_legacy_reward_rate(&mut self.farm, &reward_rates)?;
+    } else {
+      self.farmer.decrease_reward_rate(&mut self.farm, &reward_rates)?;
+    }
+
+    self.farmer.untrack_early_exit(&self.lock, 1);
+    WhitelistProof::track_unstake(&self.whitelist_proof, program_id, 1)?;
 
-  let receipt = &ctx.accounts.stake_receipt;
+    if !self.stake_receipt.delisted {
+      self.farmer.untrack_whitelist(&self.stake_receipt.whitelist, 1);
+    }
 
-  require_gte!(now, end_ts, StakingError::GemStillLocked);
+    self.stake_receipt.end_ts = Some(now);
 
-  ctx.accounts.farmer.update_accrued_rewards()?;
-  ctx.accounts.release_gems(receipt.amount)?;
-  ctx.accounts.farmer.decrease_reward_rate(receipt.reward_rate)?;
+    Ok(())
+  }
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
+  let now = now_ts()?;
 
-  ctx.accounts.stake_receipt.end_ts = Some(now);
+  require_gte!(now, ctx.accounts.lock_end()?, StakingError::GemStillLocked);
 
-  Ok(())
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+  ctx.accounts.close_stake(now, ctx.program_id, ctx.remaining_accounts)
 }
\ No newline at end of file
@@ -0,0 +1,150 @@
+use anchor_lang::{ error::ErrorCode, prelude::* };
+use anchor_spl::{
+  associated_token::get_associated_token_address,
+  token::{ Token, TokenAccount },
+};
+
+use crate::{ error::StakingError, state::*, utils::{ self, now_ts } };
+
+#[derive(Accounts)]
+pub struct UnstakeMany<'info> {
+  #[account(mut)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    mut,
+    has_one = farm,
+    has_one = owner,
+    seeds = [Farmer::PREFIX, farm.key().as_ref(), owner.key().as_ref()],
+    bump
+  )]
+  pub farmer: Account<'info, Farmer>,
+
+  // Every gem in the batch must have been staked with this lock.
+  #[account(has_one = farm)]
+  pub lock: Account<'info, Lock>,
+
+  /// CHECK: The whitelist every gem in the batch was staked under, checked against the
+  /// receipts. `track_unstake` checks that it's writable.
+  pub whitelist_proof: UncheckedAccount<'info>,
+
+  pub owner: Signer<'info>,
+
+  pub token_program: Program<'info, Token>,
+  // Remaining accounts are (gem_mint, gem_owner_ata, farmer_vault, stake_receipt) groups, one
+  // for each gem. pNFTs need their Token Metadata accounts, so they go through `unstake`.
+}
+
+impl<'info> UnstakeMany<'info> {
+  fn release_gems(
+    &self,
+    vault: &AccountInfo<'info>,
+    owner_ata: &AccountInfo<'info>,
+    amount: u64
+  ) -> Result<()> {
+    let cpi_ctx = utils::transfer_spl_ctx(
+      vault.clone(),
+      owner_ata.clone(),
+      self.farmer.to_account_info(),
+      self.token_program.to_account_info()
+    );
+
+    anchor_spl::token::transfer(cpi_ctx.with_signer(&[&self.farmer.seeds()]), amount)
+  }
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeMany<'info>>) -> Result<()> {
+  require!(
+    !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() % 4 == 0,
+    StakingError::InvalidStakeAccounts
+  );
+
+  require!(ctx.accounts.farm.custody == Custody::Vault, StakingError::UnsupportedCustody);
+
+  let now = now_ts()?;
+  let farmer = ctx.accounts.farmer.key();
+  let delisted =
+    WhitelistProof::is_delisted_at(&ctx.accounts.whitelist_proof, ctx.program_id, now)?;
+  let mut total_rates = [0; MAX_REWARDS];
+  let mut legacy_rates = [0; MAX_REWARDS];
+  // Receipts still counted in the farmer's `whitelists`.
+  let mut tracked = 0;
+
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+
+  for group in ctx.remaining_accounts.chunks(4) {
+    let gem_mint = group[0].key();
+    let gem_owner_ata = Account::<TokenAccount>::try_from(&group[1])?;
+    let farmer_vault = &group[2];
+    let stake_receipt = &group[3];
+
+    require_keys_eq!(gem_owner_ata.mint, gem_mint, StakingError::InvalidStakeAccounts);
+    require_keys_eq!(
+      gem_owner_ata.owner,
+      ctx.accounts.owner.key(),
+      StakingError::InvalidStakeAccounts
+    );
+    require_keys_eq!(
+      farmer_vault.key(),
+      get_associated_token_address(&farmer, &gem_mint),
+      StakingError::InvalidStakeAccounts
+    );
+    // Token Metadata keeps pNFTs frozen, even in the vault, and nothing else freezes it.
+    require!(
+      !Account::<TokenAccount>::try_from(farmer_vault)?.is_frozen(),
+      StakingError::UnsupportedPnft
+    );
+
+    let (pda, _) = Pubkey::find_program_address(
+      &[StakeReceipt::PREFIX, farmer.as_ref(), gem_mint.as_ref()],
+      ctx.program_id
+    );
+    require_keys_eq!(stake_receipt.key(), pda, ErrorCode::ConstraintSeeds);
+    require_keys_eq!(*stake_receipt.owner, *ctx.program_id, ErrorCode::AccountOwnedByWrongProgram);
+
+    let mut receipt = StakeReceipt::try_deserialize(&mut &stake_receipt.try_borrow_data()?[..])?;
+    let lock_end = receipt.lock_end(ctx.accounts.lock.duration)?;
+
+    require!(receipt.is_running(), StakingError::GemNotStaked);
+    require!(!receipt.is_buffed(), StakingError::GemStillBuffed);
+    require_keys_eq!(receipt.lock, ctx.accounts.lock.key(), StakingError::LockMismatch);
+    require_keys_eq!(
+      receipt.whitelist,
+      ctx.accounts.whitelist_proof.key(),
+      StakingError::InvalidWhitelistType
+    );
+    require_gte!(now, lock_end, StakingError::GemStillLocked);
+    require!(receipt.delisted || !delisted, StakingError::DelistedStakes);
+
+    receipt.settle_earnings(&ctx.accounts.farmer)?;
+
+    let reserved = receipt.take_reservation(receipt.amount)?;
+
+    ctx.accounts.release_gems(farmer_vault, &group[1], receipt.amount)?;
+    ctx.accounts.farmer.release_emission(&mut ctx.accounts.farm, &reserved)?;
+
+    let totals = if receipt.legacy_bonus { &mut legacy_rates } else { &mut total_rates };
+
+    for (total, rate) in totals.iter_mut().zip(receipt.reward_rates) {
+      *total = total.checked_add(rate).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    if !receipt.delisted {
+      tracked += 1;
+    }
+
+    receipt.end_ts = Some(now);
+
+    let mut data = stake_receipt.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+  }
+
+  let stakes = ctx.remaining_accounts.len() / 4;
+
+  WhitelistProof::track_unstake(&ctx.accounts.whitelist_proof, ctx.program_id, stakes as u64)?;
+
+  ctx.accounts.farmer.untrack_early_exit(&ctx.accounts.lock, stakes as u32);
+  ctx.accounts.farmer.untrack_whitelist(&ctx.accounts.whitelist_proof.key(), tracked);
+  ctx.accounts.farmer.decrease_reward_rate(&mut ctx.accounts.farm, &total_rates)?;
+  ctx.accounts.farmer.decrease_legacy_reward_rate(&mut ctx.accounts.farm, &legacy_rates)
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::*, utils::{ self, now_ts } };
+
+/// Withdraws the unlocked part of a fungible position. The rest keeps earning at the same
+/// rate per token.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>, amount: u64) -> Result<()> {
+  let now = now_ts()?;
+  let staked = ctx.accounts.stake_receipt.amount;
+  let locked = ctx.accounts.stake_receipt.locked_amount(ctx.accounts.lock.duration, now);
+
+  require!(ctx.accounts.stake_receipt.is_running(), StakingError::GemNotStaked);
+  require!(amount > 0 && amount <= staked, StakingError::InvalidUnstakeAmount);
+  require_gte!(staked - locked, amount, StakingError::GemStillLocked);
+
+  ctx.accounts.farmer.update_accrued_rewards(&mut ctx.accounts.farm)?;
+
+  if amount == staked {
+    return ctx.accounts.close_stake(now, ctx.program_id, ctx.remaining_accounts);
+  }
+
+  require!(!ctx.accounts.stake_receipt.is_buffed(), StakingError::GemStillBuffed);
+  require!(ctx.accounts.farm.custody == Custody::Vault, StakingError::UnsupportedCustody);
+  ctx.accounts.check_delisting(now, ctx.program_id)?;
+
+  ctx.accounts.stake_receipt.settle_earnings(&ctx.accounts.farmer)?;
+
+  let mut decrements = [0; MAX_REWARDS];
+
+  for (rate, decrement) in ctx.accounts.stake_receipt.reward_rates
+    .iter_mut()
+    .zip(decrements.iter_mut()) {
+    *decrement = utils::mul_div(*rate, amount as u128, staked as u128)?;
+    *rate -= *decrement;
+  }
+
+  let reserved = ctx.accounts.stake_receipt.take_reservation(amount)?;
+  ctx.accounts.stake_receipt.amount = staked - amount;
+
+  ctx.accounts.release_gems(amount, ctx.remaining_accounts)?;
+  ctx.accounts.farmer.release_emission(&mut ctx.accounts.farm, &reserved)?;
+  ctx.accounts.farmer.decrease_reward_rate(&mut ctx.accounts.farm, &decrements)
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct UpdateFarm<'info> {
+    #[account(mut, has_one = authority)]
+    pub farm: Account<'info, Farm>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateFarm>, config: FarmConfig) -> Result<()> {
+    config.validate()?;
+
+    ctx.accounts.farm.config = config;
+
+    Ok(())
+}
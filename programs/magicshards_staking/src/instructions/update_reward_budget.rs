@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{ error::StakingError, state::*, utils::now_ts };
+
+#[derive(Accounts)]
+pub struct UpdateRewardBudget<'info> {
+  #[account(
+    mut,
+    constraint = farm.mode == EmissionMode::Pool @ StakingError::InvalidEmissionMode
+  )]
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    has_one = authority,
+    has_one = farm,
+    constraint = farm_manager.can(FarmManager::FUNDING) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
+  pub farm_manager: Account<'info, FarmManager>,
+
+  pub reward_mint: Account<'info, Mint>,
+
+  pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateRewardBudget>, budget_rate: u128) -> Result<()> {
+  let farm = &mut ctx.accounts.farm;
+  let index = farm.reward_index(&ctx.accounts.reward_mint.key())?;
+
+  // Distribute what was emitted at the old budget before changing it.
+  farm.update_pool(now_ts()?)?;
+  farm.rewards[index].budget_rate = budget_rate;
+
+  msg!("Farm {} now emits {} of {} per second", farm.key(), budget_rate, farm.rewards[index].mint);
+
+  Ok(())
+}
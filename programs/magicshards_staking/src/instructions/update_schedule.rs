@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::*, utils::now_ts };
+
+#[derive(Accounts)]
+pub struct UpdateSchedule<'info> {
+  #[account(mut)]
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    has_one = authority,
+    has_one = farm,
+    constraint = farm_manager.can(FarmManager::FUNDING) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
+  pub farm_manager: Account<'info, FarmManager>,
+
+  pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateSchedule>, start_ts: u64, end_ts: u64) -> Result<()> {
+  let schedule = Schedule { start_ts, end_ts };
+
+  ctx.accounts.farm.set_schedule(schedule, now_ts()?)?;
+
+  msg!("Farm {} now emits from {} to {}", ctx.accounts.farm.key(), start_ts, end_ts);
+
+  Ok(())
+}
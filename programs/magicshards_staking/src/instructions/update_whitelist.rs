@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::* };
+
+#[derive(Accounts)]
+pub struct UpdateWhitelist<'info> {
+  pub farm: Account<'info, Farm>,
+
+  #[account(
+    has_one = farm,
+    has_one = authority,
+    constraint = farm_manager.can(FarmManager::WHITELIST) @ StakingError::MissingPermission,
+    seeds = [FarmManager::PREFIX, farm.key().as_ref(), authority.key().as_ref()],
+    bump
+  )]
+  pub farm_manager: Account<'info, FarmManager>,
+
+  #[account(mut, has_one = farm)]
+  pub whitelist_proof: Account<'info, WhitelistProof>,
+
+  pub authority: Signer<'info>,
+}
+
+/// Running stakes keep their old rates until `refresh_receipt` is called on them.
+pub fn handler(ctx: Context<UpdateWhitelist>, reward_rates: [u128; MAX_REWARDS]) -> Result<()> {
+  ctx.accounts.farm.validate_rates(&reward_rates)?;
+
+  ctx.accounts.whitelist_proof.reward_rates = reward_rates;
+
+  msg!(
+    "Updated {} rates to {:?}",
+    ctx.accounts.whitelist_proof.whitelisted_address,
+    reward_rates
+  );
+
+  Ok(())
+}
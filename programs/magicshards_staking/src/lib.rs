@@ -14,30 +14,78 @@ use state::*;
 pub mod magicshards_staking {
   use super::*;
 
-  pub fn create_farm(ctx: Context<CreateFarm>) -> Result<()> {
-    instructions::create_farm::handler(ctx)
+  pub fn create_farm(
+    ctx: Context<CreateFarm>,
+    mode: EmissionMode,
+    custody: Custody
+  ) -> Result<()> {
+    instructions::create_farm::handler(ctx, mode, custody)
+  }
+
+  pub fn propose_authority(ctx: Context<ProposeAuthority>) -> Result<()> {
+    instructions::propose_authority::handler(ctx)
+  }
+
+  pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    instructions::accept_authority::handler(ctx)
+  }
+
+  pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    instructions::set_paused::handler(ctx, paused)
+  }
+
+  pub fn update_farm(ctx: Context<UpdateFarm>, config: FarmConfig) -> Result<()> {
+    instructions::update_farm::handler(ctx, config)
   }
 
-  pub fn add_manager(ctx: Context<AddManager>) -> Result<()> {
-    instructions::add_manager::handler(ctx)
+  pub fn add_manager(ctx: Context<AddManager>, permissions: u8) -> Result<()> {
+    instructions::add_manager::handler(ctx, permissions)
+  }
+
+  pub fn remove_manager(ctx: Context<RemoveManager>) -> Result<()> {
+    instructions::remove_manager::handler(ctx)
+  }
+
+  pub fn add_reward(ctx: Context<AddReward>) -> Result<()> {
+    instructions::add_reward::handler(ctx)
   }
 
   pub fn add_to_whitelist(
     ctx: Context<AddToWhitelist>,
-    reward_rate: f64,
-    whitelist_type: WhitelistType
+    reward_rates: [u128; 3],
+    whitelist_type: WhitelistType,
+    buff_config: Option<BuffConfig>
+  ) -> Result<()> {
+    instructions::add_to_whitelist::handler(ctx, reward_rates, whitelist_type, buff_config)
+  }
+
+  pub fn update_whitelist(
+    ctx: Context<UpdateWhitelist>,
+    reward_rates: [u128; 3]
   ) -> Result<()> {
-    instructions::add_to_whitelist::handler(ctx, reward_rate, whitelist_type)
+    instructions::update_whitelist::handler(ctx, reward_rates)
+  }
+
+  pub fn delist_whitelist(ctx: Context<DelistWhitelist>, at_ts: u64) -> Result<()> {
+    instructions::delist_whitelist::handler(ctx, at_ts)
   }
 
-  pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>) -> Result<()> {
-    instructions::remove_from_whitelist::handler(ctx)
+  pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>, force: bool) -> Result<()> {
+    instructions::remove_from_whitelist::handler(ctx, force)
   }
 
-  pub fn fund_reward(ctx: Context<FundReward>, amount: f64) -> Result<()> {
+  pub fn fund_reward(ctx: Context<FundReward>, amount: u64) -> Result<()> {
     instructions::fund_reward::handler(ctx, amount)
   }
 
+  pub fn update_reward_budget(ctx: Context<UpdateRewardBudget>, budget_rate: u128) -> Result<()> {
+    instructions::update_reward_budget::handler(ctx, budget_rate)
+  }
+
+  pub fn update_schedule(ctx: Context<UpdateSchedule>, start_ts: u64, end_ts: u64) -> Result<()> {
+    instructions::update_schedule::handler(ctx, start_ts, end_ts)
+  }
+
   pub fn create_locks<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateLocks<'info>>,
     lock_configs: Vec<LockConfig>
@@ -45,6 +93,13 @@ pub mod magicshards_staking {
     instructions::create_locks::handler(ctx, lock_configs)
   }
 
+  pub fn set_gem_levels<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetGemLevels<'info>>,
+    levels: Vec<GemLevelConfig>
+  ) -> Result<()> {
+    instructions::set_gem_levels::handler(ctx, levels)
+  }
+
   pub fn initialize_farmer(ctx: Context<InitializeFarmer>) -> Result<()> {
     instructions::initialize_farmer::handler(ctx)
   }
@@ -52,24 +107,68 @@ pub mod magicshards_staking {
   pub fn stake<'info>(
     ctx: Context<'_, '_, '_, 'info, Stake<'info>>,
     amount: u64,
-    level: u8
+    merkle_proof: Option<MerkleProof>
   ) -> Result<()> {
-    instructions::stake::handler(ctx, amount, level)
+    instructions::stake::handler(ctx, amount, merkle_proof)
+  }
+
+  pub fn stake_many<'info>(ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>) -> Result<()> {
+    instructions::stake_many::handler(ctx)
   }
 
   pub fn unstake<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
     instructions::unstake::handler(ctx)
   }
 
+  pub fn unstake_many<'info>(ctx: Context<'_, '_, '_, 'info, UnstakeMany<'info>>) -> Result<()> {
+    instructions::unstake_many::handler(ctx)
+  }
+
+  pub fn unstake_partial<'info>(
+    ctx: Context<'_, '_, '_, 'info, Unstake<'info>>,
+    amount: u64
+  ) -> Result<()> {
+    instructions::unstake_partial::handler(ctx, amount)
+  }
+
+  pub fn early_unstake<'info>(ctx: Context<'_, '_, '_, 'info, Unstake<'info>>) -> Result<()> {
+    instructions::early_unstake::handler(ctx)
+  }
+
   pub fn buff_pair<'info>(ctx: Context<'_, '_, '_, 'info, BuffPair<'info>>) -> Result<()> {
     instructions::buff_pair::handler(ctx)
   }
 
-  pub fn debuff_pair(ctx: Context<DebuffPair>) -> Result<()> {
+  pub fn debuff_pair<'info>(ctx: Context<'_, '_, '_, 'info, DebuffPair<'info>>) -> Result<()> {
     instructions::debuff_pair::handler(ctx)
   }
 
-  pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+  pub fn buff<'info>(ctx: Context<'_, '_, '_, 'info, BuffStakes<'info>>, count: u8) -> Result<()> {
+    instructions::buff::handler(ctx, count)
+  }
+
+  pub fn debuff<'info>(ctx: Context<'_, '_, '_, 'info, DebuffStakes<'info>>) -> Result<()> {
+    instructions::debuff::handler(ctx)
+  }
+
+  pub fn force_debuff<'info>(ctx: Context<'_, '_, '_, 'info, ForceDebuff<'info>>) -> Result<()> {
+    instructions::force_debuff::handler(ctx)
+  }
+
+  pub fn refresh_receipt<'info>(
+    ctx: Context<'_, '_, '_, 'info, RefreshReceipt<'info>>,
+    merkle_proof: Option<MerkleProof>
+  ) -> Result<()> {
+    instructions::refresh_receipt::handler(ctx, merkle_proof)
+  }
+
+  pub fn claim_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>) -> Result<()> {
     instructions::claim_rewards::handler(ctx)
   }
+
+  pub fn migrate_accounts<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateAccounts<'info>>
+  ) -> Result<()> {
+    instructions::migrate_accounts::handler(ctx)
+  }
 }
\ No newline at end of file
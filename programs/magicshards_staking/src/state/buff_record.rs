@@ -0,0 +1,46 @@
+use anchor_lang::{ error::ErrorCode, prelude::* };
+
+use crate::{ state::BuffConfig, utils };
+
+/// An active buff. Created when a buff token is deposited with `buff` or `buff_pair` and
+/// closed when it's withdrawn, so a farmer can only have one of each buff mint in use and
+/// the stakes it boosts can be listed without scanning receipts.
+#[account]
+pub struct BuffRecord {
+  pub farmer: Pubkey,
+  pub buff_mint: Pubkey,
+  // Receipts of the boosted stakes, at most the buff's arity.
+  pub receipts: Vec<Pubkey>,
+  // Multiplier applied to each of them, scaled by `utils::PRECISION`.
+  pub factor: u128,
+  pub buffed_at: u64,
+  pub expires_at: Option<u64>,
+  // Consumable buffs were burned, so there's nothing to send back.
+  pub consumed: bool,
+}
+
+impl BuffRecord {
+  pub const LEN: usize = 32 + 32 + 4 + (BuffConfig::MAX_ARITY as usize) * 32 + 16 + 8 + 9 + 1;
+  pub const PREFIX: &'static [u8] = b"buff_record";
+
+  /// Closes the record at `account`, sending its rent to `destination`, and returns it. Buffs
+  /// applied before records existed don't have one, which gives `None`. The address must
+  /// already be checked against the record's seeds.
+  pub fn take<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    program_id: &Pubkey
+  ) -> Result<Option<Self>> {
+    if account.data_is_empty() {
+      return Ok(None);
+    }
+
+    require_keys_eq!(*account.owner, *program_id, ErrorCode::AccountOwnedByWrongProgram);
+
+    let record = Self::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+    utils::close_account(account, destination)?;
+
+    Ok(Some(record))
+  }
+}
@@ -1,36 +1,348 @@
 use anchor_lang::prelude::*;
 
+use crate::{ error::StakingError, state::StakeReceipt, utils::{ mul_div, PRECISION } };
+
+/// How a farm's rewards are emitted.
+#[derive(Debug, Copy, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum EmissionMode {
+  // Every staked gem earns its own absolute rate, so the total payout grows with the
+  // number of stakers.
+  Rate,
+  // Each reward emits a fixed budget per second, split among stakers pro-rata to their
+  // reward rates, which act as weights.
+  Pool,
+}
+
+/// Where staked gems are kept.
+#[derive(Debug, Copy, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum Custody {
+  // Gems are moved to a vault owned by the farmer account.
+  Vault,
+  // Gems stay in the owner's wallet, delegated to the farmer account and frozen through the
+  // Token Metadata program. Only works for NFTs with a master edition.
+  Freeze,
+}
+
 #[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
 pub struct Reward {
   pub mint: Pubkey,
-  pub reserved: f64,
-  pub available: f64,
+  // Scaled by `utils::PRECISION`.
+  // Funded rewards already promised to farmers.
+  pub reserved: u128,
+  // Funded rewards that can still be promised.
+  pub available: u128,
+  // Pool mode only. Tokens emitted per second across all stakers.
+  pub budget_rate: u128,
+  // Pool mode only. Rewards accumulated per unit of weight, scaled once more by
+  // `utils::PRECISION`.
+  pub reward_per_weight: u128,
+  // Pool mode only. Sum of the reward rates of everything staked in the farm.
+  pub total_weight: u128,
+  // Rewards accrued to farmers that weren't funded yet. They're paid back out of
+  // `available` before anything new is promised.
+  pub owed: u128,
 }
 
 impl Reward {
-  pub const LEN: usize = 32 + 8 + 8;
+  pub const LEN: usize = 32 + 16 + 16 + 16 + 16 + 16 + 16;
 
   pub fn new(mint: Pubkey) -> Self {
     Self {
       mint,
-      reserved: 0.0,
-      available: 0.0,
+      reserved: 0,
+      available: 0,
+      budget_rate: 0,
+      reward_per_weight: 0,
+      total_weight: 0,
+      owed: 0,
+    }
+  }
+
+  /// Available rewards that aren't needed to pay back what's owed.
+  pub fn unpromised(&self) -> u128 {
+    self.available.saturating_sub(self.owed)
+  }
+
+  /// Emits the budget for `elapsed` seconds of emission, splitting it among the current
+  /// weight. The emitted amount is reserved, so it's capped by what the farm still has.
+  pub fn distribute(&mut self, elapsed: u64) -> Result<()> {
+    if self.total_weight == 0 {
+      return Ok(());
+    }
+
+    let budget = self.budget_rate
+      .checked_mul(elapsed as u128)
+      .ok_or(StakingError::ArithmeticError)?;
+    let emitted = budget.min(self.unpromised());
+
+    if emitted < budget {
+      msg!("Farm is out of {} rewards, {} could not be emitted.", self.mint, budget - emitted);
     }
+
+    self.try_reserve(emitted)?;
+
+    self.reward_per_weight = self.reward_per_weight
+      .checked_add(mul_div(emitted, PRECISION, self.total_weight)?)
+      .ok_or(StakingError::ArithmeticError)?;
+
+    Ok(())
+  }
+
+  /// Rewards owed to `weight` according to the current accumulator.
+  pub fn pool_share(&self, weight: u128) -> Result<u128> {
+    mul_div(self.reward_per_weight, weight, PRECISION)
+  }
+
+  pub fn try_fund(&mut self, amount: u128) -> Result<()> {
+    self.available = self.available.checked_add(amount).ok_or(StakingError::ArithmeticError)?;
+
+    Ok(())
+  }
+
+  /// Reserves rewards for a new promise, which can't use what's owed to farmers already.
+  pub fn try_promise(&mut self, amount: u128) -> Result<()> {
+    require_gte!(self.unpromised(), amount, StakingError::CouldNotReserveReward);
+
+    self.try_reserve(amount)
   }
+
+  pub fn try_reserve(&mut self, amount: u128) -> Result<()> {
+    self.available = self.available
+      .checked_sub(amount)
+      .ok_or(StakingError::CouldNotReserveReward)?;
+
+    self.reserved = self.reserved.checked_add(amount).ok_or(StakingError::ArithmeticError)?;
+
+    Ok(())
+  }
+
+  /// Returns reserved rewards that won't be paid out to the available pool.
+  pub fn try_unreserve(&mut self, amount: u128) -> Result<()> {
+    self.try_release(amount)?;
+    self.try_fund(amount)
+  }
+
+  pub fn try_release(&mut self, amount: u128) -> Result<()> {
+    self.reserved = self.reserved
+      .checked_sub(amount)
+      .ok_or(StakingError::CouldNotReleaseReward)?;
+
+    Ok(())
+  }
+}
+
+/// Emission window of a campaign. Rewards only accrue while `start_ts <= now < end_ts`.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct Schedule {
+  pub start_ts: u64,
+  pub end_ts: u64,
 }
 
+impl Schedule {
+  pub const LEN: usize = 8 + 8;
+
+  /// A window that is always open, which is how farms behave unless a schedule is set.
+  pub const ALWAYS: Self = Self {
+    start_ts: 0,
+    end_ts: u64::MAX,
+  };
+
+  /// Number of seconds in `[from, to)` that fall inside this window.
+  pub fn overlap(&self, from: u64, to: u64) -> u64 {
+    to.min(self.end_ts).saturating_sub(from.max(self.start_ts))
+  }
+}
+
+/// How buffs of the same category combine on a stake.
+#[derive(Debug, Copy, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum BuffStacking {
+  // Bonuses add up, so two x1.5 buffs make x2.
+  Additive,
+  // Factors multiply, so two x1.5 buffs make x2.25.
+  Multiplicative,
+}
+
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct BuffCategory {
+  pub stacking: BuffStacking,
+  // How many buffs of this category a stake can hold at once.
+  pub max_stack: u8,
+}
+
+impl BuffCategory {
+  pub const LEN: usize = 1 + 1;
+}
+
+/// Maximum number of buff categories. A buff whitelist's category indexes
+/// `FarmConfig::buff_categories`.
+pub const MAX_BUFF_CATEGORIES: usize = 4;
+
+/// Farm-level settings that can be changed with `update_farm`.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct FarmConfig {
+  // Decimals of the primary reward, used to convert trip effect bonuses to raw units.
+  pub level_decimals: u8,
+  // Rules for buffs applied from then on. Buffs keep the rule they were applied with.
+  pub buff_categories: [BuffCategory; 4],
+  // Minimum number of seconds of a new stake's emission that must be funded. Stakes
+  // reserve it even when their lock is shorter, so staking fails if the farm can't cover it.
+  pub reserve_secs: u64,
+}
+
+impl FarmConfig {
+  pub const LEN: usize = 1 + MAX_BUFF_CATEGORIES * BuffCategory::LEN + 8;
+
+  pub fn validate(&self) -> Result<()> {
+    for category in self.buff_categories.iter() {
+      require!(
+        category.max_stack > 0 && (category.max_stack as usize) <= StakeReceipt::MAX_BUFFS,
+        StakingError::InvalidBuffConfig
+      );
+    }
+
+    Ok(())
+  }
+
+  pub fn buff_category(&self, category: u8) -> Result<BuffCategory> {
+    self.buff_categories
+      .get(category as usize)
+      .copied()
+      .ok_or_else(|| error!(StakingError::InvalidBuffConfig))
+  }
+}
+
+/// Every category holds a single buff by default, which is how buffs used to work, and new
+/// stakes must be funded for at least a day.
+impl Default for FarmConfig {
+  fn default() -> Self {
+    Self {
+      level_decimals: 2,
+      buff_categories: [
+        BuffCategory {
+          stacking: BuffStacking::Multiplicative,
+          max_stack: 1,
+        };
+        MAX_BUFF_CATEGORIES
+      ],
+      reserve_secs: 86_400,
+    }
+  }
+}
+
+/// Maximum number of reward mints a farm can distribute. Per-reward arrays in the other
+/// accounts are indexed by the reward's position in `Farm::rewards`.
+pub const MAX_REWARDS: usize = 3;
+
 #[account]
 pub struct Farm {
   pub authority: Pubkey,
-  pub reward: Reward,
+  // Set by `propose_authority`, and becomes the authority once it accepts.
+  pub pending_authority: Option<Pubkey>,
+  // The authority that created the farm. It seeds the farm address, so it never changes.
+  pub creator: Pubkey,
+  // The first reward is the one the farm was created with, and seeds its address.
+  pub rewards: Vec<Reward>,
+  pub mode: EmissionMode,
+  // Fixed at creation, since receipts are released the same way they were staked.
+  pub custody: Custody,
+  pub schedule: Schedule,
+  // Seconds spent inside an emission window up to `emission_updated_at`. Farmers accrue
+  // over the difference between two readings of this clock, so changing the schedule
+  // doesn't affect what was already earned.
+  pub emission_seconds: u64,
+  pub emission_updated_at: u64,
+  // Pool mode only. Reading of the emission clock when the budget was last distributed.
+  pub pool_checkpoint: u64,
+  // Blocks staking, buffing and claiming. Unstaking is always allowed.
+  pub paused: bool,
+  pub config: FarmConfig,
   pub bump: [u8; 1],
 }
 
 impl Farm {
-  pub const LEN: usize = 32 + Reward::LEN + 1;
+  pub const LEN: usize =
+    32 + 33 + 32 + 4 + MAX_REWARDS * Reward::LEN + 1 + 1 + Schedule::LEN + 8 + 8 + 8 + 1 +
+    FarmConfig::LEN + 1;
   pub const PREFIX: &'static [u8] = b"farm";
 
   pub fn seeds(&self) -> [&[u8]; 4] {
-    [Self::PREFIX, self.authority.as_ref(), self.reward.mint.as_ref(), &self.bump]
+    [Self::PREFIX, self.creator.as_ref(), self.rewards[0].mint.as_ref(), &self.bump]
+  }
+
+  pub fn reward_index(&self, mint: &Pubkey) -> Result<usize> {
+    self.rewards
+      .iter()
+      .position(|reward| reward.mint == *mint)
+      .ok_or_else(|| error!(StakingError::InvalidRewardMint))
+  }
+
+  /// Rates past the farm's last reward would start paying as soon as one is added.
+  pub fn validate_rates(&self, reward_rates: &[u128; MAX_REWARDS]) -> Result<()> {
+    require!(
+      reward_rates[self.rewards.len()..].iter().all(|rate| *rate == 0),
+      StakingError::InvalidRewardRates
+    );
+
+    Ok(())
+  }
+
+  /// Reads the emission clock at `now`.
+  pub fn emission_at(&self, now: u64) -> Result<u64> {
+    self.emission_seconds
+      .checked_add(self.schedule.overlap(self.emission_updated_at, now))
+      .ok_or_else(|| error!(StakingError::ArithmeticError))
+  }
+
+  /// Distributes every reward's budget for the emission time since the last call. Only
+  /// does anything in pool mode.
+  pub fn update_pool(&mut self, now: u64) -> Result<()> {
+    if self.mode != EmissionMode::Pool {
+      return Ok(());
+    }
+
+    let emission = self.emission_at(now)?;
+    let elapsed = emission.saturating_sub(self.pool_checkpoint);
+
+    for reward in self.rewards.iter_mut() {
+      reward.distribute(elapsed)?;
+    }
+
+    self.pool_checkpoint = emission;
+
+    Ok(())
+  }
+
+  /// Replaces the emission window. A running campaign can only be extended, while one that
+  /// ended or hasn't started yet can be replaced by a window that starts in the future.
+  pub fn set_schedule(&mut self, schedule: Schedule, now: u64) -> Result<()> {
+    require_gt!(schedule.end_ts, schedule.start_ts, StakingError::InvalidSchedule);
+
+    let current = self.schedule;
+    let running = current.start_ts <= now && now < current.end_ts;
+
+    if running {
+      require_eq!(schedule.start_ts, current.start_ts, StakingError::InvalidSchedule);
+      require_gte!(schedule.end_ts, current.end_ts, StakingError::InvalidSchedule);
+    } else {
+      require_gte!(schedule.start_ts, now, StakingError::InvalidSchedule);
+    }
+
+    // Settle the pool with the old window before replacing it.
+    self.update_pool(now)?;
+
+    self.emission_seconds = self.emission_at(now)?;
+    self.emission_updated_at = now;
+    self.schedule = schedule;
+
+    Ok(())
+  }
+
+  pub fn add_reward(&mut self, mint: Pubkey) -> Result<()> {
+    require!(self.rewards.len() < MAX_REWARDS, StakingError::TooManyRewards);
+    require!(self.reward_index(&mint).is_err(), StakingError::RewardAlreadyAdded);
+
+    self.rewards.push(Reward::new(mint));
+
+    Ok(())
   }
 }
\ No newline at end of file
@@ -1,12 +1,31 @@
 use anchor_lang::prelude::*;
 
+/// Delegated farm manager. Each manager has its own PDA, seeded by the farm and the
+/// manager's authority, so instructions find it from the signer.
 #[account]
 pub struct FarmManager {
     pub farm: Pubkey,
     pub authority: Pubkey,
+    // Bitmask of the `FarmManager::*` permissions.
+    pub permissions: u8,
 }
 
 impl FarmManager {
-    pub const LEN: usize = 32 + 32;
+    pub const LEN: usize = 32 + 32 + 1;
     pub const PREFIX: &'static [u8] = b"farm_manager";
+
+    // Add and remove whitelists.
+    pub const WHITELIST: u8 = 1 << 0;
+    // Create locks.
+    pub const LOCKS: u8 = 1 << 1;
+    // Fund rewards and change how they're emitted.
+    pub const FUNDING: u8 = 1 << 2;
+    // Pause and unpause the farm.
+    pub const PAUSE: u8 = 1 << 3;
+
+    pub const ALL: u8 = Self::WHITELIST | Self::LOCKS | Self::FUNDING | Self::PAUSE;
+
+    pub fn can(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
 }
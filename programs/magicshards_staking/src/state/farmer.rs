@@ -1,73 +1,711 @@
 use anchor_lang::prelude::*;
 
-use crate::{ utils::now_ts };
+use crate::{
+  error::StakingError,
+  state::{ EmissionMode, Farm, Lock, Reward, WhitelistProof, MAX_REWARDS },
+  utils::{ self, now_ts },
+};
+
+/// Boost of an expiring buff, taken off the farmer's rates when it expires.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct BuffExpiry {
+  pub buff_mint: Pubkey,
+  pub expires_at: u64,
+  pub boost: [u128; 3],
+}
+
+impl BuffExpiry {
+  pub const LEN: usize = 32 + 8 + MAX_REWARDS * 16;
+}
+
+/// Running stakes of a farmer under a whitelist.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct WhitelistStakes {
+  pub whitelist: Pubkey,
+  pub stakes: u32,
+}
+
+impl WhitelistStakes {
+  pub const LEN: usize = 32 + 4;
+}
 
 #[account]
 pub struct Farmer {
   pub farm: Pubkey,
   pub owner: Pubkey,
-  accrued_rewards: f64,
-  total_reward_rate: f64,
-  last_update: u64,
+  // One entry per farm reward, all scaled by `utils::PRECISION`.
+  pub(crate) accrued_rewards: [u128; 3],
+  pub(crate) total_reward_rates: [u128; 3],
+  // Rewards set aside for this farmer's locked stakes that haven't accrued yet.
+  pub(crate) reserved_rewards: [u128; 3],
+  // Pool mode only. Share of each reward's accumulator that was already accrued.
+  pub(crate) reward_debts: [u128; 3],
+  // Rewards earned that the farm couldn't cover yet, accrued once it's funded.
+  pub(crate) owed_rewards: [u128; 3],
+  // Rewards accrued per unit of reward rate over the farmer's lifetime, scaled once more by
+  // `utils::PRECISION`. Receipts read their own earnings off it.
+  pub(crate) accrued_per_rate: [u128; 3],
+  // Running stakes whose lock can be left early. Their rewards can't be claimed until
+  // `early_exit_until`, or the early unstake penalty could be dodged by claiming first.
+  pub(crate) early_exit_stakes: u32,
+  pub(crate) early_exit_until: u64,
+  // Reading of the farm's emission clock at the last update.
+  pub(crate) emission_checkpoint: u64,
+  // Rate mode only. Boosts of the farmer's expiring buffs that haven't expired yet.
+  pub(crate) buff_expiries: Vec<BuffExpiry>,
+  // Whitelists the running stakes were staked under, until their delisting is applied.
+  // Stakes under a delisted whitelist keep earning until then, so claims check these first.
+  // Legacy receipts are only counted once refreshed.
+  pub(crate) whitelists: Vec<WhitelistStakes>,
   pub bump: [u8; 1],
 }
 
 impl Farmer {
-  pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1;
+  pub const LEN: usize =
+    32 + 32 + 6 * MAX_REWARDS * 16 + 4 + 8 + 8 + 4 + Self::MAX_BUFF_EXPIRIES * BuffExpiry::LEN +
+    4 + Self::MAX_WHITELISTS * WhitelistStakes::LEN + 1;
+  pub const MAX_BUFF_EXPIRIES: usize = 4;
+  pub const MAX_WHITELISTS: usize = 8;
   pub const PREFIX: &'static [u8] = b"farmer";
 
   pub fn seeds(&self) -> [&[u8]; 4] {
     [Self::PREFIX, self.farm.as_ref(), self.owner.as_ref(), self.bump.as_ref()]
   }
 
-  pub fn new(farm: Pubkey, owner: Pubkey, bump: u8) -> Result<Self> {
+  pub fn new(farm: &Account<Farm>, owner: Pubkey, bump: u8) -> Result<Self> {
     Ok(Self {
-      farm,
+      farm: farm.key(),
       owner,
-      accrued_rewards: 0.0,
-      total_reward_rate: 0.0,
-      last_update: now_ts()?,
+      accrued_rewards: [0; MAX_REWARDS],
+      total_reward_rates: [0; MAX_REWARDS],
+      reserved_rewards: [0; MAX_REWARDS],
+      reward_debts: [0; MAX_REWARDS],
+      owed_rewards: [0; MAX_REWARDS],
+      accrued_per_rate: [0; MAX_REWARDS],
+      early_exit_stakes: 0,
+      early_exit_until: 0,
+      emission_checkpoint: farm.emission_at(now_ts()?)?,
+      buff_expiries: vec![],
+      whitelists: vec![],
       bump: [bump],
     })
   }
 
-  pub fn claim_accrued(&mut self) -> Result<f64> {
-    self.update_accrued_rewards()?;
+  /// Counts `stakes` new stakes under `lock`, blocking claims until `lock_end` if they can
+  /// leave early.
+  pub fn track_early_exit(&mut self, lock: &Lock, stakes: u32, lock_end: u64) -> Result<()> {
+    if lock.allows_early_exit() {
+      self.early_exit_stakes = self.early_exit_stakes
+        .checked_add(stakes)
+        .ok_or(StakingError::ArithmeticError)?;
+      self.early_exit_until = self.early_exit_until.max(lock_end);
+    }
+
+    Ok(())
+  }
+
+  pub fn untrack_early_exit(&mut self, lock: &Lock, stakes: u32) {
+    if lock.allows_early_exit() {
+      self.early_exit_stakes = self.early_exit_stakes.saturating_sub(stakes);
+
+      if self.early_exit_stakes == 0 {
+        self.early_exit_until = 0;
+      }
+    }
+  }
+
+  pub fn can_claim(&self, now: u64) -> bool {
+    self.early_exit_stakes == 0 || now >= self.early_exit_until
+  }
+
+  pub fn track_whitelist(&mut self, whitelist: Pubkey, stakes: u32) -> Result<()> {
+    match self.whitelists.iter_mut().find(|entry| entry.whitelist == whitelist) {
+      Some(entry) => {
+        entry.stakes = entry.stakes.checked_add(stakes).ok_or(StakingError::ArithmeticError)?;
+      }
+      None => {
+        require!(self.whitelists.len() < Self::MAX_WHITELISTS, StakingError::TooManyWhitelists);
+
+        self.whitelists.push(WhitelistStakes { whitelist, stakes });
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn untrack_whitelist(&mut self, whitelist: &Pubkey, stakes: u32) {
+    if let Some(index) = self.whitelists.iter().position(|entry| entry.whitelist == *whitelist) {
+      let entry = &mut self.whitelists[index];
+      entry.stakes = entry.stakes.saturating_sub(stakes);
+
+      if entry.stakes == 0 {
+        self.whitelists.remove(index);
+      }
+    }
+  }
+
+  /// Fails if any of the farmer's `whitelists` was delisted by `now`, since the stakes under
+  /// it earned more than they should until `refresh_receipt` applies it. `proofs` are their
+  /// accounts, in the same order.
+  pub fn check_delistings(
+    &self,
+    proofs: &[AccountInfo],
+    program_id: &Pubkey,
+    now: u64
+  ) -> Result<()> {
+    require_eq!(proofs.len(), self.whitelists.len(), StakingError::InvalidWhitelistAccounts);
+
+    for (entry, proof) in self.whitelists.iter().zip(proofs) {
+      require_keys_eq!(proof.key(), entry.whitelist, StakingError::InvalidWhitelistAccounts);
+      require!(
+        !WhitelistProof::is_delisted_at(proof, program_id, now)?,
+        StakingError::DelistedStakes
+      );
+    }
+
+    Ok(())
+  }
+
+  /// Claims the whole tokens accrued for the reward at `index`. The fractional remainder
+  /// stays in the account so it keeps counting towards the next claim.
+  pub fn claim_accrued(&mut self, farm: &mut Farm, index: usize) -> Result<u64> {
+    self.update_accrued_rewards(farm)?;
+
+    let reward = farm.rewards.get_mut(index).ok_or(StakingError::InvalidRewardMint)?;
+    let accrued = &mut self.accrued_rewards[index];
+
+    let amount = utils::to_token_amount(*accrued)?;
+    let scaled_amount = utils::to_scaled(amount)?;
+
+    reward.try_release(scaled_amount)?;
+
+    *accrued = accrued.checked_sub(scaled_amount).ok_or(StakingError::ArithmeticError)?;
+
+    Ok(amount)
+  }
+
+  /// Sets rewards aside for this farmer, failing if the farm can't cover them.
+  pub fn reserve(&mut self, rewards: &mut [Reward], amounts: &[u128; MAX_REWARDS]) -> Result<()> {
+    for (index, amount) in amounts.iter().enumerate() {
+      if *amount == 0 {
+        continue;
+      }
+
+      let reward = rewards.get_mut(index).ok_or(StakingError::InvalidRewardMint)?;
+      reward.try_promise(*amount)?;
+
+      self.reserved_rewards[index] = self.reserved_rewards[index]
+        .checked_add(*amount)
+        .ok_or(StakingError::ArithmeticError)?;
+    }
+
+    Ok(())
+  }
+
+  /// Reserves the emission of `rates` from `from` to `until`, returning the amounts set aside
+  /// so the stake can keep track of them. Pool shares depend on everyone else's stake, so
+  /// nothing is set aside for the farmer there, but the farm must still hold its whole budget
+  /// for the period.
+  pub fn reserve_emission(
+    &mut self,
+    farm: &mut Farm,
+    rates: &[u128; MAX_REWARDS],
+    from: u64,
+    until: u64
+  ) -> Result<[u128; MAX_REWARDS]> {
+    let emission = farm.schedule.overlap(from, until) as u128;
+    let mut amounts = [0; MAX_REWARDS];
+
+    match farm.mode {
+      EmissionMode::Rate => {
+        for (amount, rate) in amounts.iter_mut().zip(rates) {
+          *amount = rate.checked_mul(emission).ok_or(StakingError::ArithmeticError)?;
+        }
+
+        self.reserve(&mut farm.rewards, &amounts)?;
+      }
+      EmissionMode::Pool => {
+        for reward in farm.rewards.iter() {
+          let budget = reward.budget_rate
+            .checked_mul(emission)
+            .ok_or(StakingError::ArithmeticError)?;
 
-    let reward = self.accrued_rewards;
+          require_gte!(reward.unpromised(), budget, StakingError::CouldNotReserveReward);
+        }
+      }
+    }
+
+    Ok(amounts)
+  }
 
-    // farm.reward.try_release(reward)?;
+  /// Gives back `reserved`, what a stake had left of its reservation. The schedule may have
+  /// changed since it was reserved, so it isn't recomputed from the rates.
+  pub fn release_emission(
+    &mut self,
+    farm: &mut Farm,
+    reserved: &[u128; MAX_REWARDS]
+  ) -> Result<()> {
+    self.forfeit(&mut farm.rewards, &[0; MAX_REWARDS], reserved)
+  }
+
+  /// Records rewards earned that the farm can't cover as owed to this farmer.
+  pub fn owe(&mut self, reward: &mut Reward, index: usize, amount: u128) -> Result<()> {
+    msg!("Farm is out of {} rewards, {} are owed until it's funded.", reward.mint, amount);
 
-    self.accrued_rewards = 0.0;
+    reward.owed = reward.owed.checked_add(amount).ok_or(StakingError::ArithmeticError)?;
+    self.owed_rewards[index] = self.owed_rewards[index]
+      .checked_add(amount)
+      .ok_or(StakingError::ArithmeticError)?;
 
-    Ok(reward)
+    Ok(())
   }
 
-  pub fn update_accrued_rewards(&mut self) -> Result<()> {
+  /// Accrues as much of what's owed to this farmer as the farm can cover now.
+  fn repay_owed(&mut self, rewards: &mut [Reward]) -> Result<()> {
+    for (index, reward) in rewards.iter_mut().enumerate().take(MAX_REWARDS) {
+      let repaid = self.owed_rewards[index].min(reward.available);
+
+      if repaid == 0 {
+        continue;
+      }
+
+      reward.try_reserve(repaid)?;
+      reward.owed = reward.owed.saturating_sub(repaid);
+
+      self.owed_rewards[index] -= repaid;
+      self.accrued_rewards[index] = self.accrued_rewards[index]
+        .checked_add(repaid)
+        .ok_or(StakingError::ArithmeticError)?;
+    }
+
+    Ok(())
+  }
+
+  /// Gives back up to `accrued` of this farmer's unclaimed rewards and up to `reserved` of
+  /// what was still set aside for them, so the farm can promise them to someone else.
+  pub fn forfeit(
+    &mut self,
+    rewards: &mut [Reward],
+    accrued: &[u128; MAX_REWARDS],
+    reserved: &[u128; MAX_REWARDS]
+  ) -> Result<()> {
+    for (index, reward) in rewards.iter_mut().enumerate().take(MAX_REWARDS) {
+      let accrued = accrued[index].min(self.accrued_rewards[index]);
+      let reserved = reserved[index].min(self.reserved_rewards[index]);
+
+      self.accrued_rewards[index] -= accrued;
+      self.reserved_rewards[index] -= reserved;
+
+      reward.try_unreserve(accrued + reserved)?;
+    }
+
+    Ok(())
+  }
+
+  /// Accrues rewards for the emission time since the last update.
+  pub fn update_accrued_rewards(&mut self, farm: &mut Farm) -> Result<()> {
     let now = now_ts()?;
-    let elapsed = now.saturating_sub(self.last_update);
-    let increment = self.total_reward_rate * (elapsed as f64);
+    let emission = farm.emission_at(now)?;
 
-    if increment > 0.0 {
-      // Before updating, we try to reserve the reward.
+    self.repay_owed(&mut farm.rewards)?;
+
+    match farm.mode {
+      EmissionMode::Rate => {
+        self.expire_buffs(farm, now)?;
+        self.accrue_rate(&mut farm.rewards, emission)?;
+      }
+      EmissionMode::Pool => {
+        farm.update_pool(now)?;
+        self.accrue_pool(&farm.rewards)?;
+      }
+    }
+
+    self.emission_checkpoint = emission;
+
+    Ok(())
+  }
 
-      self.accrued_rewards = self.accrued_rewards + increment;
+  /// Accrues up to each buff expiry that passed since the last update and drops its boost
+  /// there, so buffs stop paying on time without a transaction. Expiries that passed before
+  /// the farm's last schedule change are settled at the change.
+  fn expire_buffs(&mut self, farm: &mut Farm, now: u64) -> Result<()> {
+    self.buff_expiries.sort_by_key(|expiry| expiry.expires_at);
+
+    while let Some(expiry) = self.buff_expiries.first().filter(|e| e.expires_at <= now).copied() {
+      let emission = farm.emission_at(expiry.expires_at)?.max(self.emission_checkpoint);
+
+      self.accrue_rate(&mut farm.rewards, emission)?;
+      self.emission_checkpoint = emission;
+
+      for (rate, boost) in self.total_reward_rates.iter_mut().zip(expiry.boost) {
+        *rate = rate.checked_sub(boost).ok_or(StakingError::ArithmeticError)?;
+      }
+
+      self.buff_expiries.remove(0);
     }
 
-    self.last_update = now;
+    Ok(())
+  }
+
+  /// Pool mode accrual. The rewards were already reserved when the farm distributed them.
+  fn accrue_pool(&mut self, rewards: &[Reward]) -> Result<()> {
+    for (index, reward) in rewards.iter().enumerate().take(MAX_REWARDS) {
+      let share = reward.pool_share(self.total_reward_rates[index])?;
+      let pending = share.saturating_sub(self.reward_debts[index]);
+
+      self.track_accrual(index, pending)?;
+      self.accrued_rewards[index] = self.accrued_rewards[index]
+        .checked_add(pending)
+        .ok_or(StakingError::ArithmeticError)?;
+      self.reward_debts[index] = share;
+    }
+
+    Ok(())
+  }
+
+  /// Rate mode accrual. Rewards are drawn from this farmer's reservation first and then from
+  /// the farm's available rewards. Whatever the farm can't cover is owed instead of accrued,
+  /// so claims never exceed what the vault holds.
+  fn accrue_rate(&mut self, rewards: &mut [Reward], emission: u64) -> Result<()> {
+    let elapsed = emission.saturating_sub(self.emission_checkpoint);
+
+    for (index, reward) in rewards.iter_mut().enumerate().take(MAX_REWARDS) {
+      let increment = self.total_reward_rates[index]
+        .checked_mul(elapsed as u128)
+        .ok_or(StakingError::ArithmeticError)?;
+
+      if increment == 0 {
+        continue;
+      }
+
+      let from_reserved = increment.min(self.reserved_rewards[index]);
+      let missing = increment - from_reserved;
+      let from_available = missing.min(reward.unpromised());
+
+      if from_available < missing {
+        self.owe(reward, index, missing - from_available)?;
+      }
+
+      // Before updating, we try to reserve the reward.
+      reward.try_reserve(from_available)?;
+
+      self.reserved_rewards[index] -= from_reserved;
+      self.track_accrual(index, from_reserved + from_available)?;
+      self.accrued_rewards[index] = self.accrued_rewards[index]
+        .checked_add(from_reserved + from_available)
+        .ok_or(StakingError::ArithmeticError)?;
+    }
 
     Ok(())
   }
 
-  pub fn increase_reward_rate(&mut self, increment: f64) -> Result<()> {
-    self.total_reward_rate = self.total_reward_rate + increment;
+  /// Splits `amount` newly accrued for the reward at `index` over the current rates. Repaid
+  /// debts aren't tracked, since they were earned by whatever was staked back then.
+  fn track_accrual(&mut self, index: usize, amount: u128) -> Result<()> {
+    let rate = self.total_reward_rates[index];
+
+    if rate == 0 {
+      return Ok(());
+    }
+
+    self.accrued_per_rate[index] = self.accrued_per_rate[index]
+      .checked_add(utils::mul_div(amount, utils::PRECISION, rate)?)
+      .ok_or(StakingError::ArithmeticError)?;
 
     Ok(())
   }
 
-  pub fn decrease_reward_rate(&mut self, decrement: f64) -> Result<()> {
-    self.total_reward_rate = self.total_reward_rate - decrement;
+  /// Changes this farmer's reward rates. Must be called right after
+  /// `update_accrued_rewards`, since in pool mode it also moves the farm's total weight and
+  /// resets the reward debts.
+  fn set_reward_rates(&mut self, farm: &mut Farm, rates: [u128; MAX_REWARDS]) -> Result<()> {
+    if farm.mode == EmissionMode::Pool {
+      for (index, reward) in farm.rewards.iter_mut().enumerate().take(MAX_REWARDS) {
+        reward.total_weight = reward.total_weight
+          .checked_sub(self.total_reward_rates[index])
+          .and_then(|weight| weight.checked_add(rates[index]))
+          .ok_or(StakingError::ArithmeticError)?;
+
+        self.reward_debts[index] = reward.pool_share(rates[index])?;
+      }
+    }
+
+    self.total_reward_rates = rates;
 
     Ok(())
   }
-}
\ No newline at end of file
+
+  pub fn increase_reward_rate(
+    &mut self,
+    farm: &mut Farm,
+    increments: &[u128; MAX_REWARDS]
+  ) -> Result<()> {
+    let mut rates = self.total_reward_rates;
+
+    for (rate, increment) in rates.iter_mut().zip(increments) {
+      *rate = rate.checked_add(*increment).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    self.set_reward_rates(farm, rates)
+  }
+
+  pub fn decrease_reward_rate(
+    &mut self,
+    farm: &mut Farm,
+    decrements: &[u128; MAX_REWARDS]
+  ) -> Result<()> {
+    let mut rates = self.total_reward_rates;
+
+    for (rate, decrement) in rates.iter_mut().zip(decrements) {
+      *rate = rate.checked_sub(*decrement).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    self.set_reward_rates(farm, rates)
+  }
+
+  /// Takes the rates of a receipt migrated from the legacy program off the totals. They were
+  /// scaled from floats apart from the farmer's total, which can end up slightly below their
+  /// sum, so the last of them to leave takes whatever is left.
+  pub fn decrease_legacy_reward_rate(
+    &mut self,
+    farm: &mut Farm,
+    decrements: &[u128; MAX_REWARDS]
+  ) -> Result<()> {
+    let mut rates = self.total_reward_rates;
+
+    for (rate, decrement) in rates.iter_mut().zip(decrements) {
+      *rate = rate.saturating_sub(*decrement);
+    }
+
+    self.set_reward_rates(farm, rates)
+  }
+
+  /// Adds a buff's `boost` to this farmer's rates, scheduling it to come off at `expires_at`
+  /// if the buff expires. Pool weights are farm-wide, so only rate farms can expire them.
+  pub fn add_buff(
+    &mut self,
+    farm: &mut Farm,
+    buff_mint: Pubkey,
+    boost: &[u128; MAX_REWARDS],
+    expires_at: Option<u64>
+  ) -> Result<()> {
+    if let Some(expires_at) = expires_at {
+      require!(farm.mode == EmissionMode::Rate, StakingError::InvalidEmissionMode);
+      require!(
+        self.buff_expiries.len() < Self::MAX_BUFF_EXPIRIES,
+        StakingError::TooManyExpiringBuffs
+      );
+
+      self.buff_expiries.push(BuffExpiry { buff_mint, expires_at, boost: *boost });
+    }
+
+    // The extra emission isn't reserved up front, it's drawn from the farm's available
+    // rewards as it accrues.
+    self.update_accrued_rewards(farm)?;
+    self.increase_reward_rate(farm, boost)
+  }
+
+  /// Takes `stale` out of a buff's pending expiry without touching the rates, for stakes
+  /// that already left the farmer's totals with the buff on. Must happen before accruing, or
+  /// the expiry would drop their boost a second time.
+  pub fn untrack_buff_boost(&mut self, buff_mint: &Pubkey, stale: &[u128; MAX_REWARDS]) {
+    let expiry = self.buff_expiries.iter_mut().find(|expiry| expiry.buff_mint == *buff_mint);
+
+    if let Some(expiry) = expiry {
+      for (boost, stale) in expiry.boost.iter_mut().zip(stale) {
+        *boost = boost.saturating_sub(*stale);
+      }
+    }
+  }
+
+  /// Takes a buff's `boost` off this farmer's rates, unless it already expired.
+  pub fn remove_buff(
+    &mut self,
+    farm: &mut Farm,
+    buff_mint: &Pubkey,
+    boost: &[u128; MAX_REWARDS],
+    expires: bool
+  ) -> Result<()> {
+    self.update_accrued_rewards(farm)?;
+
+    if !expires {
+      return self.decrease_reward_rate(farm, boost);
+    }
+
+    match self.buff_expiries.iter().position(|expiry| expiry.buff_mint == *buff_mint) {
+      Some(index) => {
+        let expiry = self.buff_expiries.remove(index);
+        self.decrease_reward_rate(farm, &expiry.boost)
+      }
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    state::{
+      fixtures::{ farmer, receipt },
+      legacy::{ LegacyFarm, LegacyReward },
+      BuffConfig,
+      WhitelistType,
+    },
+    utils::PRECISION,
+  };
+
+  fn reward(available: u128) -> Reward {
+    let mut reward = Reward::new(Pubkey::new_unique());
+    reward.available = available;
+    reward
+  }
+
+  #[test]
+  fn shortfall_is_owed_and_repaid_once_funded() {
+    let mut farmer = farmer(PRECISION);
+    let mut rewards = [reward(60 * PRECISION)];
+
+    farmer.accrue_rate(&mut rewards, 100).unwrap();
+
+    assert_eq!(farmer.accrued_rewards[0], 60 * PRECISION);
+    assert_eq!(farmer.owed_rewards[0], 40 * PRECISION);
+    assert_eq!(rewards[0].owed, 40 * PRECISION);
+
+    rewards[0].try_fund(100 * PRECISION).unwrap();
+    farmer.repay_owed(&mut rewards).unwrap();
+
+    assert_eq!(farmer.accrued_rewards[0], 100 * PRECISION);
+    assert_eq!(farmer.owed_rewards[0], 0);
+    assert_eq!(rewards[0].owed, 0);
+    assert_eq!(rewards[0].reserved, 100 * PRECISION);
+    assert_eq!(rewards[0].available, 60 * PRECISION);
+  }
+
+  #[test]
+  fn owed_rewards_cannot_be_promised() {
+    let mut indebted = farmer(PRECISION);
+    let mut staker = farmer(0);
+    let mut rewards = [reward(0)];
+
+    indebted.accrue_rate(&mut rewards, 100).unwrap();
+    rewards[0].try_fund(150 * PRECISION).unwrap();
+
+    assert!(staker.reserve(&mut rewards, &[60 * PRECISION, 0, 0]).is_err());
+    assert!(staker.reserve(&mut rewards, &[50 * PRECISION, 0, 0]).is_ok());
+  }
+
+  #[test]
+  fn forfeited_reservation_is_available_again() {
+    let mut rewards = [reward(100 * PRECISION)];
+    let mut farmer = farmer(0);
+
+    farmer.reserve(&mut rewards, &[100 * PRECISION, 0, 0]).unwrap();
+    farmer.total_reward_rates = [PRECISION, 0, 0];
+    farmer.accrue_rate(&mut rewards, 30).unwrap();
+    farmer.forfeit(&mut rewards, &[0; MAX_REWARDS], &[70 * PRECISION, 0, 0]).unwrap();
+
+    assert_eq!(farmer.accrued_rewards[0], 30 * PRECISION);
+    assert_eq!(farmer.reserved_rewards[0], 0);
+    assert_eq!(rewards[0].available, 70 * PRECISION);
+  }
+
+  #[test]
+  fn stakes_release_what_is_left_of_their_reservation() {
+    let mut rewards = [reward(100 * PRECISION)];
+    let mut farmer = farmer(0);
+    let mut receipt = receipt(PRECISION);
+
+    receipt.add_reservation(&[100 * PRECISION, 0, 0]).unwrap();
+    farmer.reserve(&mut rewards, &receipt.reserved).unwrap();
+    farmer.total_reward_rates = [PRECISION, 0, 0];
+    farmer.accrue_rate(&mut rewards, 30).unwrap();
+    receipt.settle_earnings(&farmer).unwrap();
+
+    let reserved = receipt.take_reservation(receipt.amount).unwrap();
+    farmer.forfeit(&mut rewards, &[0; MAX_REWARDS], &reserved).unwrap();
+
+    assert_eq!(reserved[0], 70 * PRECISION);
+    assert_eq!(receipt.reserved[0], 0);
+    assert_eq!(farmer.reserved_rewards[0], 0);
+    assert_eq!(rewards[0].available, 70 * PRECISION);
+  }
+
+  #[test]
+  fn legacy_rates_take_what_is_left_of_the_totals() {
+    let mut farm = LegacyFarm {
+      authority: Pubkey::new_unique(),
+      reward: LegacyReward { mint: Pubkey::new_unique(), reserved: 0.0, available: 0.0 },
+      bump: [255],
+    }.migrate(0);
+    let mut farmer = farmer(PRECISION - 1);
+
+    assert!(farmer.decrease_reward_rate(&mut farm, &[PRECISION, 0, 0]).is_err());
+
+    farmer.decrease_legacy_reward_rate(&mut farm, &[PRECISION, 0, 0]).unwrap();
+
+    assert_eq!(farmer.total_reward_rates[0], 0);
+  }
+
+  #[test]
+  fn stakes_earn_their_share_of_what_accrued() {
+    let mut farmer = farmer(4 * PRECISION);
+    let mut rewards = [reward(200 * PRECISION)];
+    let mut receipt = receipt(PRECISION);
+
+    // Only half of the 400 emitted is funded. The rest is repaid later, but it isn't counted
+    // as any stake's earnings.
+    farmer.accrue_rate(&mut rewards, 100).unwrap();
+    receipt.settle_earnings(&farmer).unwrap();
+
+    assert_eq!(farmer.accrued_per_rate[0], 50 * PRECISION);
+    assert_eq!(receipt.earned[0], 50 * PRECISION);
+
+    rewards[0].try_fund(1_000 * PRECISION).unwrap();
+    farmer.emission_checkpoint = 100;
+    farmer.repay_owed(&mut rewards).unwrap();
+    farmer.accrue_rate(&mut rewards, 150).unwrap();
+    receipt.settle_earnings(&farmer).unwrap();
+
+    assert_eq!(receipt.earned[0], 100 * PRECISION);
+  }
+
+  fn proof_data(delisted_at: Option<u64>) -> Vec<u8> {
+    let proof = WhitelistProof {
+      whitelisted_address: Pubkey::new_unique(),
+      farm: Pubkey::new_unique(),
+      reward_rates: [PRECISION, 0, 0],
+      ty: WhitelistType::Mint,
+      active_stakes: 2,
+      delisted_at,
+      buff_config: BuffConfig::default(),
+    };
+    let mut data = vec![];
+
+    proof.try_serialize(&mut data).unwrap();
+    data
+  }
+
+  #[test]
+  fn claims_wait_until_delistings_are_applied() {
+    let whitelist = Pubkey::new_unique();
+    let mut farmer = farmer(PRECISION);
+    let (mut lamports, mut data) = (1, proof_data(Some(100)));
+    let proofs = [
+      AccountInfo::new(&whitelist, false, false, &mut lamports, &mut data, &crate::ID, false, 0),
+    ];
+
+    farmer.track_whitelist(whitelist, 2).unwrap();
+
+    assert!(farmer.check_delistings(&[], &crate::ID, 50).is_err());
+    assert!(farmer.check_delistings(&proofs, &crate::ID, 50).is_ok());
+    assert!(farmer.check_delistings(&proofs, &crate::ID, 100).is_err());
+
+    // Each refresh applies the delisting to one stake.
+    farmer.untrack_whitelist(&whitelist, 1);
+    assert!(farmer.check_delistings(&proofs, &crate::ID, 100).is_err());
+
+    farmer.untrack_whitelist(&whitelist, 1);
+    assert!(farmer.check_delistings(&[], &crate::ID, 100).is_ok());
+  }
+}
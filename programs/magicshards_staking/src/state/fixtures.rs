@@ -0,0 +1,60 @@
+//! Accounts shared by the unit tests.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ Buff, BuffStacking, Farmer, StakeReceipt, MAX_REWARDS };
+
+/// A running stake of one gem earning `rate` of the first reward.
+pub fn receipt(rate: u128) -> StakeReceipt {
+  StakeReceipt {
+    farmer: Pubkey::new_unique(),
+    mint: Pubkey::new_unique(),
+    lock: Pubkey::new_unique(),
+    whitelist: Pubkey::new_unique(),
+    start_ts: 0,
+    end_ts: None,
+    reserved_until: 0,
+    reserved: [0; MAX_REWARDS],
+    amount: 1,
+    top_up_amount: 0,
+    top_up_ts: 0,
+    reward_rates: [rate, 0, 0],
+    unbuffed_rates: [rate, 0, 0],
+    earned: [0; MAX_REWARDS],
+    earnings_checkpoint: [0; MAX_REWARDS],
+    buffs: vec![],
+    legacy_bonus: false,
+    delisted: false,
+  }
+}
+
+/// A permanent buff from a fresh mint.
+pub fn buff(category: u8, stacking: BuffStacking, factor: u128) -> Buff {
+  Buff {
+    key: Pubkey::new_unique(),
+    category,
+    stacking,
+    factor,
+    expires_at: None,
+  }
+}
+
+/// A farmer earning `rate` of the first reward, with nothing accrued yet.
+pub fn farmer(rate: u128) -> Farmer {
+  Farmer {
+    farm: Pubkey::new_unique(),
+    owner: Pubkey::new_unique(),
+    accrued_rewards: [0; MAX_REWARDS],
+    total_reward_rates: [rate, 0, 0],
+    reserved_rewards: [0; MAX_REWARDS],
+    reward_debts: [0; MAX_REWARDS],
+    owed_rewards: [0; MAX_REWARDS],
+    accrued_per_rate: [0; MAX_REWARDS],
+    early_exit_stakes: 0,
+    early_exit_until: 0,
+    emission_checkpoint: 0,
+    buff_expiries: vec![],
+    whitelists: vec![],
+    bump: [255],
+  }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::{ error::ErrorCode, prelude::* };
+
+use crate::error::StakingError;
+
+/// Rarity table entry, set by a farm manager. It replaces the level stakers used to pass
+/// themselves, so the trip effect bonus can't be claimed without being granted.
+#[account]
+pub struct GemLevel {
+  pub farm: Pubkey,
+  pub mint: Pubkey,
+  // Trip effect level, from 0 (none) to `GemLevel::MAX`.
+  pub level: u8,
+}
+
+impl GemLevel {
+  pub const LEN: usize = 32 + 32 + 1;
+  pub const PREFIX: &'static [u8] = b"gem_level";
+  pub const MAX: u8 = 5;
+
+  /// Level of the gem whose entry is `account`. Gems without an entry are level 0. The
+  /// address must already be checked against the entry's seeds.
+  pub fn read(account: &AccountInfo, program_id: &Pubkey) -> Result<u8> {
+    if account.data_is_empty() {
+      return Ok(0);
+    }
+
+    require_keys_eq!(*account.owner, *program_id, ErrorCode::AccountOwnedByWrongProgram);
+
+    let entry = Self::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+    require_gte!(Self::MAX, entry.level, StakingError::InvalidTripEffect);
+
+    Ok(entry.level)
+  }
+}
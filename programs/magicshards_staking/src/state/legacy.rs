@@ -0,0 +1,237 @@
+//! Account layouts of the first program version, from before reward accounting moved to
+//! fixed-point integers. They're only read by the `migrate_accounts` instruction.
+use anchor_lang::prelude::*;
+
+use crate::{ error::StakingError, state::*, utils::{ mul_div, PRECISION } };
+
+fn scale(value: f64) -> u128 {
+  // Float to int casts saturate, so negative or NaN values end up as 0.
+  (value * (PRECISION as f64)) as u128
+}
+
+fn unbuff(rates: [u128; MAX_REWARDS], factor: u128) -> Result<[u128; MAX_REWARDS]> {
+  require_gt!(factor, 0, StakingError::FactorMustBeGtZero);
+
+  let mut unbuffed = [0; MAX_REWARDS];
+
+  for (unbuffed, rate) in unbuffed.iter_mut().zip(rates) {
+    *unbuffed = mul_div(rate, PRECISION, factor)?;
+  }
+
+  Ok(unbuffed)
+}
+
+// Legacy farms had a single reward, which becomes the first one.
+fn first_reward(value: f64) -> [u128; MAX_REWARDS] {
+  let mut values = [0; MAX_REWARDS];
+  values[0] = scale(value);
+  values
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyReward {
+  pub mint: Pubkey,
+  pub reserved: f64,
+  pub available: f64,
+}
+
+impl LegacyReward {
+  pub const LEN: usize = 32 + 8 + 8;
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyFarm {
+  pub authority: Pubkey,
+  pub reward: LegacyReward,
+  pub bump: [u8; 1],
+}
+
+impl LegacyFarm {
+  pub const LEN: usize = 32 + LegacyReward::LEN + 1;
+
+  pub fn migrate(self, now: u64) -> Farm {
+    let mut reward = Reward::new(self.reward.mint);
+    reward.reserved = scale(self.reward.reserved);
+    reward.available = scale(self.reward.available);
+
+    Farm {
+      authority: self.authority,
+      pending_authority: None,
+      creator: self.authority,
+      paused: false,
+      config: FarmConfig::default(),
+      rewards: vec![reward],
+      mode: EmissionMode::Rate,
+      custody: Custody::Vault,
+      schedule: Schedule::ALWAYS,
+      emission_seconds: now,
+      emission_updated_at: now,
+      pool_checkpoint: now,
+      bump: self.bump,
+    }
+  }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyFarmer {
+  pub farm: Pubkey,
+  pub owner: Pubkey,
+  pub accrued_rewards: f64,
+  pub total_reward_rate: f64,
+  pub last_update: u64,
+  pub bump: [u8; 1],
+}
+
+impl LegacyFarmer {
+  pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1;
+
+  pub fn migrate(self) -> Farmer {
+    Farmer {
+      farm: self.farm,
+      owner: self.owner,
+      accrued_rewards: first_reward(self.accrued_rewards),
+      total_reward_rates: first_reward(self.total_reward_rate),
+      reserved_rewards: [0; MAX_REWARDS],
+      reward_debts: [0; MAX_REWARDS],
+      owed_rewards: [0; MAX_REWARDS],
+      accrued_per_rate: [0; MAX_REWARDS],
+      early_exit_stakes: 0,
+      early_exit_until: 0,
+      // Legacy farms were always emitting, so their clock reads the same as a timestamp.
+      emission_checkpoint: self.last_update,
+      buff_expiries: vec![],
+      whitelists: vec![],
+      bump: self.bump,
+    }
+  }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyBuff {
+  pub key: Pubkey,
+  pub factor: f64,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyStakeReceipt {
+  pub farmer: Pubkey,
+  pub mint: Pubkey,
+  pub lock: Pubkey,
+  pub start_ts: u64,
+  pub end_ts: Option<u64>,
+  pub amount: u64,
+  pub reward_rate: f64,
+  pub buff: Option<LegacyBuff>,
+}
+
+impl LegacyStakeReceipt {
+  // Matches the space the old program allocated, which is what's on chain.
+  pub const LEN: usize = 32 + 32 + 32 + 8 + 9 + 8 + 8 + 33;
+
+  pub fn migrate(self) -> Result<StakeReceipt> {
+    let reward_rates = first_reward(self.reward_rate);
+    let buffs: Vec<Buff> = self.buff
+      .map(|buff| Buff {
+        key: buff.key,
+        category: 0,
+        stacking: BuffStacking::Multiplicative,
+        factor: scale(buff.factor),
+        expires_at: None,
+      })
+      .into_iter()
+      .collect();
+
+    Ok(StakeReceipt {
+      farmer: self.farmer,
+      mint: self.mint,
+      lock: self.lock,
+      whitelist: Pubkey::default(),
+      start_ts: self.start_ts,
+      end_ts: self.end_ts,
+      // The legacy program didn't reserve anything.
+      reserved_until: 0,
+      reserved: [0; MAX_REWARDS],
+      amount: self.amount,
+      top_up_amount: 0,
+      top_up_ts: 0,
+      reward_rates,
+      // Legacy receipts only kept the buffed rate.
+      unbuffed_rates: match buffs.first() {
+        Some(buff) => unbuff(reward_rates, buff.factor)?,
+        None => reward_rates,
+      },
+      // Only read by early unstakes, which legacy locks don't allow.
+      earned: [0; MAX_REWARDS],
+      earnings_checkpoint: [0; MAX_REWARDS],
+      buffs,
+      legacy_bonus: true,
+      delisted: false,
+    })
+  }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyWhitelistProof {
+  pub whitelisted_address: Pubkey,
+  pub farm: Pubkey,
+  pub reward_rate: f64,
+  pub ty: WhitelistType,
+}
+
+impl LegacyWhitelistProof {
+  pub const LEN: usize = 32 + 32 + 8 + 1;
+
+  pub fn migrate(self) -> WhitelistProof {
+    WhitelistProof {
+      whitelisted_address: self.whitelisted_address,
+      farm: self.farm,
+      reward_rates: first_reward(self.reward_rate),
+      ty: self.ty,
+      active_stakes: 0,
+      delisted_at: None,
+      buff_config: BuffConfig::default(),
+    }
+  }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyLock {
+  pub farm: Pubkey,
+  pub duration: u64,
+  pub cooldown: u64,
+  pub bonus_factor: u8,
+}
+
+impl LegacyLock {
+  pub const LEN: usize = 32 + 8 + 8 + 1;
+
+  // The factor was meant as a percentage.
+  pub fn migrate(self) -> Lock {
+    Lock {
+      farm: self.farm,
+      duration: self.duration,
+      cooldown: self.cooldown,
+      bonus_bps: (self.bonus_factor as u16) * 100,
+      early_unstake: EarlyUnstake::Disabled,
+    }
+  }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyFarmManager {
+  pub farm: Pubkey,
+  pub authority: Pubkey,
+}
+
+impl LegacyFarmManager {
+  pub const LEN: usize = 32 + 32;
+
+  // Managers had every permission before they were scoped.
+  pub fn migrate(self) -> FarmManager {
+    FarmManager {
+      farm: self.farm,
+      authority: self.authority,
+      permissions: FarmManager::ALL,
+    }
+  }
+}
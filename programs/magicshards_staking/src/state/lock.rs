@@ -1,15 +1,36 @@
 use anchor_lang::prelude::*;
 
+/// What happens when a gem is unstaked before its lock period is over.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum EarlyUnstake {
+    // Gems can't leave before the lock ends.
+    Disabled,
+    // Forfeits this share of the stake's unclaimed rewards, in basis points.
+    Penalty { bps: u16 },
+    // Forfeits what the lock bonus added to the stake's unclaimed rewards.
+    ForfeitBonus,
+}
+
+impl EarlyUnstake {
+    pub const LEN: usize = 1 + 2;
+}
+
 #[account]
 #[derive(Debug)]
 pub struct Lock {
     pub farm: Pubkey,
     pub duration: u64,
     pub cooldown: u64,
-    pub bonus_factor: u8,
+    // Bonus on top of the base reward rate, in basis points (2_500 is +25%).
+    pub bonus_bps: u16,
+    pub early_unstake: EarlyUnstake,
 }
 
 impl Lock {
-    pub const LEN: usize = 32 + 8 + 8 + 1;
+    pub const LEN: usize = 32 + 8 + 8 + 2 + EarlyUnstake::LEN;
     pub const PREFIX: &'static [u8] = b"lock";
+
+    pub fn allows_early_exit(&self) -> bool {
+        self.duration > 0 && self.early_unstake != EarlyUnstake::Disabled
+    }
 }
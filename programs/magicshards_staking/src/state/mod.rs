@@ -1,13 +1,21 @@
+pub mod buff_record;
 pub mod farm;
 pub mod farm_manager;
 pub mod farmer;
+pub mod gem_level;
+pub mod legacy;
 pub mod lock;
 pub mod stake_receipt;
 pub mod whitelist_proof;
 
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+pub use buff_record::*;
 pub use farm::*;
 pub use farm_manager::*;
 pub use farmer::*;
+pub use gem_level::*;
 pub use lock::*;
 pub use stake_receipt::*;
 pub use whitelist_proof::*;
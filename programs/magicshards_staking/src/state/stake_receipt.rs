@@ -1,66 +1,407 @@
 use anchor_lang::prelude::*;
 
-use crate::error::StakingError;
+use crate::{
+  error::StakingError,
+  state::{ BuffStacking, Farmer, MAX_REWARDS },
+  utils::{ self, PRECISION },
+};
 
 #[account]
 pub struct StakeReceipt {
   pub farmer: Pubkey,
   pub mint: Pubkey,
   pub lock: Pubkey,
+  // WhitelistProof the gem was staked under. Default for receipts migrated from the legacy
+  // program until they're refreshed.
+  pub whitelist: Pubkey,
   pub start_ts: u64,
   pub end_ts: Option<u64>,
+  // End of the period the stake's emission was reserved for.
+  pub reserved_until: u64,
+  // Rewards still set aside for the stake. Its earnings draw from them as they're settled,
+  // and what's left is released when it closes.
+  pub reserved: [u128; 3],
   pub amount: u64,
-  pub reward_rate: f64,
-  // Public key of the NFT that is buffing this stake.
-  pub buff: Option<Buff>,
+  // Tokens added by top-ups that are locked on their own from `top_up_ts`, so the tokens
+  // staked before them keep their lock. Later top-ups join them while they're still locked.
+  pub top_up_amount: u64,
+  pub top_up_ts: u64,
+  // One rate per farm reward, scaled by `utils::PRECISION`. Includes the buffs.
+  pub reward_rates: [u128; 3],
+  // Rates before any buff, which the buffs are composed on. Only meaningful while `buffs`
+  // isn't empty.
+  pub unbuffed_rates: [u128; 3],
+  // Rewards the unbuffed rates earned up to the farmer's `accrued_per_rate` reading in
+  // `earnings_checkpoint`. Claimed rewards are included.
+  pub earned: [u128; 3],
+  pub earnings_checkpoint: [u128; 3],
+  // Buff NFTs boosting this stake, at most `StakeReceipt::MAX_BUFFS`.
+  pub buffs: Vec<Buff>,
+  // Set on receipts migrated from the legacy program, whose lock bonus was added as a flat
+  // rate instead of a percentage. Cleared by `refresh_receipt`.
+  pub legacy_bonus: bool,
+  // Set once `refresh_receipt` applied the delisting of its whitelist, which takes the stake
+  // off the farmer's `whitelists`.
+  pub delisted: bool,
 }
 
 #[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct Buff {
   pub key: Pubkey,
-  pub factor: f64,
+  pub category: u8,
+  // The category's rule when the buff was applied, so later rule changes don't affect it.
+  pub stacking: BuffStacking,
+  // Multiplier scaled by `utils::PRECISION`.
+  pub factor: u128,
+  // When the boost stops being paid. The rates keep it until the gem is debuffed, but the
+  // farmer's totals drop it on time.
+  pub expires_at: Option<u64>,
+}
+
+impl Buff {
+  pub const LEN: usize = 32 + 1 + 1 + 16 + 9;
 }
 
 impl StakeReceipt {
-  pub const LEN: usize = 32 + 32 + 32 + 8 + 9 + 8 + 8 + 33;
+  pub const LEN: usize =
+    32 + 32 + 32 + 32 + 8 + 9 + 8 + 8 + 8 + 8 + 5 * MAX_REWARDS * 16 + 4 +
+    Self::MAX_BUFFS * Buff::LEN + 1 + 1;
   pub const PREFIX: &'static [u8] = b"stake_receipt";
+  pub const MAX_BUFFS: usize = 4;
 
   pub fn is_running(&self) -> bool {
     self.end_ts.is_none()
   }
 
-  pub fn try_buff(&mut self, buff_key: Pubkey, factor: f64) -> Result<f64> {
-    require!(self.is_running(), StakingError::GemNotStaked);
-    require_gt!(factor, 0_f64, StakingError::FactorMustBeGtZero);
+  pub fn is_buffed(&self) -> bool {
+    !self.buffs.is_empty()
+  }
+
+  pub fn has_buff(&self, buff_key: &Pubkey) -> bool {
+    self.buffs.iter().any(|buff| buff.key == *buff_key)
+  }
+
+  /// The farmer has the boost of an expiring buff scheduled to come off its rates, so the
+  /// receipt can't be repriced or buffed again until it's debuffed.
+  pub fn has_expiring_buff(&self) -> bool {
+    self.buffs.iter().any(|buff| buff.expires_at.is_some())
+  }
+
+  /// Multiplier of all the buffs together, scaled by `utils::PRECISION`. Additive buffs add
+  /// up their bonus within a category, everything else multiplies.
+  fn buff_multiplier(&self) -> Result<u128> {
+    let mut multiplier = PRECISION;
+    let mut additive: Vec<(u8, u128)> = vec![];
 
-    if self.buff.is_some() {
-      return err!(StakingError::GemAlreadyBuffed);
+    for buff in self.buffs.iter() {
+      match buff.stacking {
+        BuffStacking::Multiplicative => {
+          multiplier = utils::mul_div(multiplier, buff.factor, PRECISION)?;
+        }
+        BuffStacking::Additive => {
+          match additive.iter_mut().find(|(category, _)| *category == buff.category) {
+            Some((_, sum)) => {
+              *sum = sum
+                .checked_add(buff.factor)
+                .and_then(|sum| sum.checked_sub(PRECISION))
+                .ok_or(StakingError::ArithmeticError)?;
+            }
+            None => additive.push((buff.category, buff.factor)),
+          }
+        }
+      }
     }
 
-    self.buff.replace(Buff {
-      key: buff_key,
-      factor,
-    });
+    for (_, factor) in additive {
+      multiplier = utils::mul_div(multiplier, factor, PRECISION)?;
+    }
+
+    Ok(multiplier)
+  }
+
+  /// Sets the rates to `unbuffed` with every buff applied, returning the old ones.
+  fn apply_buffs(&mut self, unbuffed: [u128; MAX_REWARDS]) -> Result<[u128; MAX_REWARDS]> {
+    let old_rates = self.reward_rates;
+    let multiplier = self.buff_multiplier()?;
 
-    let previous_reward_rate = self.reward_rate;
-    let buffed = self.reward_rate * factor;
+    self.unbuffed_rates = unbuffed;
+
+    for (rate, unbuffed) in self.reward_rates.iter_mut().zip(unbuffed) {
+      *rate = utils::mul_div(unbuffed, multiplier, PRECISION)?;
+    }
+
+    Ok(old_rates)
+  }
+
+  fn base_rates(&self) -> [u128; MAX_REWARDS] {
+    if self.is_buffed() { self.unbuffed_rates } else { self.reward_rates }
+  }
+
+  /// When every token of the stake is unlocked, including the latest top-up.
+  pub fn lock_end(&self, duration: u64) -> Result<u64> {
+    self.start_ts
+      .max(self.top_up_ts)
+      .checked_add(duration)
+      .ok_or_else(|| error!(StakingError::ArithmeticError))
+  }
+
+  /// Tokens that can't be unstaked yet. Topped up tokens are still locked after the rest.
+  pub fn locked_amount(&self, duration: u64, now: u64) -> u64 {
+    if now < self.start_ts.saturating_add(duration) {
+      self.amount
+    } else if now < self.top_up_ts.saturating_add(duration) {
+      self.top_up_amount.min(self.amount)
+    } else {
+      0
+    }
+  }
+
+  /// Adds `amount` tokens to a running stake, locking them for `duration` from `now`. A
+  /// top-up whose lock is over is unlocked like the rest, so it's dropped from the tranche.
+  pub fn top_up(&mut self, amount: u64, duration: u64, now: u64) -> Result<()> {
+    if now >= self.top_up_ts.saturating_add(duration) {
+      self.top_up_amount = 0;
+    }
 
-    self.reward_rate = buffed;
+    self.top_up_amount = self.top_up_amount
+      .checked_add(amount)
+      .ok_or(StakingError::ArithmeticError)?;
+    self.top_up_ts = now;
+    self.amount = self.amount.checked_add(amount).ok_or(StakingError::ArithmeticError)?;
 
-    Ok(buffed - previous_reward_rate)
+    Ok(())
   }
 
-  pub fn try_debuff(&mut self) -> Result<f64> {
+  /// Starts counting earnings from the farmer's current accrual.
+  pub fn reset_earnings(&mut self, farmer: &Farmer) {
+    self.earned = [0; MAX_REWARDS];
+    self.earnings_checkpoint = farmer.accrued_per_rate;
+  }
+
+  /// Adds what the unbuffed rates earned since the last settlement to `earned`, and takes
+  /// it off the reservation. Must be called right after the farmer accrues, and before the
+  /// unbuffed rates change. Buff boosts aren't counted, since a stake is debuffed before it
+  /// leaves.
+  pub fn settle_earnings(&mut self, farmer: &Farmer) -> Result<()> {
+    for (index, rate) in self.base_rates().iter().enumerate() {
+      let per_rate = farmer.accrued_per_rate[index].saturating_sub(self.earnings_checkpoint[index]);
+      let earned = utils::mul_div(per_rate, *rate, PRECISION)?;
+
+      self.earned[index] = self.earned[index]
+        .checked_add(earned)
+        .ok_or(StakingError::ArithmeticError)?;
+      self.earnings_checkpoint[index] = farmer.accrued_per_rate[index];
+      self.reserved[index] = self.reserved[index].saturating_sub(earned);
+    }
+
+    Ok(())
+  }
+
+  pub fn add_reservation(&mut self, amounts: &[u128; MAX_REWARDS]) -> Result<()> {
+    for (reserved, amount) in self.reserved.iter_mut().zip(amounts) {
+      *reserved = reserved.checked_add(*amount).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    Ok(())
+  }
+
+  /// Takes the part of the reservation backing `amount` of the staked tokens, all of it when
+  /// they all leave. Earnings must be settled first.
+  pub fn take_reservation(&mut self, amount: u64) -> Result<[u128; MAX_REWARDS]> {
+    let mut taken = [0; MAX_REWARDS];
+
+    for (taken, reserved) in taken.iter_mut().zip(self.reserved.iter_mut()) {
+      *taken = utils::mul_div(*reserved, amount as u128, self.amount as u128)?;
+      *reserved -= *taken;
+    }
+
+    Ok(taken)
+  }
+
+  /// Replaces the receipt's rates with freshly computed unbuffed `rates`, reapplying its
+  /// buffs. Legacy receipts lose their flat bonus this way. Returns the old rates so the
+  /// farmer's totals can be moved.
+  pub fn reprice_to(&mut self, rates: [u128; MAX_REWARDS]) -> Result<[u128; MAX_REWARDS]> {
     require!(self.is_running(), StakingError::GemNotStaked);
+    require!(!self.has_expiring_buff(), StakingError::GemStillBuffed);
 
-    match self.buff.take() {
-      Some(Buff { factor, .. }) => {
-        let previous_reward_rate = self.reward_rate;
-        let debuffed = self.reward_rate / factor;
-        self.reward_rate = debuffed;
-        Ok(previous_reward_rate - debuffed)
-      }
-      None => err!(StakingError::GemNotBuffed),
+    // Fresh rates don't carry the legacy bonus anymore.
+    self.legacy_bonus = false;
+
+    self.apply_buffs(rates)
+  }
+
+  /// Adds `buff` to the stake, returning how much each rate increased. A category holds up
+  /// to `max_stack` buffs, and expiring buffs can't be stacked with anything.
+  pub fn try_buff(&mut self, buff: Buff, max_stack: u8) -> Result<[u128; MAX_REWARDS]> {
+    require!(self.is_running(), StakingError::GemNotStaked);
+    require_gt!(buff.factor, 0, StakingError::FactorMustBeGtZero);
+    require!(!self.has_buff(&buff.key), StakingError::GemAlreadyBuffed);
+    require!(
+      !self.has_expiring_buff() && (buff.expires_at.is_none() || !self.is_buffed()),
+      StakingError::ExpiringBuffNotStackable
+    );
+
+    let stacked = self.buffs
+      .iter()
+      .filter(|other| other.category == buff.category)
+      .count();
+
+    require!(
+      stacked < (max_stack as usize) && self.buffs.len() < Self::MAX_BUFFS,
+      StakingError::GemAlreadyBuffed
+    );
+
+    let unbuffed = self.base_rates();
+    self.buffs.push(buff);
+
+    let old_rates = self.apply_buffs(unbuffed)?;
+    let mut increments = [0; MAX_REWARDS];
+
+    for (increment, (rate, old_rate)) in increments
+      .iter_mut()
+      .zip(self.reward_rates.iter().zip(old_rates)) {
+      *increment = rate.checked_sub(old_rate).ok_or(StakingError::ArithmeticError)?;
     }
+
+    Ok(increments)
+  }
+
+  /// Removes the buff from `buff_key`, recomputing the rates from the unbuffed ones so the
+  /// others are unaffected. Returns how much each rate decreased.
+  pub fn try_debuff(&mut self, buff_key: &Pubkey) -> Result<[u128; MAX_REWARDS]> {
+    require!(self.is_running(), StakingError::GemNotStaked);
+
+    self.force_debuff(buff_key)
+  }
+
+  /// Same as `try_debuff`, whatever the state of the stake. A stake that isn't running left
+  /// the farmer's totals with its buffed rates, so its decrements are already accounted for.
+  pub fn force_debuff(&mut self, buff_key: &Pubkey) -> Result<[u128; MAX_REWARDS]> {
+    let index = self.buffs
+      .iter()
+      .position(|buff| buff.key == *buff_key)
+      .ok_or(StakingError::GemNotBuffed)?;
+
+    let unbuffed = self.unbuffed_rates;
+    self.buffs.remove(index);
+
+    let old_rates = self.apply_buffs(unbuffed)?;
+    let mut decrements = [0; MAX_REWARDS];
+
+    for (decrement, (rate, old_rate)) in decrements
+      .iter_mut()
+      .zip(self.reward_rates.iter().zip(old_rates)) {
+      *decrement = old_rate.checked_sub(*rate).ok_or(StakingError::ArithmeticError)?;
+    }
+
+    Ok(decrements)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::fixtures::{ buff, receipt };
+
+  const X1_5: u128 = (PRECISION * 3) / 2;
+
+  #[test]
+  fn additive_buffs_add_up_their_bonus() {
+    let mut receipt = receipt(PRECISION);
+
+    receipt.try_buff(buff(0, BuffStacking::Additive, X1_5), 2).unwrap();
+    receipt.try_buff(buff(0, BuffStacking::Additive, X1_5), 2).unwrap();
+
+    assert_eq!(receipt.reward_rates[0], 2 * PRECISION);
+  }
+
+  #[test]
+  fn multiplicative_buffs_compound() {
+    let mut receipt = receipt(PRECISION);
+
+    receipt.try_buff(buff(0, BuffStacking::Multiplicative, X1_5), 2).unwrap();
+    receipt.try_buff(buff(0, BuffStacking::Multiplicative, X1_5), 2).unwrap();
+
+    assert_eq!(receipt.reward_rates[0], (PRECISION * 9) / 4);
+  }
+
+  #[test]
+  fn category_holds_up_to_max_stack() {
+    let mut receipt = receipt(PRECISION);
+
+    receipt.try_buff(buff(0, BuffStacking::Additive, X1_5), 1).unwrap();
+
+    assert!(receipt.try_buff(buff(0, BuffStacking::Additive, X1_5), 1).is_err());
+    assert!(receipt.try_buff(buff(1, BuffStacking::Additive, X1_5), 1).is_ok());
+  }
+
+  #[test]
+  fn debuffing_any_buff_leaves_exactly_the_others() {
+    let first = buff(0, BuffStacking::Additive, X1_5);
+    let second = buff(1, BuffStacking::Multiplicative, 3 * PRECISION);
+    let third = buff(0, BuffStacking::Additive, 2 * PRECISION);
+
+    let mut stacked = receipt(7_777_777);
+    let mut increments = [0; MAX_REWARDS];
+
+    for buff in [first, second, third].iter() {
+      increments[0] += stacked.try_buff(*buff, 2).unwrap()[0];
+    }
+
+    let decrement = stacked.try_debuff(&second.key).unwrap();
+
+    let mut expected = receipt(7_777_777);
+    expected.try_buff(first, 2).unwrap();
+    expected.try_buff(third, 2).unwrap();
+
+    assert_eq!(stacked.reward_rates, expected.reward_rates);
+    assert_eq!(stacked.reward_rates[0], 7_777_777 + increments[0] - decrement[0]);
+
+    stacked.try_debuff(&first.key).unwrap();
+    stacked.try_debuff(&third.key).unwrap();
+
+    assert_eq!(stacked.reward_rates, [7_777_777, 0, 0]);
+  }
+
+  #[test]
+  fn expiring_buffs_do_not_stack() {
+    let mut receipt = receipt(PRECISION);
+    let mut expiring = buff(0, BuffStacking::Additive, X1_5);
+    expiring.expires_at = Some(100);
+
+    receipt.try_buff(expiring, 2).unwrap();
+
+    assert!(receipt.try_buff(buff(1, BuffStacking::Additive, X1_5), 2).is_err());
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn top_ups_are_locked_without_relocking_the_stake() {
+    let mut receipt = receipt(PRECISION);
+    receipt.amount = 10;
+
+    receipt.top_up(5, 100, 60).unwrap();
+
+    assert_eq!(receipt.amount, 15);
+    assert_eq!(receipt.locked_amount(100, 99), 15);
+    assert_eq!(receipt.locked_amount(100, 100), 5);
+    assert_eq!(receipt.locked_amount(100, 160), 0);
+    assert_eq!(receipt.lock_end(100).unwrap(), 160);
+  }
+
+  #[test]
+  fn top_ups_join_the_locked_tranche_until_it_unlocks() {
+    let mut receipt = receipt(PRECISION);
+    receipt.amount = 10;
+
+    receipt.top_up(5, 100, 150).unwrap();
+    receipt.top_up(5, 100, 200).unwrap();
+
+    assert_eq!(receipt.locked_amount(100, 250), 10);
+
+    receipt.top_up(5, 100, 300).unwrap();
+
+    assert_eq!(receipt.top_up_amount, 5);
+    assert_eq!(receipt.locked_amount(100, 300), 5);
+  }
+}
@@ -1,8 +1,12 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{ error::ErrorCode, prelude::*, solana_program::keccak::hashv };
 use anchor_spl::token::Mint;
 use solutils::wrappers::metadata::{ MetadataAccount, TokenMetadata };
 
-use crate::utils::metadata_creator;
+use crate::{
+  error::StakingError,
+  state::{ MAX_BUFF_CATEGORIES, MAX_REWARDS },
+  utils::{ metadata_collection, metadata_creator },
+};
 
 #[derive(Debug, Copy, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum WhitelistType {
@@ -12,43 +16,206 @@ pub enum WhitelistType {
   Mint,
   // A NFT that will be used to buff the staking accounts.
   Buff,
+  // NFTs in a verified Metaplex collection, whitelisted by the collection mint.
+  Collection,
+  // Any mint in a merkle tree whose root is the whitelisted address. Each leaf carries its
+  // own reward rates, so the proof's `reward_rates` are unused.
+  MerkleRoot,
+}
+
+/// Settings of a buff whitelist, ignored by every other type.
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct BuffConfig {
+  // How many stakes a single buff token boosts.
+  pub arity: u8,
+  // Seconds after which the boost stops applying. Permanent buffs last until debuffed.
+  pub duration: Option<u64>,
+  // The token is burned when used instead of being escrowed in the buff vault.
+  pub consumable: bool,
+  // Index in the farm's `buff_categories`, which decides how it stacks with other buffs.
+  pub category: u8,
+}
+
+impl BuffConfig {
+  pub const LEN: usize = 1 + 9 + 1 + 1;
+  pub const MAX_ARITY: u8 = 5;
+
+  pub fn validate(&self) -> Result<()> {
+    require!(
+      self.arity > 0 &&
+        self.arity <= Self::MAX_ARITY &&
+        self.duration != Some(0) &&
+        (self.category as usize) < MAX_BUFF_CATEGORIES,
+      StakingError::InvalidBuffConfig
+    );
+
+    Ok(())
+  }
+
+  pub fn expires_at(&self, now: u64) -> Option<u64> {
+    self.duration.map(|duration| now.saturating_add(duration))
+  }
+}
+
+/// Buffs used to be permanent and come in pairs, which is what proofs created before they
+/// were configurable get.
+impl Default for BuffConfig {
+  fn default() -> Self {
+    Self {
+      arity: 2,
+      duration: None,
+      consumable: false,
+      category: 0,
+    }
+  }
+}
+
+/// Proves that a mint and its reward rates are a leaf of a `MerkleRoot` whitelist.
+#[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct MerkleProof {
+  pub reward_rates: [u128; 3],
+  // Sibling hashes from the leaf up to the root.
+  pub path: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+  // Leaves and nodes are hashed with different prefixes, so a node can't pass as a leaf.
+  const LEAF_PREFIX: &'static [u8] = &[0];
+  const NODE_PREFIX: &'static [u8] = &[1];
+
+  /// `keccak(0x00 || mint || reward_rates)`, with the rates as little-endian u128s.
+  pub fn leaf(mint: &Pubkey, reward_rates: &[u128; MAX_REWARDS]) -> [u8; 32] {
+    let rates: Vec<u8> = reward_rates.iter().flat_map(|rate| rate.to_le_bytes()).collect();
+
+    hashv(&[Self::LEAF_PREFIX, mint.as_ref(), rates.as_slice()]).to_bytes()
+  }
+
+  /// Folds the path into a root. Pairs are sorted before hashing, so the path doesn't need
+  /// to say which side each sibling is on.
+  pub fn root(&self, mint: &Pubkey) -> [u8; 32] {
+    self.path.iter().fold(Self::leaf(mint, &self.reward_rates), |node, sibling| {
+      let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+
+      hashv(&[Self::NODE_PREFIX, &left[..], &right[..]]).to_bytes()
+    })
+  }
 }
 
 #[account]
 pub struct WhitelistProof {
   pub whitelisted_address: Pubkey,
   pub farm: Pubkey,
-  // Tokens/gem/sec
-  // If the type is "Buff" then this is a multiplier
-  pub reward_rate: f64,
+  // Tokens/gem/sec for each farm reward, scaled by `utils::PRECISION`.
+  // If the type is "Buff" then the first entry is a multiplier
+  pub reward_rates: [u128; 3],
   pub ty: WhitelistType,
+  // Running stakes staked under this whitelist. The proof can't be closed while there are
+  // any, unless forced.
+  pub active_stakes: u64,
+  // Set by `delist_whitelist`. Stakes stop earning from then on, and can't be claimed or
+  // unstaked until `refresh_receipt` is cranked on them.
+  pub delisted_at: Option<u64>,
+  pub buff_config: BuffConfig,
 }
 
 impl WhitelistProof {
-  pub const LEN: usize = 32 + 32 + 8 + 1;
+  pub const LEN: usize = 32 + 32 + MAX_REWARDS * 16 + 1 + 8 + 9 + BuffConfig::LEN;
   pub const PREFIX: &'static [u8] = b"collection_data";
 
+  pub fn is_delisted(&self, now: u64) -> bool {
+    self.delisted_at.map_or(false, |delisted_at| delisted_at <= now)
+  }
+
+  pub fn track_stakes(&mut self, count: u64) -> Result<()> {
+    self.active_stakes = self.active_stakes
+      .checked_add(count)
+      .ok_or(StakingError::ArithmeticError)?;
+
+    Ok(())
+  }
+
+  /// Whether the proof at `account` was delisted by `now`. Proofs that were force closed,
+  /// and the default address legacy receipts point to, never are.
+  pub fn is_delisted_at(account: &AccountInfo, program_id: &Pubkey, now: u64) -> Result<bool> {
+    if account.owner != program_id || account.data_is_empty() {
+      return Ok(false);
+    }
+
+    let proof = Self::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+    Ok(proof.is_delisted(now))
+  }
+
+  /// Takes `count` stakes off the proof at `account`, which must be writable. Proofs that
+  /// were force closed, and the default address legacy receipts point to, are skipped. That
+  /// address is read-only, so callers can't mark the account `mut` and leave it to this.
+  pub fn track_unstake(account: &AccountInfo, program_id: &Pubkey, count: u64) -> Result<()> {
+    if account.owner != program_id || account.data_is_empty() {
+      return Ok(());
+    }
+
+    require!(account.is_writable, ErrorCode::ConstraintMut);
+
+    let mut proof = Self::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    // Legacy proofs didn't count their stakes.
+    proof.active_stakes = proof.active_stakes.saturating_sub(count);
+
+    let mut data = account.try_borrow_mut_data()?;
+    proof.try_serialize(&mut &mut data[..])
+  }
+
+  /// Multiplier applied by a buff whitelist.
+  pub fn buff_factor(&self) -> u128 {
+    self.reward_rates[0]
+  }
+
+  /// Base reward rates of `mint` under this whitelist. Merkle whitelists read them from the
+  /// leaf, after checking it against the root.
+  pub fn whitelisted_rates(
+    &self,
+    mint: &Pubkey,
+    merkle_proof: Option<&MerkleProof>
+  ) -> Result<[u128; MAX_REWARDS]> {
+    match self.ty {
+      WhitelistType::MerkleRoot => {
+        let merkle_proof = merkle_proof.ok_or(StakingError::InvalidMerkleProof)?;
+
+        require!(
+          merkle_proof.root(mint) == self.whitelisted_address.to_bytes(),
+          StakingError::InvalidMerkleProof
+        );
+
+        Ok(merkle_proof.reward_rates)
+      }
+      _ => Ok(self.reward_rates),
+    }
+  }
+
   pub fn validate<'info>(
     proof: &Account<Self>,
     mint: &Account<'info, Mint>,
     program_id: &Pubkey,
-    remaining_accounts: &[AccountInfo<'info>]
+    metadata: Option<&AccountInfo<'info>>
   ) -> Result<()> {
     use WhitelistType::*;
     let creator_or_mint_key = match proof.ty {
       Mint => mint.key(),
-      Buff | Creator => {
-        // If we need to check the creator, then we look at the remaining accounts so we
-        // can access this mint's metadata account.
-        let raw_account = remaining_accounts.first();
-        let metadata = raw_account // Option<T>
+      // The leaf is checked by `whitelisted_rates`, which has the merkle proof.
+      MerkleRoot => proof.whitelisted_address,
+      Buff | Creator | Collection => {
+        // If we need to check the creator or collection, then we need this mint's metadata
+        // account.
+        let metadata = metadata // Option<T>
           .ok_or_else(|| {
             msg!("Metadata account for mint {} was not given.", mint.key());
             ProgramError::InvalidAccountData
           })
           .map(|acc| validate_metadata_account(acc, &mint.to_account_info()))??;
 
-        metadata_creator(&metadata)?
+        match proof.ty {
+          Collection => metadata_collection(&metadata)?,
+          _ => metadata_creator(&metadata)?,
+        }
       }
     };
 
@@ -77,4 +244,112 @@ pub fn validate_metadata_account<'info>(
   require_keys_eq!(metadata.key(), pk);
 
   MetadataAccount::try_deserialize(&mut metadata.try_borrow_mut_data()?.as_ref())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[MerkleProof::NODE_PREFIX, &left[..], &right[..]]).to_bytes()
+  }
+
+  #[test]
+  fn single_leaf_tree_is_its_own_root() {
+    let mint = Pubkey::new_unique();
+    let rates = [7, 0, 0];
+    let proof = MerkleProof { reward_rates: rates, path: vec![] };
+
+    assert_eq!(proof.root(&mint), MerkleProof::leaf(&mint, &rates));
+  }
+
+  #[test]
+  fn every_leaf_proves_the_same_root() {
+    let mints: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let rates: Vec<[u128; MAX_REWARDS]> = (0..4).map(|i| [i * 10, i, 0]).collect();
+    let leaves: Vec<[u8; 32]> = mints
+      .iter()
+      .zip(&rates)
+      .map(|(mint, rates)| MerkleProof::leaf(mint, rates))
+      .collect();
+
+    let left = hash_pair(leaves[0], leaves[1]);
+    let right = hash_pair(leaves[2], leaves[3]);
+    let root = hash_pair(left, right);
+
+    let paths = [
+      vec![leaves[1], right],
+      vec![leaves[0], right],
+      vec![leaves[3], left],
+      vec![leaves[2], left],
+    ];
+
+    for ((mint, rates), path) in mints.iter().zip(rates).zip(paths) {
+      let proof = MerkleProof { reward_rates: rates, path };
+      assert_eq!(proof.root(mint), root);
+    }
+  }
+
+  #[test]
+  fn changed_rates_dont_prove_the_root() {
+    let mint = Pubkey::new_unique();
+    let sibling = MerkleProof::leaf(&Pubkey::new_unique(), &[1, 0, 0]);
+    let root = hash_pair(MerkleProof::leaf(&mint, &[1, 0, 0]), sibling);
+
+    let forged = MerkleProof { reward_rates: [1_000, 0, 0], path: vec![sibling] };
+
+    assert_ne!(forged.root(&mint), root);
+  }
+
+  fn proof_data(active_stakes: u64) -> Vec<u8> {
+    let proof = WhitelistProof {
+      whitelisted_address: Pubkey::new_unique(),
+      farm: Pubkey::new_unique(),
+      reward_rates: [1, 0, 0],
+      ty: WhitelistType::Mint,
+      active_stakes,
+      delisted_at: None,
+      buff_config: BuffConfig::default(),
+    };
+    let mut data = vec![];
+
+    proof.try_serialize(&mut data).unwrap();
+    data
+  }
+
+  #[test]
+  fn unstaking_legacy_receipts_skips_the_default_address() {
+    let key = Pubkey::default();
+    let owner = Pubkey::new_unique();
+    let (mut lamports, mut data) = (1, vec![]);
+    // The system program, which can't be passed as writable.
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, true, 0);
+
+    assert!(WhitelistProof::track_unstake(&account, &crate::ID, 1).is_ok());
+  }
+
+  #[test]
+  fn unstaking_takes_stakes_off_writable_proofs_only() {
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (1, proof_data(2));
+    let mut account = AccountInfo::new(
+      &key,
+      false,
+      false,
+      &mut lamports,
+      &mut data,
+      &crate::ID,
+      false,
+      0
+    );
+
+    assert!(WhitelistProof::track_unstake(&account, &crate::ID, 1).is_err());
+
+    account.is_writable = true;
+    WhitelistProof::track_unstake(&account, &crate::ID, 1).unwrap();
+
+    let proof = WhitelistProof::try_deserialize(&mut &account.try_borrow_data().unwrap()[..]);
+
+    assert_eq!(proof.unwrap().active_stakes, 1);
+  }
+}
@@ -1,6 +1,16 @@
-use anchor_lang::{ prelude::*, system_program };
+use anchor_lang::{
+  error::ErrorCode,
+  prelude::*,
+  solana_program::{ instruction::{ AccountMeta, Instruction }, program::invoke_signed },
+  system_program,
+};
 use anchor_spl::token;
-use solutils::wrappers::metadata::MetadataAccount;
+use solutils::wrappers::metadata::{ MetadataAccount, TokenMetadata };
+
+use crate::error::StakingError;
+
+/// Fixed-point scale applied to every reward rate and reward balance.
+pub const PRECISION: u128 = 1_000_000_000_000;
 
 pub fn initialize_pda<'info>(
   seeds: &[&[u8]],
@@ -22,6 +32,46 @@ pub fn initialize_pda<'info>(
   system_program::create_account(cpi_ctx.with_signer(&[seeds]), rent, space as u64, owner)
 }
 
+/// Closes a program account like `#[account(close)]` would, moving its rent to
+/// `destination`. For accounts that can't be typed in the accounts struct.
+pub fn close_account<'info>(
+  account: &AccountInfo<'info>,
+  destination: &AccountInfo<'info>
+) -> Result<()> {
+  let lamports = destination.lamports()
+    .checked_add(account.lamports())
+    .ok_or(StakingError::ArithmeticError)?;
+
+  **destination.try_borrow_mut_lamports()? = lamports;
+  **account.try_borrow_mut_lamports()? = 0;
+
+  account.try_borrow_mut_data()?.fill(0);
+
+  Ok(())
+}
+
+/// Grows or shrinks `account` to `space` bytes, topping up its rent from `payer` if needed.
+pub fn resize_account<'info>(
+  account: &AccountInfo<'info>,
+  space: usize,
+  payer: AccountInfo<'info>,
+  system_program: AccountInfo<'info>
+) -> Result<()> {
+  let rent = Rent::get()?.minimum_balance(space);
+  let missing = rent.saturating_sub(account.lamports());
+
+  if missing > 0 {
+    let cpi_accounts = system_program::Transfer {
+      from: payer,
+      to: account.clone(),
+    };
+
+    system_program::transfer(CpiContext::new(system_program, cpi_accounts), missing)?;
+  }
+
+  account.realloc(space, false).map_err(Into::into)
+}
+
 pub fn transfer_spl_ctx<'a, 'b, 'c, 'info>(
   from: AccountInfo<'info>,
   to: AccountInfo<'info>,
@@ -35,15 +85,42 @@ pub fn transfer_spl_ctx<'a, 'b, 'c, 'info>(
   })
 }
 
-pub fn calculate_reward_rate(base: f64, factor: f64) -> Result<f64> {
-  if factor == 0.0 {
+/// Basis points in a whole, i.e. a 100% bonus.
+pub const BPS: u128 = 10_000;
+
+/// Applies a lock bonus of `bonus_bps` basis points on top of `base`, i.e.
+/// `base * (10_000 + bonus_bps) / 10_000`, rounding down.
+pub fn calculate_reward_rate(base: u128, bonus_bps: u16) -> Result<u128> {
+  if bonus_bps == 0 {
     return Ok(base);
   }
 
-  // (factor + 100) * base / 100;
-  let rate = factor + (100.0 * base) / 100.0;
+  mul_div(base, BPS + (bonus_bps as u128), BPS)
+}
 
-  Ok(rate)
+/// Computes `a * b / c`, splitting `a` by `c` first so the intermediate product doesn't
+/// overflow as long as `c * b` fits in a u128.
+pub fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+  let whole = a.checked_div(c).and_then(|whole| whole.checked_mul(b));
+  let fraction = a
+    .checked_rem(c)
+    .and_then(|fraction| fraction.checked_mul(b))
+    .and_then(|fraction| fraction.checked_div(c));
+
+  whole
+    .zip(fraction)
+    .and_then(|(whole, fraction)| whole.checked_add(fraction))
+    .ok_or_else(|| error!(StakingError::ArithmeticError))
+}
+
+/// Converts a scaled amount back to raw token units, truncating the fractional part.
+pub fn to_token_amount(scaled: u128) -> Result<u64> {
+  u64::try_from(scaled / PRECISION).map_err(|_| error!(StakingError::ArithmeticError))
+}
+
+/// Scales a raw token amount up to the fixed-point representation.
+pub fn to_scaled(amount: u64) -> Result<u128> {
+  (amount as u128).checked_mul(PRECISION).ok_or_else(|| error!(StakingError::ArithmeticError))
 }
 
 pub fn metadata_creator(metadata: &MetadataAccount) -> Result<Pubkey> {
@@ -57,8 +134,428 @@ pub fn metadata_creator(metadata: &MetadataAccount) -> Result<Pubkey> {
   )
 }
 
+/// Mint of the metadata's collection, if the NFT was verified as part of it.
+pub fn metadata_collection(metadata: &MetadataAccount) -> Result<Pubkey> {
+  Ok(
+    metadata.collection
+      .as_ref()
+      .and_then(|collection| collection.verified.then(|| collection.key))
+      .ok_or(ProgramError::InvalidAccountData)?
+  )
+}
+
 pub fn now_ts() -> Result<u64> {
   Clock::get()
     .map(|c| c.unix_timestamp as u64)
     .map_err(Into::into)
-}
\ No newline at end of file
+}
+
+// Token Metadata instruction discriminants.
+const FREEZE_DELEGATED_ACCOUNT: u8 = 26;
+const THAW_DELEGATED_ACCOUNT: u8 = 27;
+
+/// Accounts needed to freeze or thaw a gem in its owner's wallet.
+pub struct FreezeAccounts<'info> {
+  pub delegate: AccountInfo<'info>,
+  pub token_account: AccountInfo<'info>,
+  pub edition: AccountInfo<'info>,
+  pub mint: AccountInfo<'info>,
+  pub token_program: AccountInfo<'info>,
+  pub metadata_program: AccountInfo<'info>,
+}
+
+impl<'info> FreezeAccounts<'info> {
+  /// Builds the accounts from an instruction's remaining accounts, which must end with the
+  /// gem's master edition and the Token Metadata program.
+  pub fn from_remaining(
+    delegate: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>]
+  ) -> Result<Self> {
+    match remaining_accounts {
+      [.., edition, metadata_program] =>
+        Ok(Self {
+          delegate,
+          token_account,
+          edition: edition.clone(),
+          mint,
+          token_program,
+          metadata_program: metadata_program.clone(),
+        }),
+      _ => err!(StakingError::InvalidFreezeAccounts),
+    }
+  }
+
+  /// Freezes the token account through the Token Metadata program, which holds the freeze
+  /// authority of every NFT with a master edition. `delegate` must already be approved.
+  pub fn freeze(&self, delegate_seeds: &[&[u8]]) -> Result<()> {
+    self.invoke(FREEZE_DELEGATED_ACCOUNT, delegate_seeds)
+  }
+
+  pub fn thaw(&self, delegate_seeds: &[&[u8]]) -> Result<()> {
+    self.invoke(THAW_DELEGATED_ACCOUNT, delegate_seeds)
+  }
+
+  fn invoke(&self, instruction: u8, delegate_seeds: &[&[u8]]) -> Result<()> {
+    require_keys_eq!(self.metadata_program.key(), TokenMetadata::id());
+
+    let ix = Instruction {
+      program_id: TokenMetadata::id(),
+      accounts: vec![
+        AccountMeta::new(self.delegate.key(), true),
+        AccountMeta::new(self.token_account.key(), false),
+        AccountMeta::new_readonly(self.edition.key(), false),
+        AccountMeta::new_readonly(self.mint.key(), false),
+        AccountMeta::new_readonly(self.token_program.key(), false)
+      ],
+      data: vec![instruction],
+    };
+
+    invoke_signed(
+      &ix,
+      &[
+        self.delegate.clone(),
+        self.token_account.clone(),
+        self.edition.clone(),
+        self.mint.clone(),
+        self.token_program.clone(),
+        self.metadata_program.clone(),
+      ],
+      &[delegate_seeds]
+    ).map_err(Into::into)
+  }
+}
+
+const TRANSFER: u8 = 49;
+const PROGRAMMABLE_NON_FUNGIBLE: u8 = 4;
+
+/// Whether the metadata's token standard is `ProgrammableNonFungible`. The field is read by
+/// hand because older metadata parsers don't know about that variant.
+pub fn is_programmable(metadata: &AccountInfo) -> Result<bool> {
+  let data = metadata.try_borrow_data()?;
+  // Skip the key, update authority and mint.
+  let mut cursor = data.get(1 + 32 + 32..).ok_or(ErrorCode::AccountDidNotDeserialize)?;
+
+  // Name, symbol, uri, seller fee, creators, primary sale and mutability flags, and the
+  // edition nonce come before the token standard.
+  let skipped = (|| -> std::io::Result<()> {
+    for _ in 0..3 {
+      String::deserialize(&mut cursor)?;
+    }
+    u16::deserialize(&mut cursor)?;
+    Option::<Vec<(Pubkey, bool, u8)>>::deserialize(&mut cursor)?;
+    <(bool, bool)>::deserialize(&mut cursor)?;
+    Option::<u8>::deserialize(&mut cursor)?;
+    Ok(())
+  })();
+
+  if skipped.is_err() {
+    return err!(ErrorCode::AccountDidNotDeserialize);
+  }
+
+  // Metadata created before token standards existed ends here.
+  let token_standard = Option::<u8>::deserialize(&mut cursor).ok().flatten();
+
+  Ok(token_standard == Some(PROGRAMMABLE_NON_FUNGIBLE))
+}
+
+/// Token Metadata accounts needed to move a programmable NFT, which can't be moved with a
+/// plain token transfer.
+pub struct PnftAccounts<'info> {
+  pub metadata: AccountInfo<'info>,
+  pub edition: AccountInfo<'info>,
+  pub owner_token_record: AccountInfo<'info>,
+  pub destination_token_record: AccountInfo<'info>,
+  pub metadata_program: AccountInfo<'info>,
+  pub sysvar_instructions: AccountInfo<'info>,
+  pub system_program: AccountInfo<'info>,
+  pub associated_token_program: AccountInfo<'info>,
+  // Pass the Token Metadata program for both if the NFT has no rule set.
+  pub authorization_rules_program: AccountInfo<'info>,
+  pub authorization_rules: AccountInfo<'info>,
+}
+
+/// Source and destination of a programmable NFT transfer.
+pub struct PnftTransfer<'info> {
+  pub token: AccountInfo<'info>,
+  pub token_owner: AccountInfo<'info>,
+  pub destination: AccountInfo<'info>,
+  pub destination_owner: AccountInfo<'info>,
+  pub mint: AccountInfo<'info>,
+  pub authority: AccountInfo<'info>,
+  pub payer: AccountInfo<'info>,
+  pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> PnftAccounts<'info> {
+  /// Reads the accounts from the start of the remaining accounts if the first one is the
+  /// metadata of a programmable `mint`. Returns `None` for any other gem, which is moved
+  /// with a plain token transfer.
+  pub fn from_remaining(mint: &Pubkey, remaining_accounts: &[AccountInfo<'info>]) -> Result<Option<Self>> {
+    let metadata = match remaining_accounts.first() {
+      Some(metadata) => metadata,
+      None => return Ok(None),
+    };
+
+    let (metadata_address, _) = Pubkey::find_program_address(
+      &[b"metadata", TokenMetadata::id().as_ref(), mint.as_ref()],
+      &TokenMetadata::id()
+    );
+
+    if metadata.key() != metadata_address || !is_programmable(metadata)? {
+      return Ok(None);
+    }
+
+    match remaining_accounts {
+      [
+        metadata,
+        edition,
+        owner_token_record,
+        destination_token_record,
+        metadata_program,
+        sysvar_instructions,
+        system_program,
+        associated_token_program,
+        authorization_rules_program,
+        authorization_rules,
+        ..,
+      ] => {
+        require_keys_eq!(metadata_program.key(), TokenMetadata::id());
+
+        Ok(
+          Some(Self {
+            metadata: metadata.clone(),
+            edition: edition.clone(),
+            owner_token_record: owner_token_record.clone(),
+            destination_token_record: destination_token_record.clone(),
+            metadata_program: metadata_program.clone(),
+            sysvar_instructions: sysvar_instructions.clone(),
+            system_program: system_program.clone(),
+            associated_token_program: associated_token_program.clone(),
+            authorization_rules_program: authorization_rules_program.clone(),
+            authorization_rules: authorization_rules.clone(),
+          })
+        )
+      }
+      _ => err!(StakingError::InvalidPnftAccounts),
+    }
+  }
+
+  /// Moves `amount` of the pNFT through Token Metadata's `Transfer` instruction, which
+  /// checks the rule set and keeps the token records in sync.
+  pub fn transfer(
+    &self,
+    accounts: PnftTransfer<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]]
+  ) -> Result<()> {
+    // TransferArgs::V1 { amount, authorization_data: None }
+    let mut data = vec![TRANSFER, 0];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(0);
+
+    let ix = Instruction {
+      program_id: TokenMetadata::id(),
+      accounts: vec![
+        AccountMeta::new(accounts.token.key(), false),
+        AccountMeta::new_readonly(accounts.token_owner.key(), false),
+        AccountMeta::new(accounts.destination.key(), false),
+        AccountMeta::new_readonly(accounts.destination_owner.key(), false),
+        AccountMeta::new_readonly(accounts.mint.key(), false),
+        AccountMeta::new(self.metadata.key(), false),
+        AccountMeta::new_readonly(self.edition.key(), false),
+        AccountMeta::new(self.owner_token_record.key(), false),
+        AccountMeta::new(self.destination_token_record.key(), false),
+        AccountMeta::new_readonly(accounts.authority.key(), true),
+        AccountMeta::new(accounts.payer.key(), true),
+        AccountMeta::new_readonly(self.system_program.key(), false),
+        AccountMeta::new_readonly(self.sysvar_instructions.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+        AccountMeta::new_readonly(self.associated_token_program.key(), false),
+        AccountMeta::new_readonly(self.authorization_rules_program.key(), false),
+        AccountMeta::new_readonly(self.authorization_rules.key(), false)
+      ],
+      data,
+    };
+
+    invoke_signed(
+      &ix,
+      &[
+        accounts.token,
+        accounts.token_owner,
+        accounts.destination,
+        accounts.destination_owner,
+        accounts.mint,
+        self.metadata.clone(),
+        self.edition.clone(),
+        self.owner_token_record.clone(),
+        self.destination_token_record.clone(),
+        accounts.authority,
+        accounts.payer,
+        self.system_program.clone(),
+        self.sysvar_instructions.clone(),
+        accounts.token_program,
+        self.associated_token_program.clone(),
+        self.authorization_rules_program.clone(),
+        self.authorization_rules.clone(),
+        self.metadata_program.clone(),
+      ],
+      signer_seeds
+    ).map_err(Into::into)
+  }
+}
+
+/// Accounts that move a buff token between its owner's wallet and the farmer's buff vault.
+pub struct BuffTokens<'info> {
+  pub mint: AccountInfo<'info>,
+  pub user_ata: AccountInfo<'info>,
+  pub vault: AccountInfo<'info>,
+  pub farmer: AccountInfo<'info>,
+  pub authority: AccountInfo<'info>,
+  pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> BuffTokens<'info> {
+  /// Escrows the buff token in the vault, or burns it if it's consumable. pNFTs go through
+  /// Token Metadata when their accounts start the remaining accounts.
+  pub fn lock(self, consumable: bool, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+    let pnft = PnftAccounts::from_remaining(&self.mint.key(), remaining_accounts)?;
+
+    if consumable {
+      require!(pnft.is_none(), StakingError::UnconsumableBuff);
+
+      let ctx = CpiContext::new(self.token_program, token::Burn {
+        mint: self.mint,
+        to: self.user_ata,
+        authority: self.authority,
+      });
+
+      return token::burn(ctx, 1);
+    }
+
+    if let Some(pnft) = pnft {
+      let transfer = PnftTransfer {
+        token: self.user_ata,
+        token_owner: self.authority.clone(),
+        destination: self.vault,
+        destination_owner: self.farmer,
+        mint: self.mint,
+        authority: self.authority.clone(),
+        payer: self.authority,
+        token_program: self.token_program,
+      };
+
+      return pnft.transfer(transfer, 1, &[]);
+    }
+
+    let ctx = transfer_spl_ctx(self.user_ata, self.vault, self.authority, self.token_program);
+
+    token::transfer(ctx, 1)
+  }
+
+  /// Sends the escrowed buff token back to its owner, signed by the farmer.
+  pub fn unlock(
+    self,
+    farmer_seeds: &[&[u8]],
+    remaining_accounts: &[AccountInfo<'info>]
+  ) -> Result<()> {
+    if let Some(pnft) = PnftAccounts::from_remaining(&self.mint.key(), remaining_accounts)? {
+      let transfer = PnftTransfer {
+        token: self.vault,
+        token_owner: self.farmer.clone(),
+        destination: self.user_ata,
+        destination_owner: self.authority.clone(),
+        mint: self.mint,
+        authority: self.farmer,
+        payer: self.authority,
+        token_program: self.token_program,
+      };
+
+      return pnft.transfer(transfer, 1, &[farmer_seeds]);
+    }
+
+    let ctx = transfer_spl_ctx(self.vault, self.user_ata, self.farmer, self.token_program);
+
+    token::transfer(ctx.with_signer(&[farmer_seeds]), 1)
+  }
+}
+
+/// Builds the `BuffTokens` of an instruction's accounts, which every buff and debuff names
+/// `buff_mint`, `buff_user_ata`, `buff_vault`, `farmer`, `authority` and `token_program`.
+macro_rules! buff_tokens {
+  ($accounts:expr) => {
+    $crate::utils::BuffTokens {
+      mint: $accounts.buff_mint.to_account_info(),
+      user_ata: $accounts.buff_user_ata.to_account_info(),
+      vault: $accounts.buff_vault.to_account_info(),
+      farmer: $accounts.farmer.to_account_info(),
+      authority: $accounts.authority.to_account_info(),
+      token_program: $accounts.token_program.to_account_info(),
+    }
+  };
+}
+
+pub(crate) use buff_tokens;
+
+/// Creates `authority`'s associated token account for `mint`, paid by `payer`, with the rent,
+/// system, token and associated token programs of an instruction's accounts.
+macro_rules! create_vault {
+  ($accounts:expr, $payer:expr, $vault:expr, $authority:expr, $mint:expr) => {
+    anchor_spl::associated_token::create(
+      anchor_lang::prelude::CpiContext::new(
+        $accounts.associated_token_program.to_account_info(),
+        anchor_spl::associated_token::Create {
+          payer: $payer,
+          associated_token: $vault,
+          authority: $authority,
+          mint: $mint,
+          system_program: $accounts.system_program.to_account_info(),
+          token_program: $accounts.token_program.to_account_info(),
+          rent: $accounts.rent.to_account_info(),
+        }
+      )
+    )
+  };
+}
+
+pub(crate) use create_vault;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_bonus_keeps_the_base_rate() {
+    assert_eq!(calculate_reward_rate(7 * PRECISION, 0).unwrap(), 7 * PRECISION);
+  }
+
+  #[test]
+  fn bonus_is_a_percentage_of_the_base_rate() {
+    // A 20% bonus on 10 tokens/sec is 2 tokens/sec, not 20.
+    assert_eq!(calculate_reward_rate(10 * PRECISION, 2_000).unwrap(), 12 * PRECISION);
+    // 100% doubles it.
+    assert_eq!(calculate_reward_rate(10 * PRECISION, 10_000).unwrap(), 20 * PRECISION);
+    // Single basis points are kept.
+    assert_eq!(calculate_reward_rate(10_000, 1).unwrap(), 10_001);
+  }
+
+  #[test]
+  fn bonus_rounds_down() {
+    assert_eq!(calculate_reward_rate(3, 5_000).unwrap(), 4);
+  }
+
+  #[test]
+  fn zero_base_stays_zero() {
+    assert_eq!(calculate_reward_rate(0, 2_500).unwrap(), 0);
+  }
+
+  #[test]
+  fn large_rates_do_not_overflow() {
+    let base = u128::MAX / 4;
+
+    assert_eq!(calculate_reward_rate(base, 10_000).unwrap(), base * 2);
+    assert!(calculate_reward_rate(u128::MAX, 10_000).is_err());
+  }
+}
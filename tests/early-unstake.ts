@@ -0,0 +1,157 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, sleep, TestFarm } from "./fixtures";
+
+describe("early-unstake", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let gemMint: PublicKey;
+  let whitelistProof: PublicKey;
+  // Can be left early for half of what the stake earned.
+  let penaltyLock: PublicKey;
+  let disabledLock: PublicKey;
+  let shortLock: PublicKey;
+
+  const stake = (lock: PublicKey, amount = 1) =>
+    testFarm
+      .stakeAccounts(user, gemMint, whitelistProof, lock)
+      .then((accounts) =>
+        program.methods
+          .stake(new BN(amount), null)
+          .accounts(accounts)
+          .signers([user])
+          .rpc()
+      );
+
+  const earlyUnstake = (lock: PublicKey) =>
+    testFarm
+      .unstakeAccounts(user, gemMint, whitelistProof, lock)
+      .then((accounts) =>
+        program.methods.earlyUnstake().accounts(accounts).instruction()
+      )
+      .then((instruction) =>
+        testFarm.sendUnstake(instruction, whitelistProof, user)
+      );
+
+  before(async () => {
+    await airdrop(provider.connection, user);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e7);
+
+    ({ gemMint } = await testFarm.createGem(user, 10));
+    whitelistProof = await testFarm.whitelist(gemMint, PRECISION);
+
+    penaltyLock = await testFarm.createLock({
+      duration: new BN(1000),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { penalty: { bps: 5000 } },
+    });
+    disabledLock = await testFarm.createLock({
+      duration: new BN(1001),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+    shortLock = await testFarm.createLock({
+      duration: new BN(2),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { forfeitBonus: {} },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+  });
+
+  it("should not claim while a stake that can leave early is locked", async () => {
+    await stake(penaltyLock);
+    await sleep(3000);
+
+    try {
+      await testFarm.claim(user);
+
+      expect.fail("Rewards of a locked stake were claimed.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("RewardsLocked");
+    }
+  });
+
+  it("should forfeit part of what the stake earned", async () => {
+    await earlyUnstake(penaltyLock);
+
+    const receipt = await program.account.stakeReceipt.fetch(
+      testFarm.stakeReceipt(farmer, gemMint)
+    );
+    const account = await program.account.farmer.fetch(farmer);
+    const [earned] = receipt.earned;
+    const [accrued] = account.accruedRewards;
+
+    expect(earned.gtn(0)).to.be.true;
+    // Half of the earnings went back to the farm, so what's left is the other half plus
+    // rounding.
+    expect(accrued.gte(earned.divn(2))).to.be.true;
+    expect(accrued.lt(earned)).to.be.true;
+    expect(account.earlyExitStakes).to.equal(0);
+    expect(account.reservedRewards[0].isZero()).to.be.true;
+
+    await testFarm.claim(user);
+  });
+
+  it("should refuse to unstake early from a lock that doesn't allow it", async () => {
+    await stake(disabledLock);
+
+    try {
+      await earlyUnstake(disabledLock);
+
+      expect.fail("A gem left a lock that can't be left early.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("EarlyUnstakeDisabled");
+    }
+  });
+
+  it("should claim while only stakes that can't leave early are locked", async () => {
+    await testFarm.claim(user);
+  });
+
+  it("should unblock claims when a short lock ends", async () => {
+    const other = Keypair.generate();
+    await airdrop(provider.connection, other);
+
+    const { gemMint: otherMint } = await testFarm.createGem(other, 1);
+    const otherProof = await testFarm.whitelist(otherMint, PRECISION);
+
+    await testFarm.createFarmer(other);
+    await program.methods
+      .stake(new BN(1), null)
+      .accounts(
+        await testFarm.stakeAccounts(other, otherMint, otherProof, shortLock)
+      )
+      .signers([other])
+      .rpc();
+
+    try {
+      await testFarm.claim(other);
+
+      expect.fail("Rewards of a locked stake were claimed.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("RewardsLocked");
+    }
+
+    await sleep(3000);
+    await testFarm.claim(other);
+  });
+});
@@ -0,0 +1,395 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+
+// Mirrors `FarmManager::*` permissions.
+const Permissions = {
+  Whitelist: 1 << 0,
+  Locks: 1 << 1,
+  Funding: 1 << 2,
+  Pause: 1 << 3,
+};
+const ALL_PERMISSIONS =
+  Permissions.Whitelist |
+  Permissions.Locks |
+  Permissions.Funding |
+  Permissions.Pause;
+
+// Mirrors `utils::PRECISION`.
+const PRECISION = new BN(10).pow(new BN(12));
+
+describe("farm-managers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const farmAuthority = Keypair.generate();
+  // Delegated manager that doesn't own the farm.
+  const manager = Keypair.generate();
+  // Delegated manager that can only whitelist.
+  const whitelister = Keypair.generate();
+
+  let rewardMint: PublicKey;
+  let farm: PublicKey;
+
+  const findPda = (seeds: Buffer[]) =>
+    anchor.utils.publicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const findFarmManager = (authority: PublicKey) =>
+    findPda([Buffer.from("farm_manager"), farm.toBuffer(), authority.toBuffer()]);
+
+  const addManager = (authority: PublicKey, permissions: number) =>
+    program.methods
+      .addManager(permissions)
+      .accounts({
+        farm,
+        farmManager: findFarmManager(authority),
+        managerAuthority: authority,
+        authority: farmAuthority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([farmAuthority])
+      .rpc();
+
+  before(async () => {
+    for (const { publicKey } of [farmAuthority, manager, whitelister]) {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(publicKey, 1e9)
+      );
+    }
+
+    rewardMint = await createMint(
+      connection,
+      farmAuthority,
+      farmAuthority.publicKey,
+      null,
+      2
+    );
+
+    farm = findPda([
+      Buffer.from("farm"),
+      farmAuthority.publicKey.toBuffer(),
+      rewardMint.toBuffer(),
+    ]);
+
+    await program.methods
+      .createFarm({ rate: {} }, { vault: {} })
+      .accounts({
+        farm,
+        rewardMint,
+        farmVault: await anchor.utils.token.associatedAddress({
+          mint: rewardMint,
+          owner: farm,
+        }),
+        authority: farmAuthority.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([farmAuthority])
+      .rpc();
+  });
+
+  it("should derive each manager's PDA from its own authority", async () => {
+    await addManager(farmAuthority.publicKey, ALL_PERMISSIONS);
+    await addManager(manager.publicKey, ALL_PERMISSIONS);
+    await addManager(whitelister.publicKey, Permissions.Whitelist);
+
+    const ownerManager = await program.account.farmManager.fetch(
+      findFarmManager(farmAuthority.publicKey)
+    );
+    const delegatedManager = await program.account.farmManager.fetch(
+      findFarmManager(manager.publicKey)
+    );
+
+    expect(ownerManager.authority.toString()).to.equal(
+      farmAuthority.publicKey.toString()
+    );
+    expect(delegatedManager.authority.toString()).to.equal(
+      manager.publicKey.toString()
+    );
+    expect(delegatedManager.permissions).to.equal(ALL_PERMISSIONS);
+  });
+
+  it("should let a non-owner manager add to the whitelist", async () => {
+    const whitelistProof = findPda([
+      Buffer.from("collection_data"),
+      farm.toBuffer(),
+      rewardMint.toBuffer(),
+    ]);
+
+    await program.methods
+      .addToWhitelist(
+        [PRECISION, new BN(0), new BN(0)],
+        { mint: {} },
+        null
+      )
+      .accounts({
+        farm,
+        farmManager: findFarmManager(manager.publicKey),
+        whitelistProof,
+        creatorOrMint: rewardMint,
+        authority: manager.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([manager])
+      .rpc();
+
+    const proof = await program.account.whitelistProof.fetch(whitelistProof);
+
+    expect(proof.farm.toString()).to.equal(farm.toString());
+    expect(proof.rewardRates[0].eq(PRECISION)).to.be.true;
+  });
+
+  it("should let a whitelist manager set gem levels", async () => {
+    const gem = Keypair.generate().publicKey;
+    const gemLevel = findPda([
+      Buffer.from("gem_level"),
+      farm.toBuffer(),
+      gem.toBuffer(),
+    ]);
+
+    const setLevel = (level: number) =>
+      program.methods
+        .setGemLevels([{ mint: gem, level }])
+        .accounts({
+          farm,
+          farmManager: findFarmManager(whitelister.publicKey),
+          authority: whitelister.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: gemLevel, isSigner: false, isWritable: true },
+        ])
+        .signers([whitelister])
+        .rpc();
+
+    await setLevel(3);
+    expect((await program.account.gemLevel.fetch(gemLevel)).level).to.equal(3);
+
+    // Existing entries are updated in place.
+    await setLevel(1);
+    expect((await program.account.gemLevel.fetch(gemLevel)).level).to.equal(1);
+
+    try {
+      await setLevel(6);
+      expect.fail("A gem was given a level past the last trip effect.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidTripEffect");
+    }
+
+    try {
+      await program.methods
+        .setGemLevels([
+          { mint: gem, level: 1 },
+          { mint: gem, level: 2 },
+        ])
+        .accounts({
+          farm,
+          farmManager: findFarmManager(whitelister.publicKey),
+          authority: whitelister.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: gemLevel, isSigner: false, isWritable: true },
+        ])
+        .signers([whitelister])
+        .rpc();
+      expect.fail("Levels were set without an account for each of them.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidGemLevelAccounts");
+    }
+  });
+
+  it("should let a non-owner manager create locks", async () => {
+    const lockConfig = {
+      duration: new BN(60 * 60 * 24 * 7),
+      bonusBps: 2_500,
+      cooldown: new BN(0),
+    };
+
+    const lock = findPda([
+      Buffer.from("lock"),
+      farm.toBuffer(),
+      lockConfig.duration.toBuffer("le", 8),
+      lockConfig.cooldown.toBuffer("le", 8),
+    ]);
+
+    await program.methods
+      .createLocks([lockConfig])
+      .accounts({
+        farm,
+        farmManager: findFarmManager(manager.publicKey),
+        authority: manager.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: lock, isSigner: false, isWritable: true }])
+      .signers([manager])
+      .rpc();
+
+    const lockAccount = await program.account.lock.fetch(lock);
+
+    expect(lockAccount.farm.toString()).to.equal(farm.toString());
+    expect(lockAccount.duration.eq(lockConfig.duration)).to.be.true;
+  });
+
+  it("should let a non-owner manager fund rewards", async () => {
+    const managerAta = await getOrCreateAssociatedTokenAccount(
+      connection,
+      manager,
+      rewardMint,
+      manager.publicKey
+    );
+
+    await mintTo(
+      connection,
+      farmAuthority,
+      rewardMint,
+      managerAta.address,
+      farmAuthority,
+      100e2
+    );
+
+    await program.methods
+      .fundReward(new BN(100e2))
+      .accounts({
+        farm,
+        farmManager: findFarmManager(manager.publicKey),
+        mint: rewardMint,
+        farmVault: await anchor.utils.token.associatedAddress({
+          mint: rewardMint,
+          owner: farm,
+        }),
+        managerAta: managerAta.address,
+        authority: manager.publicKey,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      })
+      .signers([manager])
+      .rpc();
+
+    const { rewards } = await program.account.farm.fetch(farm);
+
+    expect(rewards[0].available.eq(new BN(100e2).mul(PRECISION))).to.be.true;
+  });
+
+  it("should not let a manager act outside its permissions", async () => {
+    try {
+      await program.methods
+        .setPaused(true)
+        .accounts({
+          farm,
+          farmManager: findFarmManager(whitelister.publicKey),
+          authority: whitelister.publicKey,
+        })
+        .signers([whitelister])
+        .rpc();
+
+      expect.fail("A whitelist-only manager paused the farm.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("MissingPermission");
+    }
+  });
+
+  it("should not let a manager use another manager's PDA", async () => {
+    try {
+      await program.methods
+        .setPaused(true)
+        .accounts({
+          farm,
+          farmManager: findFarmManager(manager.publicKey),
+          authority: whitelister.publicKey,
+        })
+        .signers([whitelister])
+        .rpc();
+
+      expect.fail("A manager signed with another manager's PDA.");
+    } catch (e) {
+      expect(["ConstraintHasOne", "ConstraintSeeds"]).to.include(
+        e.error.errorCode.code
+      );
+    }
+  });
+
+  it("should stop a removed manager from acting", async () => {
+    await program.methods
+      .removeManager()
+      .accounts({
+        farm,
+        farmManager: findFarmManager(manager.publicKey),
+        authority: farmAuthority.publicKey,
+      })
+      .signers([farmAuthority])
+      .rpc();
+
+    try {
+      await program.methods
+        .setPaused(true)
+        .accounts({
+          farm,
+          farmManager: findFarmManager(manager.publicKey),
+          authority: manager.publicKey,
+        })
+        .signers([manager])
+        .rpc();
+
+      expect.fail("A removed manager paused the farm.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("AccountNotInitialized");
+    }
+  });
+
+  it("should refuse permissions that don't exist", async () => {
+    try {
+      await addManager(Keypair.generate().publicKey, 1 << 4);
+
+      expect.fail("A manager was given an unknown permission.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidPermissions");
+    }
+  });
+
+  it("should close the old authority's manager when the farm changes hands", async () => {
+    const newAuthority = Keypair.generate();
+
+    await program.methods
+      .proposeAuthority()
+      .accounts({
+        farm,
+        newAuthority: newAuthority.publicKey,
+        authority: farmAuthority.publicKey,
+      })
+      .signers([farmAuthority])
+      .rpc();
+
+    await program.methods
+      .acceptAuthority()
+      .accounts({
+        farm,
+        authority: farmAuthority.publicKey,
+        authorityManager: findFarmManager(farmAuthority.publicKey),
+        newAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const { authority } = await program.account.farm.fetch(farm);
+
+    expect(authority.equals(newAuthority.publicKey)).to.be.true;
+    expect(
+      await connection.getAccountInfo(findFarmManager(farmAuthority.publicKey))
+    ).to.be.null;
+  });
+});
@@ -0,0 +1,543 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, Program } from "@project-serum/anchor";
+import {
+  createCreateInstruction,
+  createMintInstruction,
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+  TokenStandard,
+} from "@metaplex-foundation/mpl-token-metadata";
+import {
+  createMint,
+  getAssociatedTokenAddress,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  ComputeBudgetProgram,
+  Connection,
+  Keypair,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { BN } from "bn.js";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+
+// Mirrors `utils::PRECISION`.
+export const PRECISION = new BN(10).pow(new BN(12));
+
+export const sleep = (ms: number) =>
+  new Promise((resolve) => setTimeout(resolve, ms));
+
+export const airdrop = async (connection: Connection, ...keys: Keypair[]) => {
+  for (const { publicKey } of keys) {
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(publicKey, 1e9)
+    );
+  }
+};
+
+// pNFT transfers don't fit in the default compute budget.
+export const computeBudget = ComputeBudgetProgram.setComputeUnitLimit({
+  units: 400_000,
+});
+
+export const findMetadataPda = (mint: PublicKey, suffix: Buffer[] = []) =>
+  anchor.utils.publicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      ...suffix,
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+export const findTokenRecord = (mint: PublicKey, tokenAccount: PublicKey) =>
+  findMetadataPda(mint, [Buffer.from("token_record"), tokenAccount.toBuffer()]);
+
+// Accounts `PnftAccounts::from_remaining` expects, moving `mint` from `source` to
+// `destination`. The NFT has no rule set.
+export const pnftAccounts = (
+  mint: PublicKey,
+  source: PublicKey,
+  destination: PublicKey
+) =>
+  [
+    findMetadataPda(mint),
+    findMetadataPda(mint, [Buffer.from("edition")]),
+    findTokenRecord(mint, source),
+    findTokenRecord(mint, destination),
+    TOKEN_METADATA_PROGRAM_ID,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    anchor.web3.SystemProgram.programId,
+    anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    TOKEN_METADATA_PROGRAM_ID,
+    TOKEN_METADATA_PROGRAM_ID,
+  ].map((pubkey, index) => ({
+    pubkey,
+    isSigner: false,
+    isWritable: [0, 2, 3].includes(index),
+  }));
+
+export type LockConfig = {
+  duration: BN;
+  bonusBps: number;
+  cooldown: BN;
+  earlyUnstake: object;
+};
+
+// A farm owned by a fresh authority, which is also its manager with every permission.
+// Its only reward has 2 decimals.
+export class TestFarm {
+  farm: PublicKey;
+  farmManager: PublicKey;
+  rewardMint: PublicKey;
+  farmVault: PublicKey;
+
+  constructor(
+    readonly program: Program<MagicshardsStaking>,
+    readonly authority: Keypair
+  ) {}
+
+  get connection() {
+    return this.program.provider.connection;
+  }
+
+  static async create(
+    program: Program<MagicshardsStaking>,
+    mode: object = { rate: {} },
+    custody: object = { vault: {} }
+  ) {
+    const testFarm = new TestFarm(program, Keypair.generate());
+    const { authority } = testFarm;
+
+    await airdrop(testFarm.connection, authority);
+
+    testFarm.rewardMint = await createMint(
+      testFarm.connection,
+      authority,
+      authority.publicKey,
+      null,
+      2
+    );
+    testFarm.farm = testFarm.findPda([
+      Buffer.from("farm"),
+      authority.publicKey.toBuffer(),
+      testFarm.rewardMint.toBuffer(),
+    ]);
+    testFarm.farmManager = testFarm.findPda([
+      Buffer.from("farm_manager"),
+      testFarm.farm.toBuffer(),
+      authority.publicKey.toBuffer(),
+    ]);
+    testFarm.farmVault = await getAssociatedTokenAddress(
+      testFarm.rewardMint,
+      testFarm.farm,
+      true
+    );
+
+    await program.methods
+      .createFarm(mode as any, custody as any)
+      .accounts({
+        farm: testFarm.farm,
+        rewardMint: testFarm.rewardMint,
+        farmVault: testFarm.farmVault,
+        authority: authority.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+    await program.methods
+      .addManager(0xff)
+      .accounts({
+        farm: testFarm.farm,
+        farmManager: testFarm.farmManager,
+        managerAuthority: authority.publicKey,
+        authority: authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    return testFarm;
+  }
+
+  findPda(seeds: Buffer[]) {
+    return anchor.utils.publicKey.findProgramAddressSync(
+      seeds,
+      this.program.programId
+    )[0];
+  }
+
+  // Mints `amount` reward units to the authority and funds the farm with them.
+  async fund(amount: number) {
+    const authorityAta = await getOrCreateAssociatedTokenAccount(
+      this.connection,
+      this.authority,
+      this.rewardMint,
+      this.authority.publicKey
+    );
+
+    await mintTo(
+      this.connection,
+      this.authority,
+      this.rewardMint,
+      authorityAta.address,
+      this.authority,
+      amount
+    );
+
+    await this.program.methods
+      .fundReward(new BN(amount))
+      .accounts({
+        farm: this.farm,
+        farmManager: this.farmManager,
+        mint: this.rewardMint,
+        farmVault: this.farmVault,
+        managerAta: authorityAta.address,
+        authority: this.authority.publicKey,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      })
+      .signers([this.authority])
+      .rpc();
+  }
+
+  whitelistProof(creatorOrMint: PublicKey) {
+    return this.findPda([
+      Buffer.from("collection_data"),
+      this.farm.toBuffer(),
+      creatorOrMint.toBuffer(),
+    ]);
+  }
+
+  // Whitelists `creatorOrMint` with `rate` units per second of the first reward. Buff
+  // whitelists take their factor from `rate` instead.
+  async whitelist(
+    creatorOrMint: PublicKey,
+    rate: BN,
+    type: object = { mint: {} },
+    buffConfig: object = null
+  ) {
+    const whitelistProof = this.whitelistProof(creatorOrMint);
+
+    await this.program.methods
+      .addToWhitelist(
+        [rate, new BN(0), new BN(0)],
+        type as any,
+        buffConfig as any
+      )
+      .accounts({
+        farm: this.farm,
+        farmManager: this.farmManager,
+        whitelistProof,
+        creatorOrMint,
+        authority: this.authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([this.authority])
+      .rpc();
+
+    return whitelistProof;
+  }
+
+  async createLock(config: LockConfig) {
+    const lock = this.findPda([
+      Buffer.from("lock"),
+      this.farm.toBuffer(),
+      config.duration.toBuffer("le", 8),
+      config.cooldown.toBuffer("le", 8),
+    ]);
+
+    await this.program.methods
+      .createLocks([config as any])
+      .accounts({
+        farm: this.farm,
+        farmManager: this.farmManager,
+        authority: this.authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: lock, isSigner: false, isWritable: true }])
+      .signers([this.authority])
+      .rpc();
+
+    return lock;
+  }
+
+  async createFarmer(owner: Keypair) {
+    const farmer = this.findPda([
+      Buffer.from("farmer"),
+      this.farm.toBuffer(),
+      owner.publicKey.toBuffer(),
+    ]);
+
+    await this.program.methods
+      .initializeFarmer()
+      .accounts({
+        farm: this.farm,
+        farmer,
+        owner: owner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    return farmer;
+  }
+
+  // A fungible gem with `amount` units in `owner`'s wallet.
+  async createGem(owner: Keypair, amount: number, decimals = 0) {
+    const gemMint = await createMint(
+      this.connection,
+      this.authority,
+      this.authority.publicKey,
+      this.authority.publicKey,
+      decimals
+    );
+    const ownerAta = await getOrCreateAssociatedTokenAccount(
+      this.connection,
+      this.authority,
+      gemMint,
+      owner.publicKey
+    );
+
+    await mintTo(
+      this.connection,
+      this.authority,
+      gemMint,
+      ownerAta.address,
+      this.authority,
+      amount
+    );
+
+    return { gemMint, ownerAta: ownerAta.address };
+  }
+
+  // An NFT in `owner`'s wallet whose verified creator is `creator`, the farm authority by
+  // default. Uses the Token Metadata program cloned from devnet.
+  async createNft(
+    owner: Keypair,
+    tokenStandard = TokenStandard.NonFungible,
+    creator = this.authority
+  ) {
+    const gemMint = Keypair.generate();
+    const metadata = findMetadataPda(gemMint.publicKey);
+    const edition = findMetadataPda(gemMint.publicKey, [
+      Buffer.from("edition"),
+    ]);
+    const ownerAta = await getAssociatedTokenAddress(
+      gemMint.publicKey,
+      owner.publicKey
+    );
+    const programmable =
+      tokenStandard === TokenStandard.ProgrammableNonFungible;
+
+    const createNft = new Transaction().add(
+      computeBudget,
+      createCreateInstruction(
+        {
+          metadata,
+          masterEdition: edition,
+          mint: gemMint.publicKey,
+          authority: creator.publicKey,
+          payer: creator.publicKey,
+          updateAuthority: creator.publicKey,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          splTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        },
+        {
+          createArgs: {
+            __kind: "V1",
+            assetData: {
+              name: "Gem",
+              symbol: "GEM",
+              uri: "",
+              sellerFeeBasisPoints: 0,
+              creators: [
+                {
+                  address: creator.publicKey,
+                  verified: true,
+                  share: 100,
+                },
+              ],
+              primarySaleHappened: false,
+              isMutable: true,
+              tokenStandard,
+              collection: null,
+              uses: null,
+              collectionDetails: null,
+              ruleSet: null,
+            },
+            decimals: 0,
+            printSupply: { __kind: "Zero" },
+          },
+        }
+      ),
+      createMintInstruction(
+        {
+          token: ownerAta,
+          tokenOwner: owner.publicKey,
+          metadata,
+          masterEdition: edition,
+          tokenRecord: programmable
+            ? findTokenRecord(gemMint.publicKey, ownerAta)
+            : undefined,
+          mint: gemMint.publicKey,
+          authority: creator.publicKey,
+          payer: creator.publicKey,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          splTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          splAtaProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        },
+        { mintArgs: { __kind: "V1", amount: 1, authorizationData: null } }
+      )
+    );
+
+    // The `create` instruction marks the mint as a signer in its account metas.
+    createNft.instructions[1].keys.find(({ pubkey }) =>
+      pubkey.equals(gemMint.publicKey)
+    ).isSigner = true;
+
+    await this.program.provider.sendAndConfirm(createNft, [creator, gemMint]);
+
+    return { gemMint: gemMint.publicKey, ownerAta, metadata, edition };
+  }
+
+  stakeReceipt(farmer: PublicKey, gemMint: PublicKey) {
+    return this.findPda([
+      Buffer.from("stake_receipt"),
+      farmer.toBuffer(),
+      gemMint.toBuffer(),
+    ]);
+  }
+
+  // Accounts of `stake`, for `owner` staking `gemMint` under `whitelistProof`.
+  async stakeAccounts(
+    owner: Keypair,
+    gemMint: PublicKey,
+    whitelistProof: PublicKey,
+    lock: PublicKey
+  ) {
+    const farmer = this.findPda([
+      Buffer.from("farmer"),
+      this.farm.toBuffer(),
+      owner.publicKey.toBuffer(),
+    ]);
+
+    return {
+      farm: this.farm,
+      farmer,
+      gemMint,
+      whitelistProof,
+      gemLevel: this.findPda([
+        Buffer.from("gem_level"),
+        this.farm.toBuffer(),
+        gemMint.toBuffer(),
+      ]),
+      farmerVault: await getAssociatedTokenAddress(gemMint, farmer, true),
+      gemOwnerAta: await getAssociatedTokenAddress(gemMint, owner.publicKey),
+      stakeReceipt: this.stakeReceipt(farmer, gemMint),
+      lock,
+      owner: owner.publicKey,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+  }
+
+  // Accounts of `unstake`, `unstake_partial` and `early_unstake`.
+  async unstakeAccounts(
+    owner: Keypair,
+    gemMint: PublicKey,
+    whitelistProof: PublicKey,
+    lock: PublicKey
+  ) {
+    const { farm, farmer, farmerVault, gemOwnerAta, stakeReceipt } =
+      await this.stakeAccounts(owner, gemMint, whitelistProof, lock);
+
+    return {
+      farm,
+      farmer,
+      gemMint,
+      stakeReceipt,
+      lock,
+      whitelistProof,
+      farmerVault,
+      gemOwnerAta,
+      owner: owner.publicKey,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+    };
+  }
+
+  // Sends an unstaking instruction. The IDL leaves the whitelist proof
+  // read-only, since legacy receipts point to the default address, so it's
+  // made writable here.
+  async sendUnstake(
+    instruction: TransactionInstruction,
+    whitelistProof: PublicKey,
+    owner: Keypair,
+    ...preInstructions: TransactionInstruction[]
+  ) {
+    instruction.keys.find(({ pubkey }) =>
+      pubkey.equals(whitelistProof)
+    ).isWritable = true;
+
+    try {
+      await this.program.provider.sendAndConfirm(
+        new Transaction().add(...preInstructions, instruction),
+        [owner]
+      );
+    } catch (e) {
+      // Surfaces the program's error like `rpc` does.
+      throw AnchorError.parse(e.logs) ?? e;
+    }
+  }
+
+  // Accounts of `claim_rewards` for the first reward, after the farmer's whitelists.
+  async claim(owner: Keypair) {
+    const farmer = this.findPda([
+      Buffer.from("farmer"),
+      this.farm.toBuffer(),
+      owner.publicKey.toBuffer(),
+    ]);
+    const ownerAta = await getAssociatedTokenAddress(
+      this.rewardMint,
+      owner.publicKey
+    );
+    const { whitelists } = await this.program.account.farmer.fetch(farmer);
+    const proofs = whitelists.map(({ whitelist }) => ({
+      pubkey: whitelist,
+      isSigner: false,
+      isWritable: false,
+    }));
+
+    return this.program.methods
+      .claimRewards()
+      .accounts({
+        farm: this.farm,
+        farmer,
+        authority: owner.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        ...proofs,
+        ...[this.rewardMint, this.farmVault, ownerAta].map((pubkey, index) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: index > 0,
+        })),
+      ])
+      .signers([owner])
+      .rpc();
+  }
+}
@@ -0,0 +1,182 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenStandard } from "@metaplex-foundation/mpl-token-metadata";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { AccountMeta, Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, TestFarm } from "./fixtures";
+
+type Nft = { gemMint: PublicKey; ownerAta: PublicKey; metadata: PublicKey };
+
+// Uses the Token Metadata program cloned from devnet, see Anchor.toml.
+describe("force-debuff", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+  // Verified creators of the escrowed and of the consumable buffs.
+  const buffCreator = Keypair.generate();
+  const consumableCreator = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let lock: PublicKey;
+  let creatorProof: PublicKey;
+  let buffWhitelist: PublicKey;
+  let consumableWhitelist: PublicKey;
+  let gems: Nft[];
+
+  const meta = (pubkey: PublicKey, isWritable = true): AccountMeta => ({
+    pubkey,
+    isSigner: false,
+    isWritable,
+  });
+
+  const stakeReceipt = ({ gemMint }: Nft) =>
+    testFarm.stakeReceipt(farmer, gemMint);
+
+  const buffAccounts = async ({ gemMint, ownerAta }: Nft) => ({
+    farm: testFarm.farm,
+    farmer,
+    buffMint: gemMint,
+    buffUserAta: ownerAta,
+    buffVault: await getAssociatedTokenAddress(gemMint, farmer, true),
+    buffRecord: testFarm.findPda([
+      Buffer.from("buff_record"),
+      farmer.toBuffer(),
+      gemMint.toBuffer(),
+    ]),
+    authority: user.publicKey,
+    tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+  });
+
+  const buff = async (buffNft: Nft, whitelist: PublicKey, gem: Nft) =>
+    program.methods
+      .buff(1)
+      .accounts({
+        ...(await buffAccounts(buffNft)),
+        buffWhitelist: whitelist,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        meta(stakeReceipt(gem)),
+        meta(buffNft.metadata, false),
+      ])
+      .signers([user])
+      .rpc();
+
+  const forceDebuff = async (buffNft: Nft, gem: Nft) =>
+    program.methods
+      .forceDebuff()
+      .accounts(await buffAccounts(buffNft))
+      .remainingAccounts([meta(stakeReceipt(gem))])
+      .signers([user])
+      .rpc();
+
+  const expectUnbuffed = async (gem: Nft) => {
+    const receipt = await program.account.stakeReceipt.fetch(
+      stakeReceipt(gem)
+    );
+
+    expect(receipt.buffs).to.be.empty;
+    expect(receipt.rewardRates[0].eq(PRECISION)).to.be.true;
+  };
+
+  before(async () => {
+    await airdrop(connection, user, buffCreator, consumableCreator);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e7);
+
+    creatorProof = await testFarm.whitelist(
+      testFarm.authority.publicKey,
+      PRECISION,
+      { creator: {} }
+    );
+    buffWhitelist = await testFarm.whitelist(
+      buffCreator.publicKey,
+      PRECISION.muln(2),
+      { buff: {} },
+      { arity: 1, duration: null, consumable: false, category: 0 }
+    );
+    consumableWhitelist = await testFarm.whitelist(
+      consumableCreator.publicKey,
+      PRECISION.muln(2),
+      { buff: {} },
+      { arity: 1, duration: null, consumable: true, category: 0 }
+    );
+    lock = await testFarm.createLock({
+      duration: new BN(0),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+    gems = [await testFarm.createNft(user), await testFarm.createNft(user)];
+
+    for (const { gemMint } of gems) {
+      await program.methods
+        .stake(new BN(1), null)
+        .accounts(
+          await testFarm.stakeAccounts(user, gemMint, creatorProof, lock)
+        )
+        .signers([user])
+        .rpc();
+    }
+  });
+
+  it("should refuse receipts that aren't in the buff record", async () => {
+    const buffNft = await testFarm.createNft(
+      user,
+      TokenStandard.NonFungible,
+      buffCreator
+    );
+
+    await buff(buffNft, buffWhitelist, gems[0]);
+
+    try {
+      await forceDebuff(buffNft, gems[1]);
+
+      expect.fail("A buff was taken off a stake it didn't boost.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidStakeAccounts");
+    }
+
+    await forceDebuff(buffNft, gems[0]);
+    await expectUnbuffed(gems[0]);
+
+    const wallet = await getAccount(connection, buffNft.ownerAta);
+
+    expect(Number(wallet.amount)).to.equal(1);
+  });
+
+  it("should take a consumed buff off without sending it back", async () => {
+    const buffNft = await testFarm.createNft(
+      user,
+      TokenStandard.NonFungible,
+      consumableCreator
+    );
+    const { buffRecord } = await buffAccounts(buffNft);
+
+    await buff(buffNft, consumableWhitelist, gems[1]);
+    await forceDebuff(buffNft, gems[1]);
+    await expectUnbuffed(gems[1]);
+
+    const wallet = await getAccount(connection, buffNft.ownerAta);
+    const account = await program.account.farmer.fetch(farmer);
+
+    expect(Number(wallet.amount)).to.equal(0);
+    expect(account.totalRewardRates[0].eq(PRECISION.muln(2))).to.be.true;
+    expect(await connection.getAccountInfo(buffRecord)).to.be.null;
+  });
+});
@@ -0,0 +1,188 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import {
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { getAccount } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, TestFarm } from "./fixtures";
+
+type Nft = {
+  gemMint: PublicKey;
+  ownerAta: PublicKey;
+  metadata: PublicKey;
+  edition: PublicKey;
+};
+
+// Uses the Token Metadata program cloned from devnet, see Anchor.toml.
+describe("freeze", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let creatorProof: PublicKey;
+  let lock: PublicKey;
+  let penaltyLock: PublicKey;
+
+  // The metadata for the creator whitelist, then the edition and program that freeze it.
+  const freezeAccounts = ({ metadata, edition }: Nft) =>
+    [metadata, edition, TOKEN_METADATA_PROGRAM_ID].map((pubkey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: false,
+    }));
+
+  const stake = async (nft: Nft, stakeLock: PublicKey) =>
+    program.methods
+      .stake(new BN(1), null)
+      .accounts(
+        await testFarm.stakeAccounts(user, nft.gemMint, creatorProof, stakeLock)
+      )
+      .remainingAccounts(freezeAccounts(nft))
+      .signers([user])
+      .rpc();
+
+  const unstakeAccounts = (nft: Nft, stakeLock: PublicKey) =>
+    testFarm.unstakeAccounts(user, nft.gemMint, creatorProof, stakeLock);
+
+  const expectFrozen = async (nft: Nft) => {
+    const wallet = await getAccount(connection, nft.ownerAta);
+    const { farmerVault } = await unstakeAccounts(nft, lock);
+
+    expect(Number(wallet.amount)).to.equal(1);
+    expect(wallet.isFrozen).to.be.true;
+    expect(wallet.delegate.equals(farmer)).to.be.true;
+    // Frozen gems never leave the wallet, so the farmer doesn't need an account for them.
+    expect(await connection.getAccountInfo(farmerVault)).to.be.null;
+  };
+
+  const expectThawed = async (nft: Nft) => {
+    const wallet = await getAccount(connection, nft.ownerAta);
+
+    expect(Number(wallet.amount)).to.equal(1);
+    expect(wallet.isFrozen).to.be.false;
+    expect(wallet.delegate).to.be.null;
+  };
+
+  before(async () => {
+    await airdrop(connection, user);
+
+    testFarm = await TestFarm.create(program, { rate: {} }, { freeze: {} });
+    await testFarm.fund(1e7);
+
+    creatorProof = await testFarm.whitelist(
+      testFarm.authority.publicKey,
+      PRECISION,
+      { creator: {} }
+    );
+    lock = await testFarm.createLock({
+      duration: new BN(0),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+    penaltyLock = await testFarm.createLock({
+      duration: new BN(1000),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { penalty: { bps: 5000 } },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+  });
+
+  it("should refuse to stake without the freeze accounts", async () => {
+    const nft = await testFarm.createNft(user);
+
+    try {
+      await program.methods
+        .stake(new BN(1), null)
+        .accounts(
+          await testFarm.stakeAccounts(user, nft.gemMint, creatorProof, lock)
+        )
+        .remainingAccounts(freezeAccounts(nft).slice(0, 1))
+        .signers([user])
+        .rpc();
+
+      expect.fail("A gem was staked without being frozen.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidFreezeAccounts");
+    }
+  });
+
+  it("should refuse to stake more than the wallet holds", async () => {
+    const nft = await testFarm.createNft(user);
+
+    try {
+      await program.methods
+        .stake(new BN(2), null)
+        .accounts(
+          await testFarm.stakeAccounts(user, nft.gemMint, creatorProof, lock)
+        )
+        .remainingAccounts(freezeAccounts(nft))
+        .signers([user])
+        .rpc();
+
+      expect.fail("A frozen stake counted more gems than the wallet holds.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidStakeAmount");
+    }
+
+    // Nothing was approved or frozen.
+    await expectThawed(nft);
+  });
+
+  it("should freeze a gem in the wallet and thaw it when unstaking", async () => {
+    const nft = await testFarm.createNft(user);
+
+    await stake(nft, lock);
+    await expectFrozen(nft);
+
+    await testFarm.sendUnstake(
+      await program.methods
+        .unstake()
+        .accounts(await unstakeAccounts(nft, lock))
+        .remainingAccounts(freezeAccounts(nft))
+        .instruction(),
+      creatorProof,
+      user
+    );
+
+    await expectThawed(nft);
+  });
+
+  it("should thaw a gem unstaked early", async () => {
+    const nft = await testFarm.createNft(user);
+
+    await stake(nft, penaltyLock);
+    await expectFrozen(nft);
+
+    await testFarm.sendUnstake(
+      await program.methods
+        .earlyUnstake()
+        .accounts(await unstakeAccounts(nft, penaltyLock))
+        .remainingAccounts(freezeAccounts(nft))
+        .instruction(),
+      creatorProof,
+      user
+    );
+
+    await expectThawed(nft);
+
+    const account = await program.account.farmer.fetch(farmer);
+
+    expect(account.totalRewardRates[0].isZero()).to.be.true;
+    expect(account.earlyExitStakes).to.equal(0);
+  });
+});
@@ -0,0 +1,162 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, sleep, TestFarm } from "./fixtures";
+
+describe("fungible", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let gemMint: PublicKey;
+  let whitelistProof: PublicKey;
+  let lock: PublicKey;
+  let otherLock: PublicKey;
+
+  const stake = async (amount: number, stakeLock = lock) =>
+    program.methods
+      .stake(new BN(amount), null)
+      .accounts(
+        await testFarm.stakeAccounts(user, gemMint, whitelistProof, stakeLock)
+      )
+      .signers([user])
+      .rpc();
+
+  const unstakePartial = async (amount: number) =>
+    testFarm.sendUnstake(
+      await program.methods
+        .unstakePartial(new BN(amount))
+        .accounts(
+          await testFarm.unstakeAccounts(user, gemMint, whitelistProof, lock)
+        )
+        .instruction(),
+      whitelistProof,
+      user
+    );
+
+  const fetchReceipt = () =>
+    program.account.stakeReceipt.fetch(
+      testFarm.stakeReceipt(farmer, gemMint)
+    );
+
+  before(async () => {
+    await airdrop(provider.connection, user);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e7);
+
+    ({ gemMint } = await testFarm.createGem(user, 100));
+    whitelistProof = await testFarm.whitelist(gemMint, PRECISION);
+
+    lock = await testFarm.createLock({
+      duration: new BN(5),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+    otherLock = await testFarm.createLock({
+      duration: new BN(6),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+  });
+
+  it("should lock topped up tokens without relocking the stake", async () => {
+    await stake(10);
+    const { startTs } = await fetchReceipt();
+
+    await sleep(6000);
+    await stake(5);
+
+    const receipt = await fetchReceipt();
+
+    expect(receipt.startTs.eq(startTs)).to.be.true;
+    expect(receipt.amount.toNumber()).to.equal(15);
+    expect(receipt.topUpAmount.toNumber()).to.equal(5);
+    expect(receipt.topUpTs.gt(startTs)).to.be.true;
+  });
+
+  it("should only top up with the stake's lock", async () => {
+    try {
+      await stake(1, otherLock);
+
+      expect.fail("A stake was topped up with another lock.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("LockMismatch");
+    }
+  });
+
+  it("should not unstake zero tokens", async () => {
+    try {
+      await unstakePartial(0);
+
+      expect.fail("Zero tokens were unstaked.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidUnstakeAmount");
+    }
+  });
+
+  it("should not unstake topped up tokens that are still locked", async () => {
+    try {
+      await unstakePartial(11);
+
+      expect.fail("Locked tokens were unstaked.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("GemStillLocked");
+    }
+  });
+
+  it("should unstake the unlocked tokens", async () => {
+    await unstakePartial(4);
+
+    const receipt = await fetchReceipt();
+    const account = await program.account.farmer.fetch(farmer);
+
+    expect(receipt.amount.toNumber()).to.equal(11);
+    expect(receipt.endTs).to.be.null;
+    // The whitelisted rate is per token.
+    expect(receipt.rewardRates[0].eq(PRECISION.muln(11))).to.be.true;
+    expect(account.totalRewardRates[0].eq(PRECISION.muln(11))).to.be.true;
+  });
+
+  it("should unstake everything once the top-up unlocks", async () => {
+    try {
+      await testFarm.sendUnstake(
+        await program.methods
+          .unstake()
+          .accounts(
+            await testFarm.unstakeAccounts(user, gemMint, whitelistProof, lock)
+          )
+          .instruction(),
+        whitelistProof,
+        user
+      );
+
+      expect.fail("A stake was closed with locked tokens.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("GemStillLocked");
+    }
+
+    await sleep(6000);
+    await unstakePartial(11);
+
+    const receipt = await fetchReceipt();
+    const account = await program.account.farmer.fetch(farmer);
+
+    expect(receipt.endTs).not.to.be.null;
+    expect(account.totalRewardRates[0].isZero()).to.be.true;
+  });
+});
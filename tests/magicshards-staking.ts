@@ -17,15 +17,20 @@ import {
 import { BN } from "bn.js";
 import { assert, expect } from "chai";
 
-import { StakingProgram } from "../app/lib";
+import { PRECISION, StakingProgram } from "../app/lib";
 import {
   WhitelistProof,
   Farm,
   Farmer,
   StakeReceipt,
 } from "../app/lib/gen/accounts";
-import { GemStillStaked } from "../app/lib/gen/errors/custom";
-import { WhitelistType } from "../app/lib/gen/types";
+import { LockMismatch } from "../app/lib/gen/errors/custom";
+import {
+  Custody,
+  EarlyUnstake,
+  EmissionMode,
+  WhitelistType,
+} from "../app/lib/gen/types";
 import { LockConfigFields } from "../app/lib/gen/types/LockConfig";
 import {
   findFarmAddress,
@@ -33,7 +38,6 @@ import {
   findStakeReceiptAddress,
   findWhitelistProofAddress,
 } from "../app/lib/pda";
-import { TripEffect } from "../app/lib/types";
 import { findFarmLocks, withParsedError } from "../app/lib/utils";
 
 const send = (
@@ -89,8 +93,9 @@ describe("staking-program", () => {
 
   console.log("farmAuthority", farmAuthority.publicKey.toString());
 
-  // 10 tokens per day = 10e2 / 86400 = 0.011574074074074073
-  const whitelistRewardRatePerSecond = 0.011574074074074073;
+  // 10 tokens per day, in base units per second scaled by `PRECISION`.
+  const whitelistRewardRate = new BN(10e2).mul(PRECISION).divn(86400);
+  const mintRewardRate = PRECISION.divn(86400);
 
   before(async () => {
     // Create new fungible token and mint to farmAuthority.
@@ -108,6 +113,8 @@ describe("staking-program", () => {
     const { ix } = await stakingClient.createCreateFarmInstruction({
       authority: farmAuthority.publicKey,
       rewardMint,
+      mode: new EmissionMode.Rate(),
+      custody: new Custody.Vault(),
     });
 
     await send(connection, ix, [farmAuthority]);
@@ -117,11 +124,11 @@ describe("staking-program", () => {
       rewardMint,
     });
 
-    const { authority, reward } = await Farm.fetch(connection, farm);
+    const { authority, rewards } = await Farm.fetch(connection, farm);
 
-    expect(reward.reserved).to.equal(0);
-    expect(reward.available).to.equal(0);
-    expect(reward.mint.toString()).to.eql(rewardMint.toString());
+    expect(rewards[0].reserved.isZero()).to.be.true;
+    expect(rewards[0].available.isZero()).to.be.true;
+    expect(rewards[0].mint.toString()).to.eql(rewardMint.toString());
     expect(authority.toString()).to.eql(farmAuthority.publicKey.toString());
   });
 
@@ -133,11 +140,18 @@ describe("staking-program", () => {
 
     const ONE_WEEK = new BN(60 * 60 * 24 * 7);
 
-    const lockConfigs: LockConfigFields[] = [
-      { duration: new BN(0), bonusFactor: 0, cooldown: new BN(0) },
-      { duration: ONE_WEEK, bonusFactor: 25, cooldown: new BN(0) },
-      { duration: ONE_WEEK.muln(2), bonusFactor: 50, cooldown: new BN(0) },
-      { duration: ONE_WEEK.muln(4), bonusFactor: 75, cooldown: new BN(0) },
+    const lockConfig = (duration: BN, bonusBps: number): LockConfigFields => ({
+      duration,
+      bonusBps,
+      cooldown: new BN(0),
+      earlyUnstake: new EarlyUnstake.Disabled(),
+    });
+
+    const lockConfigs = [
+      lockConfig(new BN(0), 0),
+      lockConfig(ONE_WEEK, 2500),
+      lockConfig(ONE_WEEK.muln(2), 5000),
+      lockConfig(ONE_WEEK.muln(4), 7500),
     ];
 
     const { ix } = await stakingClient.createCreateLocksInstruction({
@@ -167,16 +181,16 @@ describe("staking-program", () => {
     const { ix } = await stakingClient.createFundRewardInstruction({
       farm,
       authority: farmAuthority.publicKey,
-      amount: 100e2,
+      amount: new BN(100e2),
+      rewardMint,
     });
 
-    const farmVault = await anchor.utils.token.associatedAddress({
-      mint: rewardMint,
-      owner: farm,
-    });
+    await send(connection, [ix], [farmAuthority]);
 
-    const txid = await send(connection, [ix], [farmAuthority]);
-    const farmAccount = await Farm.fetch(connection, farm);
+    const { rewards } = await Farm.fetch(connection, farm);
+
+    // Balances are scaled like the rates.
+    expect(rewards[0].available.eq(new BN(100e2).mul(PRECISION))).to.be.true;
   });
 
   it("should be able to whitelist a creator address", async () => {
@@ -188,11 +202,12 @@ describe("staking-program", () => {
     const whitelistBuff = await stakingClient.createAddToWhitelistInstruction({
       farm,
       authority: farmAuthority.publicKey,
-      // Since this is a buff, the rewardRate will act as a multiplier.
+      // Since this is a buff, the first reward rate will act as a multiplier.
       // Here it will buff the pair reward in 2x.
-      rewardRate: { tokenAmount: 2, intervalInSeconds: 1 },
+      rewardRates: [{ tokenAmount: 2, intervalInSeconds: 1 }],
       creatorOrMint: buffCreator,
       whitelistType: new WhitelistType.Buff(),
+      buffConfig: { arity: 2, duration: null, consumable: false, category: 0 },
     });
 
     const whitelistCreator =
@@ -200,11 +215,9 @@ describe("staking-program", () => {
         creatorOrMint: creatorAddress,
         authority: farmAuthority.publicKey,
         farm,
-        rewardRate: {
-          tokenAmount: whitelistRewardRatePerSecond,
-          intervalInSeconds: 1,
-        },
+        rewardRates: [{ tokenAmount: 10e2, intervalInSeconds: 86400 }],
         whitelistType: new WhitelistType.Creator(),
+        buffConfig: null,
       });
 
     await send(
@@ -228,9 +241,8 @@ describe("staking-program", () => {
       creatorAddress.toString()
     );
     expect(whitelistProofAccount.ty.kind).to.equal("Creator");
-    expect(whitelistProofAccount.rewardRate).to.equal(
-      whitelistRewardRatePerSecond
-    );
+    expect(whitelistProofAccount.rewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it("should be able to whitelist a mint address", async () => {
@@ -243,8 +255,10 @@ describe("staking-program", () => {
       creatorOrMint: rewardMint,
       authority: farmAuthority.publicKey,
       farm,
-      rewardRate: { tokenAmount: 1, intervalInSeconds: 1 },
+      // One per staked base unit a day, so the farm can cover the reservation.
+      rewardRates: [{ tokenAmount: 1, intervalInSeconds: 86400 }],
       whitelistType: new WhitelistType.Mint(),
+      buffConfig: null,
     });
 
     await send(connection, [ix], [farmAuthority]);
@@ -263,7 +277,7 @@ describe("staking-program", () => {
     expect(whitelistProofAccount.whitelistedAddress.toString()).to.eql(
       rewardMint.toString()
     );
-    expect(whitelistProofAccount.rewardRate).to.equal(1);
+    expect(whitelistProofAccount.rewardRates[0].eq(mintRewardRate)).to.be.true;
   });
 
   it("should be able to initialize a farmer", async () => {
@@ -284,13 +298,13 @@ describe("staking-program", () => {
       owner: userWallet.publicKey,
     });
 
-    const { totalRewardRate, accruedRewards, owner } = await Farmer.fetch(
+    const { totalRewardRates, accruedRewards, owner } = await Farmer.fetch(
       connection,
       farmer
     );
 
-    expect(totalRewardRate).to.equal(0);
-    expect(accruedRewards).to.equal(0);
+    expect(totalRewardRates[0].isZero()).to.be.true;
+    expect(accruedRewards[0].isZero()).to.be.true;
     expect(owner.toString()).to.eql(userWallet.publicKey.toString());
   });
 
@@ -301,31 +315,25 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
-
-    const tripEffect: TripEffect = "None";
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const { ix } = await stakingClient.createStakeInstruction({
       farm,
       mint: nft,
       lock: lock.address,
       owner: userWallet.publicKey,
-      args: { amount: new BN(1), tripEffect },
+      args: { amount: new BN(1), merkleProof: null },
     });
 
-    const txid = await send(connection, [ix], [userWallet]);
+    await send(connection, [ix], [userWallet]);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
 
     const farmerAccount = await Farmer.fetch(connection, farmer);
-    const { reward } = await Farm.fetch(connection, farm);
-
-    const expectedRewardRate =
-      whitelistRewardRatePerSecond * (1 + lock.bonusFactor / 100);
-    const expectedReservedReward =
-      expectedRewardRate * lock.duration.toNumber();
 
-    expect(farmerAccount.totalRewardRate).to.equal(expectedRewardRate);
+    // The lock has no bonus.
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it("should be able to buff a pair", async () => {
@@ -335,17 +343,16 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
-    const farmerAccount = await Farmer.fetch(connection, farmer);
 
     const stakeNft = await stakingClient.createStakeInstruction({
       farm,
       owner: userWallet.publicKey,
       mint: otherNft,
       lock: lock.address,
-      args: { amount: new BN(1), tripEffect: "None" },
+      args: { amount: new BN(1), merkleProof: null },
     });
 
     const { ix } = await stakingClient.createBuffPairInstruction({
@@ -359,11 +366,11 @@ describe("staking-program", () => {
 
     await send(connection, [ix], [farmAuthority, userWallet]);
 
-    const farmerAccount2 = await Farmer.fetch(connection, farmer);
+    const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount2.totalRewardRate).to.equal(
-      whitelistRewardRatePerSecond * 4
-    );
+    // Both NFTs earn twice their rate.
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate.muln(4))).to
+      .be.true;
   });
 
   it("should be able to debuff a pair", async () => {
@@ -390,9 +397,8 @@ describe("staking-program", () => {
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
     const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount.totalRewardRate).to.equal(
-      whitelistRewardRatePerSecond
-    );
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it("should be able to unstake an NFT", async () => {
@@ -416,10 +422,10 @@ describe("staking-program", () => {
 
     const stakeReceipt = findStakeReceiptAddress({ farmer, mint: nft });
 
-    const { totalRewardRate } = await Farmer.fetch(connection, farmer);
+    const { totalRewardRates } = await Farmer.fetch(connection, farmer);
     const { endTs } = await StakeReceipt.fetch(connection, stakeReceipt);
 
-    expect(totalRewardRate).to.equal(0);
+    expect(totalRewardRates[0].isZero()).to.be.true;
     expect(endTs.toNumber()).to.be.closeTo(Math.floor(Date.now() / 1000), 1);
   });
 
@@ -430,7 +436,7 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
 
@@ -440,25 +446,25 @@ describe("staking-program", () => {
       mint: rewardMint,
       lock: lock.address,
       owner: userWallet.publicKey,
-      args: { amount: new BN(5e2), tripEffect: "None" },
+      args: { amount: new BN(5e2), merkleProof: null },
     });
 
     await send(connection, [ix], [userWallet]);
 
-    const { totalRewardRate } = await Farmer.fetch(connection, farmer);
-    const expectedRewardRate = 5e2 * Math.floor(1 + lock.bonusFactor / 100);
+    const { totalRewardRates } = await Farmer.fetch(connection, farmer);
 
-    expect(totalRewardRate).to.eql(expectedRewardRate);
+    // The lock has no bonus.
+    expect(totalRewardRates[0].eq(mintRewardRate.muln(5e2))).to.be.true;
   });
 
-  it("should not be able to stake more while still staking", async () => {
+  it("should not be able to top up a stake with another lock", async () => {
     const farm = findFarmAddress({
       authority: farmAuthority.publicKey,
       rewardMint,
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
+    const lock = locks.find((lock) => lock.bonusBps === 2500);
 
     try {
       const { ix } = await stakingClient.createStakeInstruction({
@@ -466,42 +472,16 @@ describe("staking-program", () => {
         mint: rewardMint,
         lock: lock.address,
         owner: userWallet.publicKey,
-        args: { amount: new BN(5e2), tripEffect: "None" },
+        args: { amount: new BN(5e2), merkleProof: null },
       });
 
       await send(connection, [ix], [userWallet]);
       assert(false);
     } catch (e) {
-      expect(e).to.be.instanceOf(GemStillStaked);
+      expect(e).to.be.instanceOf(LockMismatch);
     }
   });
 
-  it("should be able to remove and address from the whitelist", async () => {
-    const farm = findFarmAddress({
-      authority: farmAuthority.publicKey,
-      rewardMint,
-    });
-
-    const { ix } = stakingClient.createRemoveFromWhitelistInstruction({
-      farm,
-      authority: farmAuthority.publicKey,
-      addressToRemove: rewardMint,
-    });
-
-    await send(connection, [ix], [farmAuthority]);
-
-    const whitelistProof = findWhitelistProofAddress({
-      farm,
-      creatorOrMint: rewardMint,
-    });
-    const whitelistProofAccount = await WhitelistProof.fetch(
-      connection,
-      whitelistProof
-    );
-
-    expect(whitelistProofAccount).to.be.null;
-  });
-
   it("should be able to unstake a fungible token", async () => {
     // Sleep for 2 seconds so the rewards get updated.
     await new Promise((resolve) => setTimeout(resolve, 2000));
@@ -525,10 +505,36 @@ describe("staking-program", () => {
     const farmerAccount = await Farmer.fetch(connection, farmer);
     const { endTs } = await StakeReceipt.fetch(connection, stakeReceipt);
 
-    expect(farmerAccount.totalRewardRate).to.equal(0);
+    expect(farmerAccount.totalRewardRates[0].isZero()).to.be.true;
     expect(endTs.toNumber()).to.be.closeTo(Math.floor(Date.now() / 1000), 1);
   });
 
+  it("should be able to remove and address from the whitelist", async () => {
+    const farm = findFarmAddress({
+      authority: farmAuthority.publicKey,
+      rewardMint,
+    });
+
+    const { ix } = stakingClient.createRemoveFromWhitelistInstruction({
+      farm,
+      authority: farmAuthority.publicKey,
+      addressToRemove: rewardMint,
+    });
+
+    await send(connection, [ix], [farmAuthority]);
+
+    const whitelistProof = findWhitelistProofAddress({
+      farm,
+      creatorOrMint: rewardMint,
+    });
+    const whitelistProofAccount = await WhitelistProof.fetch(
+      connection,
+      whitelistProof
+    );
+
+    expect(whitelistProofAccount).to.be.null;
+  });
+
   it("should be able to claim rewards", async () => {
     const farm = findFarmAddress({
       authority: farmAuthority.publicKey,
@@ -540,12 +546,12 @@ describe("staking-program", () => {
       authority: userWallet.publicKey,
     });
 
-    const txid = await send(connection, [ix], [userWallet]);
+    await send(connection, [ix], [userWallet]);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
     const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount.accruedRewards).to.equal(0);
+    expect(farmerAccount.accruedRewards[0].isZero()).to.be.true;
   });
 });
 
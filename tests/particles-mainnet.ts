@@ -10,17 +10,21 @@ import {
   TransactionInstruction,
 } from "@solana/web3.js";
 import { BN } from "bn.js";
-import { assert, expect } from "chai";
+import { expect } from "chai";
 
-import { StakingProgram } from "../app/lib";
+import { PRECISION, StakingProgram } from "../app/lib";
 import {
   WhitelistProof,
   Farm,
   Farmer,
   StakeReceipt,
 } from "../app/lib/gen/accounts";
-import { GemStillStaked } from "../app/lib/gen/errors/custom";
-import { WhitelistType } from "../app/lib/gen/types";
+import {
+  Custody,
+  EarlyUnstake,
+  EmissionMode,
+  WhitelistType,
+} from "../app/lib/gen/types";
 import { LockConfigFields } from "../app/lib/gen/types/LockConfig";
 import {
   findFarmAddress,
@@ -28,7 +32,6 @@ import {
   findStakeReceiptAddress,
   findWhitelistProofAddress,
 } from "../app/lib/pda";
-import { TripEffect } from "../app/lib/types";
 import { findFarmLocks, withParsedError } from "../app/lib/utils";
 
 const send = (
@@ -90,13 +93,15 @@ describe("staking-program", () => {
 
   console.log("farmAuthority", farmAuthority.publicKey.toString());
 
-  // 10 tokens per day = 10e2 / 86400 = 0.011574074074074073
-  const whitelistRewardRatePerSecond = 0.011574074074074073;
+  // 10 tokens per day, in base units per second scaled by `PRECISION`.
+  const whitelistRewardRate = new BN(10e2).mul(PRECISION).divn(86400);
 
   it.skip("should be able to create a new farm.", async () => {
     const { ix } = await stakingClient.createCreateFarmInstruction({
       authority: farmAuthority.publicKey,
       rewardMint,
+      mode: new EmissionMode.Rate(),
+      custody: new Custody.Vault(),
     });
 
     await send(connection, ix, [farmAuthority]);
@@ -106,11 +111,11 @@ describe("staking-program", () => {
       rewardMint,
     });
 
-    const { authority, reward } = await Farm.fetch(connection, farm);
+    const { authority, rewards } = await Farm.fetch(connection, farm);
 
-    expect(reward.reserved).to.equal(0);
-    expect(reward.available).to.equal(0);
-    expect(reward.mint.toString()).to.eql(rewardMint.toString());
+    expect(rewards[0].reserved.isZero()).to.be.true;
+    expect(rewards[0].available.isZero()).to.be.true;
+    expect(rewards[0].mint.toString()).to.eql(rewardMint.toString());
     expect(authority.toString()).to.eql(farmAuthority.publicKey.toString());
   });
 
@@ -121,7 +126,12 @@ describe("staking-program", () => {
     });
 
     const lockConfigs: LockConfigFields[] = [
-      { duration: new BN(0), bonusFactor: 0, cooldown: new BN(0) },
+      {
+        duration: new BN(0),
+        bonusBps: 0,
+        cooldown: new BN(0),
+        earlyUnstake: new EarlyUnstake.Disabled(),
+      },
     ];
 
     const { ix } = await stakingClient.createCreateLocksInstruction({
@@ -151,16 +161,11 @@ describe("staking-program", () => {
     const { ix } = await stakingClient.createFundRewardInstruction({
       farm,
       authority: farmAuthority.publicKey,
-      amount: 100e2,
-    });
-
-    const farmVault = await anchor.utils.token.associatedAddress({
-      mint: rewardMint,
-      owner: farm,
+      amount: new BN(100e2),
+      rewardMint,
     });
 
-    const txid = await send(connection, [ix], [farmAuthority]);
-    const farmAccount = await Farm.fetch(connection, farm);
+    await send(connection, [ix], [farmAuthority]);
   });
 
   it("should be able to whitelist a creator address", async () => {
@@ -172,11 +177,12 @@ describe("staking-program", () => {
     // const whitelistBuff = await stakingClient.createAddToWhitelistInstruction({
     //   farm,
     //   authority: farmAuthority.publicKey,
-    //   // Since this is a buff, the rewardRate will act as a multiplier.
+    //   // Since this is a buff, the first rate will act as a multiplier.
     //   // Here it will buff the pair reward in 2x.
-    //   rewardRate: { tokenAmount: 2, intervalInSeconds: 1 },
+    //   rewardRates: [{ tokenAmount: 2, intervalInSeconds: 1 }],
     //   creatorOrMint: buffCreator,
     //   whitelistType: new WhitelistType.Buff(),
+    //   buffConfig: { arity: 2, duration: null, consumable: false, category: 0 },
     // });
 
     const whitelistCreator =
@@ -184,11 +190,9 @@ describe("staking-program", () => {
         creatorOrMint: creatorAddress,
         authority: farmAuthority.publicKey,
         farm,
-        rewardRate: {
-          tokenAmount: whitelistRewardRatePerSecond,
-          intervalInSeconds: 1,
-        },
+        rewardRates: [{ tokenAmount: 10e2, intervalInSeconds: 86400 }],
         whitelistType: new WhitelistType.Creator(),
+        buffConfig: null,
       });
 
     const whitelistNFTabs = await stakingClient.createAddToWhitelistInstruction(
@@ -198,11 +202,9 @@ describe("staking-program", () => {
         ),
         authority: farmAuthority.publicKey,
         farm,
-        rewardRate: {
-          tokenAmount: 0,
-          intervalInSeconds: 1,
-        },
+        rewardRates: [{ tokenAmount: 0, intervalInSeconds: 1 }],
         whitelistType: new WhitelistType.Creator(),
+        buffConfig: null,
       }
     );
 
@@ -227,9 +229,8 @@ describe("staking-program", () => {
       creatorAddress.toString()
     );
     expect(whitelistProofAccount.ty.kind).to.equal("Creator");
-    expect(whitelistProofAccount.rewardRate).to.equal(
-      whitelistRewardRatePerSecond
-    );
+    expect(whitelistProofAccount.rewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it.skip("should be able to whitelist a mint address", async () => {
@@ -242,8 +243,9 @@ describe("staking-program", () => {
       creatorOrMint: rewardMint,
       authority: farmAuthority.publicKey,
       farm,
-      rewardRate: { tokenAmount: 1, intervalInSeconds: 1 },
+      rewardRates: [{ tokenAmount: 1, intervalInSeconds: 1 }],
       whitelistType: new WhitelistType.Mint(),
+      buffConfig: null,
     });
 
     await send(connection, [ix], [farmAuthority]);
@@ -262,7 +264,7 @@ describe("staking-program", () => {
     expect(whitelistProofAccount.whitelistedAddress.toString()).to.eql(
       rewardMint.toString()
     );
-    expect(whitelistProofAccount.rewardRate).to.equal(1);
+    expect(whitelistProofAccount.rewardRates[0].eq(PRECISION)).to.be.true;
   });
 
   it.skip("should be able to initialize a farmer", async () => {
@@ -283,13 +285,13 @@ describe("staking-program", () => {
       owner: userWallet.publicKey,
     });
 
-    const { totalRewardRate, accruedRewards, owner } = await Farmer.fetch(
+    const { totalRewardRates, accruedRewards, owner } = await Farmer.fetch(
       connection,
       farmer
     );
 
-    expect(totalRewardRate).to.equal(0);
-    expect(accruedRewards).to.equal(0);
+    expect(totalRewardRates[0].isZero()).to.be.true;
+    expect(accruedRewards[0].isZero()).to.be.true;
     expect(owner.toString()).to.eql(userWallet.publicKey.toString());
   });
 
@@ -300,31 +302,25 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
-
-    const tripEffect: TripEffect = "None";
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const { ix } = await stakingClient.createStakeInstruction({
       farm,
       mint: nft,
       lock: lock.address,
       owner: userWallet.publicKey,
-      args: { amount: new BN(1), tripEffect },
+      args: { amount: new BN(1), merkleProof: null },
     });
 
-    const txid = await send(connection, [ix], [userWallet]);
+    await send(connection, [ix], [userWallet]);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
 
     const farmerAccount = await Farmer.fetch(connection, farmer);
-    const { reward } = await Farm.fetch(connection, farm);
-
-    const expectedRewardRate =
-      whitelistRewardRatePerSecond * (1 + lock.bonusFactor / 100);
-    const expectedReservedReward =
-      expectedRewardRate * lock.duration.toNumber();
 
-    expect(farmerAccount.totalRewardRate).to.equal(expectedRewardRate);
+    // The lock has no bonus.
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it.skip("should be able to buff a pair", async () => {
@@ -334,17 +330,16 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
-    const farmerAccount = await Farmer.fetch(connection, farmer);
 
     const stakeNft = await stakingClient.createStakeInstruction({
       farm,
       owner: userWallet.publicKey,
       mint: otherNft,
       lock: lock.address,
-      args: { amount: new BN(1), tripEffect: "None" },
+      args: { amount: new BN(1), merkleProof: null },
     });
 
     const { ix } = await stakingClient.createBuffPairInstruction({
@@ -358,11 +353,11 @@ describe("staking-program", () => {
 
     await send(connection, [ix], [farmAuthority, userWallet]);
 
-    const farmerAccount2 = await Farmer.fetch(connection, farmer);
+    const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount2.totalRewardRate).to.equal(
-      whitelistRewardRatePerSecond * 4
-    );
+    // Both NFTs earn twice their rate.
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate.muln(4))).to
+      .be.true;
   });
 
   it.skip("should be able to debuff a pair", async () => {
@@ -389,9 +384,8 @@ describe("staking-program", () => {
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
     const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount.totalRewardRate).to.equal(
-      whitelistRewardRatePerSecond
-    );
+    expect(farmerAccount.totalRewardRates[0].eq(whitelistRewardRate)).to.be
+      .true;
   });
 
   it.skip("should be able to unstake an NFT", async () => {
@@ -415,10 +409,10 @@ describe("staking-program", () => {
 
     const stakeReceipt = findStakeReceiptAddress({ farmer, mint: nft });
 
-    const { totalRewardRate } = await Farmer.fetch(connection, farmer);
+    const { totalRewardRates } = await Farmer.fetch(connection, farmer);
     const { endTs } = await StakeReceipt.fetch(connection, stakeReceipt);
 
-    expect(totalRewardRate).to.equal(0);
+    expect(totalRewardRates[0].isZero()).to.be.true;
     expect(endTs.toNumber()).to.be.closeTo(Math.floor(Date.now() / 1000), 1);
   });
 
@@ -429,7 +423,7 @@ describe("staking-program", () => {
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
 
@@ -439,66 +433,42 @@ describe("staking-program", () => {
       mint: rewardMint,
       lock: lock.address,
       owner: userWallet.publicKey,
-      args: { amount: new BN(5e2), tripEffect: "None" },
+      args: { amount: new BN(5e2), merkleProof: null },
     });
 
     await send(connection, [ix], [userWallet]);
 
-    const { totalRewardRate } = await Farmer.fetch(connection, farmer);
-    const expectedRewardRate = 5e2 * Math.floor(1 + lock.bonusFactor / 100);
+    const { totalRewardRates } = await Farmer.fetch(connection, farmer);
 
-    expect(totalRewardRate).to.eql(expectedRewardRate);
+    // The lock has no bonus.
+    expect(totalRewardRates[0].eq(PRECISION.muln(5e2))).to.be.true;
   });
 
-  it.skip("should not be able to stake more while still staking", async () => {
+  it.skip("should be able to top up a fungible stake", async () => {
     const farm = findFarmAddress({
       authority: farmAuthority.publicKey,
       rewardMint,
     });
 
     const locks = await findFarmLocks(connection, farm);
-    const lock = locks.find((lock) => lock.bonusFactor === 0);
-
-    try {
-      const { ix } = await stakingClient.createStakeInstruction({
-        farm,
-        mint: rewardMint,
-        lock: lock.address,
-        owner: userWallet.publicKey,
-        args: { amount: new BN(5e2), tripEffect: "None" },
-      });
+    const lock = locks.find((lock) => lock.bonusBps === 0);
 
-      await send(connection, [ix], [userWallet]);
-      assert(false);
-    } catch (e) {
-      expect(e).to.be.instanceOf(GemStillStaked);
-    }
-  });
-
-  it.skip("should be able to remove and address from the whitelist", async () => {
-    const farm = findFarmAddress({
-      authority: farmAuthority.publicKey,
-      rewardMint,
-    });
+    const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
 
-    const { ix } = stakingClient.createRemoveFromWhitelistInstruction({
+    // Stake 0.5 more tokens under the same lock.
+    const { ix } = await stakingClient.createStakeInstruction({
       farm,
-      authority: farmAuthority.publicKey,
-      addressToRemove: rewardMint,
+      mint: rewardMint,
+      lock: lock.address,
+      owner: userWallet.publicKey,
+      args: { amount: new BN(5e2), merkleProof: null },
     });
 
-    await send(connection, [ix], [farmAuthority]);
+    await send(connection, [ix], [userWallet]);
 
-    const whitelistProof = findWhitelistProofAddress({
-      farm,
-      creatorOrMint: rewardMint,
-    });
-    const whitelistProofAccount = await WhitelistProof.fetch(
-      connection,
-      whitelistProof
-    );
+    const { totalRewardRates } = await Farmer.fetch(connection, farmer);
 
-    expect(whitelistProofAccount).to.be.null;
+    expect(totalRewardRates[0].eq(PRECISION.muln(10e2))).to.be.true;
   });
 
   it.skip("should be able to unstake a fungible token", async () => {
@@ -524,10 +494,36 @@ describe("staking-program", () => {
     const farmerAccount = await Farmer.fetch(connection, farmer);
     const { endTs } = await StakeReceipt.fetch(connection, stakeReceipt);
 
-    expect(farmerAccount.totalRewardRate).to.equal(0);
+    expect(farmerAccount.totalRewardRates[0].isZero()).to.be.true;
     expect(endTs.toNumber()).to.be.closeTo(Math.floor(Date.now() / 1000), 1);
   });
 
+  it.skip("should be able to remove and address from the whitelist", async () => {
+    const farm = findFarmAddress({
+      authority: farmAuthority.publicKey,
+      rewardMint,
+    });
+
+    const { ix } = stakingClient.createRemoveFromWhitelistInstruction({
+      farm,
+      authority: farmAuthority.publicKey,
+      addressToRemove: rewardMint,
+    });
+
+    await send(connection, [ix], [farmAuthority]);
+
+    const whitelistProof = findWhitelistProofAddress({
+      farm,
+      creatorOrMint: rewardMint,
+    });
+    const whitelistProofAccount = await WhitelistProof.fetch(
+      connection,
+      whitelistProof
+    );
+
+    expect(whitelistProofAccount).to.be.null;
+  });
+
   it.skip("should be able to claim rewards", async () => {
     const farm = findFarmAddress({
       authority: farmAuthority.publicKey,
@@ -539,11 +535,11 @@ describe("staking-program", () => {
       authority: userWallet.publicKey,
     });
 
-    const txid = await send(connection, [ix], [userWallet]);
+    await send(connection, [ix], [userWallet]);
 
     const farmer = findFarmerAddress({ farm, owner: userWallet.publicKey });
     const farmerAccount = await Farmer.fetch(connection, farmer);
 
-    expect(farmerAccount.accruedRewards).to.equal(0);
+    expect(farmerAccount.accruedRewards[0].isZero()).to.be.true;
   });
 });
@@ -0,0 +1,311 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenStandard } from "@metaplex-foundation/mpl-token-metadata";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import {
+  airdrop,
+  computeBudget,
+  pnftAccounts,
+  PRECISION,
+  TestFarm,
+} from "./fixtures";
+
+// Uses the Token Metadata program cloned from devnet, see Anchor.toml.
+describe("pnft", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+  // Verified creator of the buff pNFTs, whitelisted as buffs.
+  const buffCreator = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let lock: PublicKey;
+  let whitelistProof: PublicKey;
+  let buffWhitelist: PublicKey;
+  let gemMint: PublicKey;
+  let userAta: PublicKey;
+  let farmerVault: PublicKey;
+  let buffMint: PublicKey;
+  let buffUserAta: PublicKey;
+  let buffVault: PublicKey;
+
+  const stakeReceipt = () => testFarm.stakeReceipt(farmer, gemMint);
+
+  const buffRecord = () =>
+    testFarm.findPda([
+      Buffer.from("buff_record"),
+      farmer.toBuffer(),
+      buffMint.toBuffer(),
+    ]);
+
+  before(async () => {
+    await airdrop(connection, user, buffCreator);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e6);
+
+    ({ gemMint, ownerAta: userAta } = await testFarm.createNft(
+      user,
+      TokenStandard.ProgrammableNonFungible
+    ));
+    ({ gemMint: buffMint, ownerAta: buffUserAta } = await testFarm.createNft(
+      user,
+      TokenStandard.ProgrammableNonFungible,
+      buffCreator
+    ));
+
+    whitelistProof = await testFarm.whitelist(
+      testFarm.authority.publicKey,
+      PRECISION,
+      { creator: {} }
+    );
+    // Doubles the rates of a single stake until it's debuffed.
+    buffWhitelist = await testFarm.whitelist(
+      buffCreator.publicKey,
+      PRECISION.muln(2),
+      { buff: {} },
+      { arity: 1, duration: null, consumable: false, category: 0 }
+    );
+    lock = await testFarm.createLock({
+      duration: new BN(0),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+    farmerVault = await getAssociatedTokenAddress(gemMint, farmer, true);
+    buffVault = await getAssociatedTokenAddress(buffMint, farmer, true);
+  });
+
+  it("should stake a pNFT through Token Metadata", async () => {
+    await program.methods
+      .stake(new BN(1), null)
+      .accounts(
+        await testFarm.stakeAccounts(user, gemMint, whitelistProof, lock)
+      )
+      .remainingAccounts(pnftAccounts(gemMint, userAta, farmerVault))
+      .preInstructions([computeBudget])
+      .signers([user])
+      .rpc();
+
+    const vault = await getAccount(connection, farmerVault);
+    const wallet = await getAccount(connection, userAta);
+
+    expect(Number(vault.amount)).to.equal(1);
+    expect(Number(wallet.amount)).to.equal(0);
+    // Token Metadata keeps pNFT token accounts frozen wherever they are.
+    expect(vault.isFrozen).to.be.true;
+  });
+
+  it("should buff with a pNFT through Token Metadata", async () => {
+    await program.methods
+      .buff(1)
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        buffMint,
+        buffWhitelist,
+        buffUserAta,
+        buffVault,
+        buffRecord: buffRecord(),
+        authority: user.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: stakeReceipt(), isSigner: false, isWritable: true },
+        ...pnftAccounts(buffMint, buffUserAta, buffVault),
+      ])
+      .preInstructions([computeBudget])
+      .signers([user])
+      .rpc();
+
+    const vault = await getAccount(connection, buffVault);
+    const wallet = await getAccount(connection, buffUserAta);
+    const receipt = await program.account.stakeReceipt.fetch(stakeReceipt());
+
+    expect(Number(vault.amount)).to.equal(1);
+    expect(Number(wallet.amount)).to.equal(0);
+    expect(vault.isFrozen).to.be.true;
+    expect(receipt.rewardRates[0].eq(PRECISION.muln(2))).to.be.true;
+  });
+
+  it("should debuff a pNFT through Token Metadata", async () => {
+    await program.methods
+      .debuff()
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        buffMint,
+        buffUserAta,
+        buffVault,
+        buffRecord: buffRecord(),
+        authority: user.publicKey,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: stakeReceipt(), isSigner: false, isWritable: true },
+        ...pnftAccounts(buffMint, buffVault, buffUserAta),
+      ])
+      .preInstructions([computeBudget])
+      .signers([user])
+      .rpc();
+
+    const vault = await getAccount(connection, buffVault);
+    const wallet = await getAccount(connection, buffUserAta);
+    const receipt = await program.account.stakeReceipt.fetch(stakeReceipt());
+
+    expect(Number(vault.amount)).to.equal(0);
+    expect(Number(wallet.amount)).to.equal(1);
+    expect(receipt.rewardRates[0].eq(PRECISION)).to.be.true;
+    expect(await connection.getAccountInfo(buffRecord())).to.be.null;
+  });
+
+  it("should send a pNFT buff back when forcing a debuff", async () => {
+    await program.methods
+      .buff(1)
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        buffMint,
+        buffWhitelist,
+        buffUserAta,
+        buffVault,
+        buffRecord: buffRecord(),
+        authority: user.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: stakeReceipt(), isSigner: false, isWritable: true },
+        ...pnftAccounts(buffMint, buffUserAta, buffVault),
+      ])
+      .preInstructions([computeBudget])
+      .signers([user])
+      .rpc();
+
+    await program.methods
+      .forceDebuff()
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        buffMint,
+        buffUserAta,
+        buffVault,
+        buffRecord: buffRecord(),
+        authority: user.publicKey,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: stakeReceipt(), isSigner: false, isWritable: true },
+        ...pnftAccounts(buffMint, buffVault, buffUserAta),
+      ])
+      .preInstructions([computeBudget])
+      .signers([user])
+      .rpc();
+
+    const vault = await getAccount(connection, buffVault);
+    const wallet = await getAccount(connection, buffUserAta);
+    const receipt = await program.account.stakeReceipt.fetch(stakeReceipt());
+
+    expect(Number(vault.amount)).to.equal(0);
+    expect(Number(wallet.amount)).to.equal(1);
+    expect(receipt.rewardRates[0].eq(PRECISION)).to.be.true;
+    expect(await connection.getAccountInfo(buffRecord())).to.be.null;
+  });
+
+  it("should refuse to unstake a pNFT in a batch", async () => {
+    try {
+      await testFarm.sendUnstake(
+        await program.methods
+          .unstakeMany()
+          .accounts({
+            farm: testFarm.farm,
+            farmer,
+            lock,
+            whitelistProof,
+            owner: user.publicKey,
+            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(
+            [gemMint, userAta, farmerVault, stakeReceipt()].map(
+              (pubkey) => ({
+                pubkey,
+                isSigner: false,
+                isWritable: !pubkey.equals(gemMint),
+              })
+            )
+          )
+          .instruction(),
+        whitelistProof,
+        user
+      );
+
+      expect.fail("A pNFT was unstaked without Token Metadata.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("UnsupportedPnft");
+    }
+  });
+
+  it("should unstake a pNFT through Token Metadata", async () => {
+    await testFarm.sendUnstake(
+      await program.methods
+        .unstake()
+        .accounts(
+          await testFarm.unstakeAccounts(user, gemMint, whitelistProof, lock)
+        )
+        .remainingAccounts(pnftAccounts(gemMint, farmerVault, userAta))
+        .instruction(),
+      whitelistProof,
+      user,
+      computeBudget
+    );
+
+    const vault = await getAccount(connection, farmerVault);
+    const wallet = await getAccount(connection, userAta);
+
+    expect(Number(vault.amount)).to.equal(0);
+    expect(Number(wallet.amount)).to.equal(1);
+
+    const { activeStakes } = await program.account.whitelistProof.fetch(
+      whitelistProof
+    );
+
+    expect(activeStakes.toNumber()).to.equal(0);
+  });
+
+  it("should require every Token Metadata account for a pNFT", async () => {
+    try {
+      await program.methods
+        .stake(new BN(1), null)
+        .accounts(
+          await testFarm.stakeAccounts(user, gemMint, whitelistProof, lock)
+        )
+        .remainingAccounts(
+          pnftAccounts(gemMint, userAta, farmerVault).slice(0, 1)
+        )
+        .signers([user])
+        .rpc();
+
+      expect.fail("A pNFT was staked without its token records.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidPnftAccounts");
+    }
+  });
+});
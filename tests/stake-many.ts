@@ -0,0 +1,201 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenStandard } from "@metaplex-foundation/mpl-token-metadata";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { AccountMeta, Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, TestFarm } from "./fixtures";
+
+type Gem = { gemMint: PublicKey; ownerAta: PublicKey; metadata: PublicKey };
+
+// Uses the Token Metadata program cloned from devnet, see Anchor.toml.
+describe("stake-many", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let lock: PublicKey;
+  let creatorProof: PublicKey;
+  let gems: Gem[];
+
+  const meta = (pubkey: PublicKey, isWritable = true): AccountMeta => ({
+    pubkey,
+    isSigner: false,
+    isWritable,
+  });
+
+  // (gem_mint, gem_owner_ata, farmer_vault, stake_receipt, metadata, gem_level) groups.
+  const stakeGroups = async (groups: Gem[]) => {
+    const accounts: AccountMeta[] = [];
+
+    for (const { gemMint, ownerAta, metadata } of groups) {
+      accounts.push(
+        meta(gemMint, false),
+        meta(ownerAta),
+        meta(await getAssociatedTokenAddress(gemMint, farmer, true)),
+        meta(testFarm.stakeReceipt(farmer, gemMint)),
+        meta(metadata, false),
+        meta(
+          testFarm.findPda([
+            Buffer.from("gem_level"),
+            testFarm.farm.toBuffer(),
+            gemMint.toBuffer(),
+          ]),
+          false
+        )
+      );
+    }
+
+    return accounts;
+  };
+
+  const stakeMany = async (whitelistProof: PublicKey, groups: Gem[]) =>
+    program.methods
+      .stakeMany()
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        whitelistProof,
+        lock,
+        owner: user.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(await stakeGroups(groups))
+      .signers([user])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, user);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e7);
+
+    creatorProof = await testFarm.whitelist(
+      testFarm.authority.publicKey,
+      PRECISION,
+      { creator: {} }
+    );
+    lock = await testFarm.createLock({
+      duration: new BN(0),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+
+    gems = [await testFarm.createNft(user), await testFarm.createNft(user)];
+    farmer = await testFarm.createFarmer(user);
+  });
+
+  it("should refuse to batch fungible tokens", async () => {
+    const { gemMint, ownerAta } = await testFarm.createGem(user, 10);
+    const mintProof = await testFarm.whitelist(gemMint, PRECISION);
+
+    try {
+      // Mint whitelists don't need metadata, so any account does.
+      await stakeMany(mintProof, [{ gemMint, ownerAta, metadata: gemMint }]);
+
+      expect.fail("Fungible tokens were staked one unit at a time.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidWhitelistType");
+    }
+  });
+
+  it("should refuse to batch pNFTs", async () => {
+    const pnft = await testFarm.createNft(
+      user,
+      TokenStandard.ProgrammableNonFungible
+    );
+
+    try {
+      await stakeMany(creatorProof, [pnft]);
+
+      expect.fail("A pNFT was staked without Token Metadata.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("UnsupportedPnft");
+    }
+  });
+
+  it("should stake a batch of NFTs", async () => {
+    await stakeMany(creatorProof, gems);
+
+    const account = await program.account.farmer.fetch(farmer);
+    const { activeStakes } = await program.account.whitelistProof.fetch(
+      creatorProof
+    );
+
+    expect(activeStakes.toNumber()).to.equal(2);
+    expect(account.totalRewardRates[0].eq(PRECISION.muln(2))).to.be.true;
+
+    for (const { gemMint, ownerAta } of gems) {
+      const receipt = await program.account.stakeReceipt.fetch(
+        testFarm.stakeReceipt(farmer, gemMint)
+      );
+      const wallet = await getAccount(provider.connection, ownerAta);
+
+      expect(receipt.amount.toNumber()).to.equal(1);
+      expect(receipt.whitelist.equals(creatorProof)).to.be.true;
+      expect(Number(wallet.amount)).to.equal(0);
+    }
+  });
+
+  it("should unstake a batch of NFTs", async () => {
+    const accounts: AccountMeta[] = [];
+
+    // (gem_mint, gem_owner_ata, farmer_vault, stake_receipt) groups.
+    for (const { gemMint, ownerAta } of gems) {
+      accounts.push(
+        meta(gemMint, false),
+        meta(ownerAta),
+        meta(await getAssociatedTokenAddress(gemMint, farmer, true)),
+        meta(testFarm.stakeReceipt(farmer, gemMint))
+      );
+    }
+
+    await testFarm.sendUnstake(
+      await program.methods
+        .unstakeMany()
+        .accounts({
+          farm: testFarm.farm,
+          farmer,
+          lock,
+          whitelistProof: creatorProof,
+          owner: user.publicKey,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(accounts)
+        .instruction(),
+      creatorProof,
+      user
+    );
+
+    const account = await program.account.farmer.fetch(farmer);
+    const { activeStakes } = await program.account.whitelistProof.fetch(
+      creatorProof
+    );
+
+    expect(activeStakes.toNumber()).to.equal(0);
+    expect(account.totalRewardRates[0].isZero()).to.be.true;
+
+    for (const { gemMint, ownerAta } of gems) {
+      const receipt = await program.account.stakeReceipt.fetch(
+        testFarm.stakeReceipt(farmer, gemMint)
+      );
+      const wallet = await getAccount(provider.connection, ownerAta);
+
+      expect(receipt.endTs).not.to.be.null;
+      expect(Number(wallet.amount)).to.equal(1);
+    }
+  });
+});
@@ -0,0 +1,238 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+
+import { MagicshardsStaking } from "../target/types/magicshards_staking";
+import { airdrop, PRECISION, sleep, TestFarm } from "./fixtures";
+
+describe("whitelists", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .MagicshardsStaking as Program<MagicshardsStaking>;
+
+  const user = Keypair.generate();
+
+  let testFarm: TestFarm;
+  let farmer: PublicKey;
+  let lock: PublicKey;
+  let gemMint: PublicKey;
+  let whitelistProof: PublicKey;
+
+  const updateWhitelist = (rates: BN[]) =>
+    program.methods
+      .updateWhitelist(rates)
+      .accounts({
+        farm: testFarm.farm,
+        farmManager: testFarm.farmManager,
+        whitelistProof,
+        authority: testFarm.authority.publicKey,
+      })
+      .signers([testFarm.authority])
+      .rpc();
+
+  const refreshReceipt = () =>
+    program.methods
+      .refreshReceipt(null)
+      .accounts({
+        farm: testFarm.farm,
+        farmer,
+        stakeReceipt: testFarm.stakeReceipt(farmer, gemMint),
+        gemMint,
+        whitelistProof,
+        gemLevel: testFarm.findPda([
+          Buffer.from("gem_level"),
+          testFarm.farm.toBuffer(),
+          gemMint.toBuffer(),
+        ]),
+        lock,
+      })
+      .rpc();
+
+  const unstake = async () =>
+    testFarm.sendUnstake(
+      await program.methods
+        .unstake()
+        .accounts(
+          await testFarm.unstakeAccounts(user, gemMint, whitelistProof, lock)
+        )
+        .instruction(),
+      whitelistProof,
+      user
+    );
+
+  before(async () => {
+    await airdrop(provider.connection, user);
+
+    testFarm = await TestFarm.create(program);
+    await testFarm.fund(1e7);
+
+    ({ gemMint } = await testFarm.createGem(user, 10));
+    whitelistProof = await testFarm.whitelist(gemMint, PRECISION);
+    lock = await testFarm.createLock({
+      duration: new BN(0),
+      bonusBps: 0,
+      cooldown: new BN(0),
+      earlyUnstake: { disabled: {} },
+    });
+
+    farmer = await testFarm.createFarmer(user);
+
+    await program.methods
+      .stake(new BN(2), null)
+      .accounts(
+        await testFarm.stakeAccounts(user, gemMint, whitelistProof, lock)
+      )
+      .signers([user])
+      .rpc();
+  });
+
+  it("should refuse rates for rewards the farm doesn't have", async () => {
+    try {
+      await updateWhitelist([PRECISION, PRECISION, new BN(0)]);
+
+      expect.fail("A whitelist was given a rate for a missing reward.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidRewardRates");
+    }
+
+    const { gemMint: otherMint } = await testFarm.createGem(user, 1);
+
+    try {
+      await program.methods
+        .addToWhitelist([PRECISION, new BN(0), PRECISION], { mint: {} }, null)
+        .accounts({
+          farm: testFarm.farm,
+          farmManager: testFarm.farmManager,
+          whitelistProof: testFarm.whitelistProof(otherMint),
+          creatorOrMint: otherMint,
+          authority: testFarm.authority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([testFarm.authority])
+        .rpc();
+
+      expect.fail("A whitelist was added with a rate for a missing reward.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("InvalidRewardRates");
+    }
+  });
+
+  it("should keep running stakes at their rates until refreshed", async () => {
+    await updateWhitelist([PRECISION.muln(3), new BN(0), new BN(0)]);
+
+    const receipt = await program.account.stakeReceipt.fetch(
+      testFarm.stakeReceipt(farmer, gemMint)
+    );
+
+    expect(receipt.rewardRates[0].eq(PRECISION.muln(2))).to.be.true;
+  });
+
+  it("should reprice a running stake to the whitelist's rates", async () => {
+    await refreshReceipt();
+
+    const receipt = await program.account.stakeReceipt.fetch(
+      testFarm.stakeReceipt(farmer, gemMint)
+    );
+    const account = await program.account.farmer.fetch(farmer);
+
+    // Two tokens at the new rate.
+    expect(receipt.rewardRates[0].eq(PRECISION.muln(6))).to.be.true;
+    expect(account.totalRewardRates[0].eq(PRECISION.muln(6))).to.be.true;
+  });
+
+  it("should refuse to remove a whitelist with active stakes", async () => {
+    const { activeStakes } = await program.account.whitelistProof.fetch(
+      whitelistProof
+    );
+
+    expect(activeStakes.toNumber()).to.equal(1);
+
+    try {
+      await program.methods
+        .removeFromWhitelist(false)
+        .accounts({
+          farm: testFarm.farm,
+          farmManager: testFarm.farmManager,
+          whitelistProof,
+          authority: testFarm.authority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([testFarm.authority])
+        .rpc();
+
+      expect.fail("A whitelist with active stakes was removed.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("WhitelistHasStakes");
+    }
+  });
+
+  it("should hold claims and unstakes of delisted stakes", async () => {
+    await program.methods
+      .delistWhitelist(new BN(Math.floor(Date.now() / 1000) + 2))
+      .accounts({
+        farm: testFarm.farm,
+        farmManager: testFarm.farmManager,
+        whitelistProof,
+        authority: testFarm.authority.publicKey,
+      })
+      .signers([testFarm.authority])
+      .rpc();
+
+    await sleep(4000);
+
+    try {
+      await testFarm.claim(user);
+
+      expect.fail("Rewards earned after the delisting were claimed.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("DelistedStakes");
+    }
+
+    try {
+      await unstake();
+
+      expect.fail("A stake left with what it earned after the delisting.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("DelistedStakes");
+    }
+
+    try {
+      await program.methods
+        .removeFromWhitelist(true)
+        .accounts({
+          farm: testFarm.farm,
+          farmManager: testFarm.farmManager,
+          whitelistProof,
+          authority: testFarm.authority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([testFarm.authority])
+        .rpc();
+
+      expect.fail("A delisted whitelist was removed before its stakes.");
+    } catch (e) {
+      expect(e.error.errorCode.code).to.equal("DelistedStakes");
+    }
+  });
+
+  it("should stop a delisted stake from earning once refreshed", async () => {
+    await refreshReceipt();
+
+    const receipt = await program.account.stakeReceipt.fetch(
+      testFarm.stakeReceipt(farmer, gemMint)
+    );
+    const account = await program.account.farmer.fetch(farmer);
+
+    expect(receipt.delisted).to.be.true;
+    expect(receipt.rewardRates[0].isZero()).to.be.true;
+    expect(account.totalRewardRates[0].isZero()).to.be.true;
+    expect(account.whitelists).to.be.empty;
+
+    await testFarm.claim(user);
+    await unstake();
+  });
+});